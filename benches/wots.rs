@@ -0,0 +1,89 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use w_ots::digits;
+use w_ots::fixtures::{Fixture, FIXTURES, FIXTURE_MSG as MSG};
+use w_ots::hasher::{Blake2bHasher, Hasher, Sha3_224Hasher, Sha3_256Hasher};
+use w_ots::keys::Key;
+use w_ots::params::Params;
+use w_ots::security::{self, ParamsEncoding};
+
+fn bench_encoding<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    c: &mut Criterion,
+    fixture: &Fixture,
+    params: Params<PRFH, MSGH>,
+) {
+    let name = fixture.encoding.to_string();
+    let key = fixture.key(params.clone()).unwrap();
+    let mut generated = key.clone();
+    generated.generate().unwrap();
+    let signature = key.sign(MSG).unwrap();
+
+    c.bench_with_input(BenchmarkId::new("keygen", &name), &params, |b, params| {
+        b.iter(|| {
+            Key::from_seed(
+                params.clone(),
+                black_box(fixture.seed),
+                black_box(fixture.p_seed),
+            )
+            .unwrap()
+        })
+    });
+
+    c.bench_with_input(BenchmarkId::new("generate", &name), &key, |b, key| {
+        b.iter(|| {
+            let mut key = key.clone();
+            key.generate().unwrap();
+            key
+        })
+    });
+
+    c.bench_with_input(BenchmarkId::new("sign", &name), &key, |b, key| {
+        b.iter(|| key.sign(black_box(MSG)).unwrap())
+    });
+
+    c.bench_with_input(
+        BenchmarkId::new("fast_sign", &name),
+        &generated,
+        |b, key| b.iter(|| key.sign(black_box(MSG)).unwrap()),
+    );
+
+    c.bench_with_input(BenchmarkId::new("verify", &name), &signature, |b, sig| {
+        b.iter(|| security::verify(black_box(MSG), sig, &key.public_key).unwrap())
+    });
+}
+
+fn bench_digits(c: &mut Criterion) {
+    let digest = [0xa5u8; 32];
+    for w in [16usize, 256, 1 << 16] {
+        c.bench_with_input(BenchmarkId::new("base_w", w), &w, |b, &w| {
+            b.iter(|| digits::base_w(black_box(&digest), w))
+        });
+        let msg_digits = digits::base_w(&digest, w);
+        c.bench_with_input(
+            BenchmarkId::new("checksum", w),
+            &msg_digits,
+            |b, msg_digits| b.iter(|| digits::wide_checksum(black_box(msg_digits), w)),
+        );
+    }
+}
+
+fn benchmarks(c: &mut Criterion) {
+    bench_digits(c);
+    for fixture in &FIXTURES {
+        match fixture.encoding {
+            ParamsEncoding::Consensus => bench_encoding(
+                c,
+                fixture,
+                security::consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+            ),
+            encoding => bench_encoding(
+                c,
+                fixture,
+                Params::<Blake2bHasher, Sha3_224Hasher>::new(encoding).unwrap(),
+            ),
+        }
+    }
+}
+
+criterion_group!(benches, benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,15 @@
+use std::net::SocketAddr;
+
+use w_ots::security::VerifyPolicy;
+use w_ots::server::{self, VerificationService};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let addr: SocketAddr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:50051".to_string())
+        .parse()?;
+    println!("serving wots.v1.Verification on {addr}");
+    server::serve(addr, VerificationService::new(VerifyPolicy::default())).await?;
+    Ok(())
+}
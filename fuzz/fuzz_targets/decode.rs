@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use w_ots::hasher::{Blake2bHasher, Sha3_224Hasher, Sha3_256Hasher};
+use w_ots::security;
+
+// Input layout: message length (1 byte) || message || signature without the encoding byte.
+fuzz_target!(|data: &[u8]| {
+    if data.is_empty() {
+        return;
+    }
+    let msg_len = (data[0] as usize).min(data.len() - 1);
+    let (msg, signature) = data[1..].split_at(msg_len);
+
+    let _ = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>().decode(msg, signature);
+    let _ = security::level_3_params::<Blake2bHasher, Sha3_224Hasher>().decode(msg, signature);
+    let _ = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>().decode(msg, signature);
+});
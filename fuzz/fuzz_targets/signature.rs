@@ -0,0 +1,19 @@
+#![no_main]
+
+use std::convert::TryFrom;
+
+use libfuzzer_sys::fuzz_target;
+use w_ots::hasher::{Blake2bHasher, Sha3_224Hasher, Sha3_256Hasher};
+use w_ots::security;
+use w_ots::signature::Signature;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(signature) = Signature::try_from(data) {
+        assert_eq!(signature.as_bytes(), data);
+        let _ = signature.encoding();
+        let _ = signature.p_seed();
+        let _ = signature.points();
+        let _ = signature.validate(&security::level_0_params::<Blake2bHasher, Sha3_224Hasher>());
+        let _ = signature.validate(&security::consensus_params::<Blake2bHasher, Sha3_256Hasher>());
+    }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use w_ots::keys::PK_SIZE;
+use w_ots::security;
+
+// Input layout: public key (PK_SIZE bytes) || message length (1 byte) || message || signature.
+fuzz_target!(|data: &[u8]| {
+    if data.len() < PK_SIZE + 1 {
+        return;
+    }
+    let (public_key, rest) = data.split_at(PK_SIZE);
+    let msg_len = (rest[0] as usize).min(rest.len() - 1);
+    let (msg, signature) = rest[1..].split_at(msg_len);
+
+    let _ = security::verify(msg, signature, public_key);
+    let _ = security::verify_no_consensus(msg, signature, public_key);
+
+    // arbitrary public key sizes must be rejected, not panic
+    let _ = security::verify(msg, signature, &data[..data.len() / 2]);
+});
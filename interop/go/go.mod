@@ -0,0 +1,3 @@
+module github.com/ChainSafe/W-OTS/interop/go
+
+go 1.17
@@ -0,0 +1,79 @@
+// Command interop signs and decodes W-OTS+ signatures with the xx-labs Go implementation, for
+// the Rust `interop-tests`. It reads one hex-encoded command per line from stdin and writes
+// one result line to stdout:
+//
+//	sign <encoding byte> <seed> <p_seed> <msg>  ->  <public key> <signature>
+//	decode <msg> <signature>               ->  <public key>
+//
+// Errors are reported as `error <description>`.
+package main
+
+import (
+	"bufio"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"os"
+	"strings"
+
+	"github.com/xx-labs/sleeve/wots"
+)
+
+func main() {
+	in := bufio.NewScanner(os.Stdin)
+	in.Buffer(make([]byte, 1<<20), 1<<20)
+	out := bufio.NewWriter(os.Stdout)
+	defer out.Flush()
+
+	for in.Scan() {
+		res, err := run(strings.Fields(in.Text()))
+		if err != nil {
+			res = "error " + err.Error()
+		}
+		fmt.Fprintln(out, res)
+		out.Flush()
+	}
+}
+
+func run(args []string) (string, error) {
+	if len(args) == 0 {
+		return "", errors.New("empty command")
+	}
+	bytes := make([][]byte, 0, len(args))
+	for _, arg := range args[1:] {
+		b, err := hex.DecodeString(arg)
+		if err != nil {
+			return "", err
+		}
+		bytes = append(bytes, b)
+	}
+
+	switch {
+	case args[0] == "sign" && len(bytes) == 4:
+		if len(bytes[0]) != 1 {
+			return "", errors.New("encoding must be a single byte")
+		}
+		params := wots.DecodeParams(wots.ParamsEncoding(bytes[0][0]))
+		if params == nil {
+			return "", errors.New("unknown encoding")
+		}
+		key := wots.NewKeyFromSeed(params, bytes[1], bytes[2])
+		sig, err := key.Sign(bytes[3])
+		if err != nil {
+			return "", err
+		}
+		return hex.EncodeToString(key.ComputePK()) + " " + hex.EncodeToString(sig), nil
+	case args[0] == "decode" && len(bytes) == 2:
+		sig := bytes[1]
+		if len(sig) == 0 {
+			return "", errors.New("empty signature")
+		}
+		params := wots.DecodeParams(wots.ParamsEncoding(sig[0]))
+		if params == nil {
+			return "", errors.New("unknown encoding")
+		}
+		return hex.EncodeToString(params.Decode(bytes[0], sig[1:])), nil
+	default:
+		return "", fmt.Errorf("bad command %q", args[0])
+	}
+}
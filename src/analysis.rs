@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use crate::hasher::Hasher;
+use crate::keys::PublicKey;
+use crate::params::{Params, WotsError, MAX_MSG_SIZE, SEED_SIZE};
+use crate::signature::validate_bytes;
+
+/// What a signature on a given message gives away, ladder by ladder.
+///
+/// The point on ladder `i` sits `digits[i]` steps above the secret start, so anyone holding the
+/// signature can compute levels `digits[i]..w` of that ladder. A forger needs a message whose
+/// digits are all at least the signed ones; the lower a digit, the less that ladder constrains
+/// forgeries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exposure {
+    /// message digest digits followed by the checksum digits
+    pub digits: Vec<u16>,
+    /// number of chain levels computable from the signature, per ladder
+    pub revealed: Vec<usize>,
+    /// `histogram[d]` is the number of ladders signed at digit `d`
+    pub histogram: Vec<usize>,
+}
+
+impl Exposure {
+    /// Index and digit of the ladder revealing the most levels.
+    pub fn weakest_ladder(&self) -> (usize, u16) {
+        self.digits
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by_key(|&(_, d)| d)
+            .expect("there is always at least one ladder")
+    }
+
+    /// Chain levels revealed over all ladders.
+    pub fn total_revealed(&self) -> usize {
+        self.revealed.iter().sum()
+    }
+}
+
+/// Reports which chain positions a signature on `msg` under `params` reveals.
+pub fn signature_exposure<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    msg: &[u8],
+    params: &Params<PRFH, MSGH>,
+) -> Result<Exposure, WotsError> {
+    if msg.len() > MAX_MSG_SIZE {
+        return Err(WotsError::InvalidMessageSize);
+    }
+
+    let digits = params.msg_hash_and_compute_checksum(msg);
+    let revealed = digits.iter().map(|&d| params.w - d as usize).collect();
+    let mut histogram = vec![0; params.w];
+    for &d in &digits {
+        histogram[d as usize] += 1;
+    }
+    Ok(Exposure {
+        digits,
+        revealed,
+        histogram,
+    })
+}
+
+/// Proof that a one-time key signed more than one message: the messages, and the lowest
+/// level of every ladder the signatures revealed between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReuseEvidence {
+    pub public_key: PublicKey,
+    /// every distinct message seen signed, in the order observed
+    pub msgs: Vec<Vec<u8>>,
+    /// per ladder, the lowest revealed level and the value at it; level 0 is the secret start
+    pub lowest: Vec<(u16, Vec<u8>)>,
+    /// Winternitz parameter of the key's params
+    pub w: usize,
+}
+
+impl ReuseEvidence {
+    /// Ladders whose secret start is recovered.
+    pub fn recovered_ladders(&self) -> Vec<usize> {
+        self.lowest
+            .iter()
+            .enumerate()
+            .filter(|(_, (level, _))| *level == 0)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Chain levels computable from the signatures together, over all ladders.
+    pub fn total_revealed(&self) -> usize {
+        self.lowest
+            .iter()
+            .map(|(level, _)| self.w - *level as usize)
+            .sum()
+    }
+}
+
+/// Flags one-time public keys seen signing two different messages, for network monitors
+/// policing OTS misuse. Only signatures that verify are taken into account, so evidence can't
+/// be fabricated by feeding in forgeries.
+pub struct ReuseDetector<PRFH: Hasher + Clone, MSGH: Hasher + Clone> {
+    params: Params<PRFH, MSGH>,
+    seen: HashMap<PublicKey, ReuseEvidence>,
+}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> ReuseDetector<PRFH, MSGH> {
+    pub fn new(params: Params<PRFH, MSGH>) -> Self {
+        ReuseDetector {
+            params,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Records a full `signature` (encoding byte included) of `msg` under `public_key`.
+    /// Returns the evidence gathered so far once the key has signed a second message, and on
+    /// every observation after that.
+    pub fn ingest(
+        &mut self,
+        public_key: &PublicKey,
+        msg: &[u8],
+        signature: &[u8],
+    ) -> Result<Option<ReuseEvidence>, WotsError> {
+        validate_bytes(signature, &self.params)?;
+        self.params
+            .verify(msg, &signature[1..], public_key.as_bytes())?;
+
+        let n = self.params.n;
+        let digits = self.params.msg_hash_and_compute_checksum(msg);
+        let points = signature[1 + SEED_SIZE..].chunks(n);
+        let entry = self
+            .seen
+            .entry(*public_key)
+            .or_insert_with(|| ReuseEvidence {
+                public_key: *public_key,
+                msgs: Vec::new(),
+                lowest: vec![(u16::MAX, Vec::new()); digits.len()],
+                w: self.params.w,
+            });
+        if !entry.msgs.iter().any(|seen| seen == msg) {
+            entry.msgs.push(msg.to_vec());
+        }
+        for ((lowest, &digit), point) in entry.lowest.iter_mut().zip(&digits).zip(points) {
+            if digit < lowest.0 || lowest.1.is_empty() {
+                *lowest = (digit, point.to_vec());
+            }
+        }
+
+        Ok((entry.msgs.len() > 1).then(|| entry.clone()))
+    }
+
+    /// Keys flagged so far.
+    pub fn flagged(&self) -> impl Iterator<Item = &ReuseEvidence> {
+        self.seen
+            .values()
+            .filter(|evidence| evidence.msgs.len() > 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{signature_exposure, ReuseDetector};
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{chain_walk, WotsError, MAX_MSG_SIZE, SEED_SIZE, W};
+    use crate::security;
+
+    #[test]
+    fn exposure() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let exposure = signature_exposure(b"XX NETWORK", &params).unwrap();
+
+        assert_eq!(
+            exposure.digits,
+            params.msg_hash_and_compute_checksum(b"XX NETWORK")
+        );
+        assert_eq!(exposure.revealed.len(), params.total);
+        assert_eq!(exposure.histogram.iter().sum::<usize>(), params.total);
+        let digit_sum: usize = exposure.digits.iter().map(|&d| d as usize).sum();
+        assert_eq!(exposure.total_revealed(), params.total * W - digit_sum);
+
+        let (ladder, digit) = exposure.weakest_ladder();
+        assert_eq!(exposure.digits[ladder], digit);
+        assert_eq!(
+            exposure.revealed[ladder],
+            *exposure.revealed.iter().max().unwrap()
+        );
+
+        assert_eq!(
+            signature_exposure(&[0u8; MAX_MSG_SIZE + 1], &params),
+            Err(WotsError::InvalidMessageSize)
+        );
+    }
+
+    #[test]
+    fn reuse_detector() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        // two instances of one key, since a single one refuses to sign twice
+        let instance =
+            || Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let key = instance();
+        let pk = key.verifying_key().unwrap();
+        let (a, b) = (
+            key.sign(b"first").unwrap(),
+            instance().sign(b"second").unwrap(),
+        );
+        let mut detector = ReuseDetector::new(params.clone());
+
+        assert_eq!(detector.ingest(&pk, b"first", &a), Ok(None));
+        assert_eq!(detector.ingest(&pk, b"first", &a), Ok(None));
+        let mut forged = b.clone();
+        forged[40] ^= 1;
+        assert_eq!(
+            detector.ingest(&pk, b"second", &forged),
+            Err(WotsError::InvalidSignature)
+        );
+
+        let evidence = detector.ingest(&pk, b"second", &b).unwrap().unwrap();
+        assert_eq!(evidence.msgs, vec![b"first".to_vec(), b"second".to_vec()]);
+        assert_eq!(detector.flagged().count(), 1);
+        let da = params.msg_hash_and_compute_checksum(b"first");
+        let db = params.msg_hash_and_compute_checksum(b"second");
+        for (i, (level, value)) in evidence.lowest.iter().enumerate() {
+            assert_eq!(*level, da[i].min(db[i]));
+            // the lowest level walks up to the point of either signature
+            let (sig, digit) = if da[i] >= db[i] {
+                (&a, da[i])
+            } else {
+                (&b, db[i])
+            };
+            let mut walked = value.clone();
+            let range = (*level as u8, digit as u8);
+            chain_walk::<Blake2bHasher>(&key.p_seed, &mut walked, range).unwrap();
+            let at = 1 + SEED_SIZE + i * params.n;
+            assert_eq!(walked, sig[at..at + params.n]);
+        }
+        assert!(evidence.total_revealed() <= params.total * W);
+    }
+}
@@ -0,0 +1,405 @@
+use crate::hasher::Hasher;
+use crate::params::{hash_buf, StepIndex, WotsError};
+use crate::randomness::PublicRandomness;
+
+/// Rows of ladder values recorded while generating; row `r` holds level `r * stride` of
+/// every ladder (`n * total` bytes).
+pub struct ChainRows<'a> {
+    pub rows: Vec<&'a mut [u8]>,
+    pub stride: usize,
+}
+
+/// Everything a `ChainBackend` needs to walk the ladders of one `compute_ladders` call.
+pub struct LadderJob<'a> {
+    /// security parameter; size of each ladder value (in bytes)
+    pub n: usize,
+
+    /// randomizers of the public seed; `randomness.element(j)` is xor'd into the value
+    /// before step `j`, and `randomness.p_seed()` is mixed into every step
+    pub randomness: &'a PublicRandomness,
+
+    /// `(begin, end)` levels to walk, one per ladder
+    pub ranges: &'a [(u16, u16)],
+}
+
+/// Walks W-OTS+ ladders.
+///
+/// Step `j` of a ladder maps `value` to the first `n` bytes of
+/// `H(p_seed || j + 1 || value ^ randomness.element(j))`, with `j + 1` hashed as two bytes
+/// when `randomness.w()` is above `W`. Implementations may offload this to
+/// other hardware, but must produce exactly the values `SoftwareBackend` does.
+pub trait ChainBackend<H: Hasher> {
+    /// Walks ladder `i` of `values` (`n` bytes per ladder) in place over `job.ranges[i]`,
+    /// recording the levels selected by `chains` when given. Fails if the walk can't finish,
+    /// e.g. with `WotsError::LadderWorkerPanicked` when a worker thread panics.
+    fn walk_ladders(
+        &self,
+        job: &LadderJob,
+        values: &mut [u8],
+        chains: Option<&mut ChainRows>,
+    ) -> Result<(), WotsError>;
+}
+
+/// Default backend walking the ladders one after another on the calling thread.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SoftwareBackend;
+
+impl<H: Hasher> ChainBackend<H> for SoftwareBackend {
+    fn walk_ladders(
+        &self,
+        job: &LadderJob,
+        values: &mut [u8],
+        mut chains: Option<&mut ChainRows>,
+    ) -> Result<(), WotsError> {
+        for (i, value) in values.chunks_mut(job.n).enumerate() {
+            walk_chain::<H>(
+                job.n,
+                job.randomness,
+                value,
+                chains.as_deref_mut(),
+                i,
+                job.ranges[i],
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Backend spreading the ladders over a number of scoped threads.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct ThreadedBackend {
+    threads: usize,
+}
+
+#[cfg(feature = "std")]
+impl ThreadedBackend {
+    pub fn new(threads: usize) -> Self {
+        ThreadedBackend { threads }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: Hasher> ChainBackend<H> for ThreadedBackend {
+    fn walk_ladders(
+        &self,
+        job: &LadderJob,
+        values: &mut [u8],
+        chains: Option<&mut ChainRows>,
+    ) -> Result<(), WotsError> {
+        let total = job.ranges.len();
+        if total == 0 {
+            return Ok(());
+        }
+        let ladders_per_thread = total.div_ceil(self.threads.clamp(1, total));
+        let parts = split_rows(
+            chains,
+            ladders_per_thread * job.n,
+            total.div_ceil(ladders_per_thread),
+        );
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = values
+                .chunks_mut(ladders_per_thread * job.n)
+                .zip(job.ranges.chunks(ladders_per_thread))
+                .zip(parts)
+                .map(|((chunk, ranges), rows)| {
+                    scope.spawn(move || walk_part::<H>(job, ranges, chunk, rows))
+                })
+                .collect();
+
+            // join every thread, so that a panic is reported here instead of by the scope
+            let mut result = Ok(());
+            for handle in handles {
+                let walked = handle
+                    .join()
+                    .unwrap_or(Err(WotsError::LadderWorkerPanicked));
+                result = result.and(walked);
+            }
+            result
+        })
+    }
+}
+
+/// Backend spreading the ladders over the threads of a caller-provided rayon pool, so
+/// applications bound the CPU W-OTS+ uses by sizing the pool instead of it grabbing the
+/// global one.
+#[cfg(feature = "rayon")]
+#[derive(Debug, Clone, Copy)]
+pub struct PoolBackend<'a> {
+    pool: &'a rayon::ThreadPool,
+}
+
+#[cfg(feature = "rayon")]
+impl<'a> PoolBackend<'a> {
+    pub fn new(pool: &'a rayon::ThreadPool) -> Self {
+        PoolBackend { pool }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<H: Hasher> ChainBackend<H> for PoolBackend<'_> {
+    fn walk_ladders(
+        &self,
+        job: &LadderJob,
+        values: &mut [u8],
+        chains: Option<&mut ChainRows>,
+    ) -> Result<(), WotsError> {
+        use rayon::prelude::*;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let total = job.ranges.len();
+        if total == 0 {
+            return Ok(());
+        }
+        let threads = self.pool.current_num_threads();
+        let ladders_per_part = total.div_ceil(threads.clamp(1, total));
+        let parts = split_rows(
+            chains,
+            ladders_per_part * job.n,
+            total.div_ceil(ladders_per_part),
+        );
+
+        // rayon rethrows panics on the caller's thread: catch them in the part that panicked
+        self.pool.install(|| {
+            values
+                .par_chunks_mut(ladders_per_part * job.n)
+                .zip(job.ranges.par_chunks(ladders_per_part))
+                .zip(parts)
+                .map(|((chunk, ranges), rows)| {
+                    catch_unwind(AssertUnwindSafe(|| {
+                        walk_part::<H>(job, ranges, chunk, rows)
+                    }))
+                    .unwrap_or(Err(WotsError::LadderWorkerPanicked))
+                })
+                .collect()
+        })
+    }
+}
+
+// split_rows splits every row of `chains` into `count` parts of `part_len` bytes, like the
+// values are split, so each part records into its own slices of the rows. Without `chains`,
+// every part gets `None`.
+#[cfg(feature = "std")]
+fn split_rows<'a>(
+    chains: Option<&'a mut ChainRows>,
+    part_len: usize,
+    count: usize,
+) -> Vec<Option<ChainRows<'a>>> {
+    let Some(chains) = chains else {
+        return (0..count).map(|_| None).collect();
+    };
+    let mut parts: Vec<Vec<&mut [u8]>> = (0..count).map(|_| Vec::new()).collect();
+    for row in chains.rows.iter_mut() {
+        for (part, slice) in parts.iter_mut().zip(row.chunks_mut(part_len)) {
+            part.push(slice);
+        }
+    }
+    let stride = chains.stride;
+    parts
+        .into_iter()
+        .map(|rows| Some(ChainRows { rows, stride }))
+        .collect()
+}
+
+// walk_part walks the ladders of `chunk` over `ranges`, recording into `chains`, the slices
+// of the rows `split_rows` gave this part.
+#[cfg(feature = "std")]
+fn walk_part<H: Hasher>(
+    job: &LadderJob,
+    ranges: &[(u16, u16)],
+    chunk: &mut [u8],
+    mut chains: Option<ChainRows>,
+) -> Result<(), WotsError> {
+    let sub_job = LadderJob { ranges, ..*job };
+    ChainBackend::<H>::walk_ladders(&SoftwareBackend, &sub_job, chunk, chains.as_mut())
+}
+
+/// Backend walking the ladders in a given order, so a repeated computation doesn't retrace the
+/// same sequence of operations.
+pub(crate) struct OrderedBackend<'a> {
+    pub order: &'a [usize],
+}
+
+impl<H: Hasher> ChainBackend<H> for OrderedBackend<'_> {
+    fn walk_ladders(
+        &self,
+        job: &LadderJob,
+        values: &mut [u8],
+        mut chains: Option<&mut ChainRows>,
+    ) -> Result<(), WotsError> {
+        for &i in self.order {
+            walk_chain::<H>(
+                job.n,
+                job.randomness,
+                &mut values[i * job.n..(i + 1) * job.n],
+                chains.as_deref_mut(),
+                i,
+                job.ranges[i],
+            );
+        }
+        Ok(())
+    }
+}
+
+// walk_chain iterates c(value, randomness) over `range`, updating `value` in place.
+pub(crate) fn walk_chain<H: Hasher>(
+    n: usize,
+    randomness: &PublicRandomness,
+    value: &mut [u8],
+    mut maybe_chains: Option<&mut ChainRows>,
+    idx: usize,
+    range: (u16, u16),
+) {
+    let mut preimage = hash_buf(n);
+    let mut buf = hash_buf(H::size());
+
+    for j in range.0..range.1 {
+        preimage
+            .iter_mut()
+            .zip(value.iter().zip(randomness.element(j as usize)))
+            .for_each(|(p, (&x1, &x2))| *p = x1 ^ x2);
+
+        let mut hasher = H::new();
+        hasher.write(randomness.p_seed());
+        hasher.write(StepIndex::new(randomness.w(), j as usize + 1).as_bytes());
+        hasher.write(&preimage);
+        hasher.sum(&mut buf);
+        value.copy_from_slice(&buf[0..n]);
+
+        if let Some(ref mut chains) = maybe_chains {
+            chains.record(j as usize + 1, idx, value);
+        }
+    }
+}
+
+impl ChainRows<'_> {
+    // record stores `value`, level `level` of ladder `idx`, if the stride keeps that level.
+    pub(crate) fn record(&mut self, level: usize, idx: usize, value: &[u8]) {
+        if level.is_multiple_of(self.stride) {
+            let n = value.len();
+            self.rows[level / self.stride][idx * n..(idx + 1) * n].copy_from_slice(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "rayon")]
+    use crate::backend::PoolBackend;
+    use crate::backend::{ChainBackend, ChainRows, LadderJob, SoftwareBackend, ThreadedBackend};
+    use crate::hasher::Blake2bHasher;
+    use crate::params::{WotsError, W};
+    use crate::randomness::PublicRandomness;
+
+    #[test]
+    fn threaded_backend_matches_software() {
+        let n = 32;
+        let total = 34;
+        // above W, the step is hashed as two bytes
+        for randomness in [
+            PublicRandomness::new::<Blake2bHasher>(n, &[88u8; 32]),
+            PublicRandomness::with_w::<Blake2bHasher>(n, 1024, &[88u8; 32]),
+        ] {
+            let ranges: Vec<(u16, u16)> = (0..total).map(|i| (i as u16, (W - 1) as u16)).collect();
+            let job = LadderJob {
+                n,
+                randomness: &randomness,
+                ranges: &ranges,
+            };
+
+            let mut expected = vec![99u8; n * total];
+            let mut expected_rows = vec![expected.clone(); W];
+            ChainBackend::<Blake2bHasher>::walk_ladders(
+                &SoftwareBackend,
+                &job,
+                &mut expected,
+                Some(&mut ChainRows {
+                    rows: expected_rows.iter_mut().map(Vec::as_mut_slice).collect(),
+                    stride: 1,
+                }),
+            )
+            .unwrap();
+
+            for threads in [1, 5, 100] {
+                let mut values = vec![99u8; n * total];
+                let mut rows = vec![values.clone(); W];
+                ChainBackend::<Blake2bHasher>::walk_ladders(
+                    &ThreadedBackend::new(threads),
+                    &job,
+                    &mut values,
+                    Some(&mut ChainRows {
+                        rows: rows.iter_mut().map(Vec::as_mut_slice).collect(),
+                        stride: 1,
+                    }),
+                )
+                .unwrap();
+                assert_eq!(values, expected);
+                assert_eq!(rows, expected_rows);
+            }
+
+            #[cfg(feature = "rayon")]
+            for threads in [1, 3] {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap();
+                let mut values = vec![99u8; n * total];
+                let mut rows = vec![values.clone(); W];
+                ChainBackend::<Blake2bHasher>::walk_ladders(
+                    &PoolBackend::new(&pool),
+                    &job,
+                    &mut values,
+                    Some(&mut ChainRows {
+                        rows: rows.iter_mut().map(Vec::as_mut_slice).collect(),
+                        stride: 1,
+                    }),
+                )
+                .unwrap();
+                assert_eq!(values, expected);
+                assert_eq!(rows, expected_rows);
+            }
+        }
+    }
+
+    #[test]
+    fn worker_panics_are_errors() {
+        let n = 32;
+        let randomness = PublicRandomness::new::<Blake2bHasher>(n, &[88u8; 32]);
+        // a range past the randomizers makes the worker walking the last ladder panic
+        let mut ranges = vec![(0, 1); 8];
+        ranges[7] = (0, W as u16 + 1);
+        let job = LadderJob {
+            n,
+            randomness: &randomness,
+            ranges: &ranges,
+        };
+        let mut values = vec![0u8; n * ranges.len()];
+        assert_eq!(
+            ChainBackend::<Blake2bHasher>::walk_ladders(
+                &ThreadedBackend::new(4),
+                &job,
+                &mut values,
+                None
+            ),
+            Err(WotsError::LadderWorkerPanicked)
+        );
+
+        #[cfg(feature = "rayon")]
+        {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(4)
+                .build()
+                .unwrap();
+            assert_eq!(
+                ChainBackend::<Blake2bHasher>::walk_ladders(
+                    &PoolBackend::new(&pool),
+                    &job,
+                    &mut values,
+                    None
+                ),
+                Err(WotsError::LadderWorkerPanicked)
+            );
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Emits deterministic W-OTS+ known-answer vectors as JSON, one entry per encoding and
+//! message, so other implementations can cross-check against this crate.
+//!
+//! Usage: `cargo run --bin gen_vectors [output.json]` (defaults to stdout).
+
+use std::fs::File;
+use std::io::{self, Write};
+
+use w_ots::hasher::{Blake2bHasher, Hasher, Sha3_224Hasher, Sha3_256Hasher};
+use w_ots::keys::Key;
+use w_ots::params::{Params, MAX_MSG_SIZE, SEED_SIZE};
+use w_ots::security::{self, ParamsEncoding};
+
+/// Version of the JSON vector file format
+const FORMAT_VERSION: u32 = 1;
+
+// derive_seed deterministically derives a seed from a label and the encoding, so the vectors
+// only change when the scheme does.
+fn derive_seed(label: &str, encoding: &ParamsEncoding) -> [u8; SEED_SIZE] {
+    let mut hasher = Blake2bHasher::new();
+    hasher.write(b"w-ots test vectors/");
+    hasher.write(label.as_bytes());
+    hasher.write(&[encoding.into()]);
+    let mut seed = [0u8; SEED_SIZE];
+    hasher.sum(&mut seed);
+    seed
+}
+
+fn encoding_name(encoding: &ParamsEncoding) -> &'static str {
+    match encoding {
+        ParamsEncoding::Level0 => "level0",
+        ParamsEncoding::Level1 => "level1",
+        ParamsEncoding::Level2 => "level2",
+        ParamsEncoding::Level3 => "level3",
+        ParamsEncoding::Consensus => "consensus",
+        ParamsEncoding::Custom => "custom",
+    }
+}
+
+fn vectors_for<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    params: Params<PRFH, MSGH>,
+    prf_hash: &str,
+    msg_hash: &str,
+    messages: &[Vec<u8>],
+    out: &mut Vec<String>,
+) {
+    let encoding = params.encoding;
+    let seed = derive_seed("seed", &encoding);
+    let p_seed = derive_seed("p_seed", &encoding);
+    let key = Key::from_seed(params.clone(), seed, p_seed).expect("deriving key should not fail");
+
+    for msg in messages {
+        let signature = key
+            .sign(msg)
+            .expect("signing vector message should not fail");
+        out.push(format!(
+            concat!(
+                "    {{\n",
+                "      \"encoding\": \"{}\",\n",
+                "      \"n\": {},\n",
+                "      \"m\": {},\n",
+                "      \"prf_hash\": \"{}\",\n",
+                "      \"msg_hash\": \"{}\",\n",
+                "      \"seed\": \"{}\",\n",
+                "      \"p_seed\": \"{}\",\n",
+                "      \"secret_key\": \"{}\",\n",
+                "      \"public_key\": \"{}\",\n",
+                "      \"msg\": \"{}\",\n",
+                "      \"signature\": \"{}\"\n",
+                "    }}"
+            ),
+            encoding_name(&encoding),
+            params.n,
+            params.m,
+            prf_hash,
+            msg_hash,
+            hex::encode(seed),
+            hex::encode(p_seed),
+            hex::encode(&key.secret_key),
+            hex::encode(&key.public_key),
+            hex::encode(msg),
+            hex::encode(&signature),
+        ));
+    }
+}
+
+fn main() -> io::Result<()> {
+    let messages = vec![
+        Vec::new(),
+        b"XX NETWORK".to_vec(),
+        (0..MAX_MSG_SIZE).map(|i| i as u8).collect(),
+    ];
+
+    let mut vectors = Vec::new();
+    vectors_for::<Blake2bHasher, Sha3_224Hasher>(
+        security::level_0_params(),
+        "blake2b-256",
+        "sha3-224",
+        &messages,
+        &mut vectors,
+    );
+    vectors_for::<Blake2bHasher, Sha3_224Hasher>(
+        security::level_1_params(),
+        "blake2b-256",
+        "sha3-224",
+        &messages,
+        &mut vectors,
+    );
+    vectors_for::<Blake2bHasher, Sha3_224Hasher>(
+        security::level_2_params(),
+        "blake2b-256",
+        "sha3-224",
+        &messages,
+        &mut vectors,
+    );
+    vectors_for::<Blake2bHasher, Sha3_224Hasher>(
+        security::level_3_params(),
+        "blake2b-256",
+        "sha3-224",
+        &messages,
+        &mut vectors,
+    );
+    vectors_for::<Blake2bHasher, Sha3_256Hasher>(
+        security::consensus_params(),
+        "blake2b-256",
+        "sha3-256",
+        &messages,
+        &mut vectors,
+    );
+
+    let json = format!(
+        "{{\n  \"version\": {},\n  \"vectors\": [\n{}\n  ]\n}}\n",
+        FORMAT_VERSION,
+        vectors.join(",\n")
+    );
+
+    match std::env::args().nth(1) {
+        Some(path) => File::create(path)?.write_all(json.as_bytes()),
+        None => io::stdout().write_all(json.as_bytes()),
+    }
+}
@@ -0,0 +1,185 @@
+//! Command line front end for generating W-OTS+ keys, signing and verifying.
+//!
+//! Usage:
+//!
+//! ```text
+//! wots keygen <encoding> <key-file>           writes a new key, prints its public key
+//! wots sign <key-file> <msg>                  prints the signature of msg
+//! wots verify <public-key> <msg> <signature>  exits with 0 iff the signature is valid
+//! wots inspect <signature> [<msg>]            prints the fields of a signature, and the
+//!                                             digit of every ladder when given msg
+//! ```
+//!
+//! `<encoding>` is one of `level0`..`level3` or `consensus`. Keys, messages and signatures are
+//! given as hex, or as `@path` to read the raw bytes from a file. Key files hold the hex of
+//! `encoding || seed || p_seed`.
+
+use std::env;
+use std::fs;
+use std::process;
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use w_ots::hasher::{Blake2bHasher, Hasher, Sha3_224Hasher, Sha3_256Hasher};
+use w_ots::keys::Key;
+use w_ots::params::{Params, SEED_SIZE};
+use w_ots::security::{self, ParamsEncoding};
+use w_ots::signature::Signature;
+use w_ots::traits::WotsSigner;
+
+const USAGE: &str = "usage:
+  wots keygen <encoding> <key-file>
+  wots sign <key-file> <msg>
+  wots verify <public-key> <msg> <signature>
+  wots inspect <signature> [<msg>]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let res = match args.as_slice() {
+        ["keygen", encoding, path] => keygen(encoding, path),
+        ["sign", path, msg] => sign(path, msg),
+        ["verify", public_key, msg, signature] => verify(public_key, msg, signature),
+        ["inspect", signature] => inspect(signature, None),
+        ["inspect", signature, msg] => inspect(signature, Some(msg)),
+        _ => Err(USAGE.to_string()),
+    };
+    if let Err(err) = res {
+        eprintln!("{}", err);
+        process::exit(2);
+    }
+}
+
+fn parse_encoding(name: &str) -> Result<ParamsEncoding, String> {
+    match name {
+        "level0" => Ok(ParamsEncoding::Level0),
+        "level1" => Ok(ParamsEncoding::Level1),
+        "level2" => Ok(ParamsEncoding::Level2),
+        "level3" => Ok(ParamsEncoding::Level3),
+        "consensus" => Ok(ParamsEncoding::Consensus),
+        _ => Err(format!("unknown encoding {:?}", name)),
+    }
+}
+
+// read_bytes decodes a hex argument, or reads the raw contents of `@path`.
+fn read_bytes(arg: &str) -> Result<Vec<u8>, String> {
+    match arg.strip_prefix('@') {
+        Some(path) => fs::read(path).map_err(|err| format!("reading {}: {}", path, err)),
+        None => hex::decode(arg).map_err(|err| format!("decoding {:?}: {}", arg, err)),
+    }
+}
+
+fn keygen(encoding: &str, path: &str) -> Result<(), String> {
+    let encoding = parse_encoding(encoding)?;
+    let mut key_file = vec![u8::from(&encoding)];
+    key_file.resize(1 + 2 * SEED_SIZE, 0);
+    OsRng.fill_bytes(&mut key_file[1..]);
+    let public_key = with_key(&key_file, |key| key.public_key())?.map_err(|err| err.to_string())?;
+
+    fs::write(path, hex::encode(&key_file)).map_err(|err| format!("writing {}: {}", path, err))?;
+    println!("{}", hex::encode(public_key));
+    Ok(())
+}
+
+fn sign(path: &str, msg: &str) -> Result<(), String> {
+    let key_file = fs::read_to_string(path).map_err(|err| format!("reading {}: {}", path, err))?;
+    let key_file = read_bytes(key_file.trim())?;
+    let msg = read_bytes(msg)?;
+    let signature = with_key(&key_file, |key| key.sign(&msg))?.map_err(|err| err.to_string())?;
+    println!("{}", hex::encode(signature));
+    Ok(())
+}
+
+fn verify(public_key: &str, msg: &str, signature: &str) -> Result<(), String> {
+    let public_key = read_bytes(public_key)?;
+    let msg = read_bytes(msg)?;
+    let signature = read_bytes(signature)?;
+    match security::verify(&msg, &signature, &public_key) {
+        Ok(()) => {
+            println!("ok");
+            Ok(())
+        }
+        Err(err) => {
+            println!("invalid: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn inspect(signature: &str, msg: Option<&str>) -> Result<(), String> {
+    let signature = read_bytes(signature)?;
+    let msg = msg.map(read_bytes).transpose()?;
+    let info = match security::inspect(&signature, msg.as_deref()) {
+        Ok(info) => info,
+        Err(err) => {
+            // report what the header claims, so size mismatches can still be diagnosed
+            if let Some(Ok(encoding)) = signature.first().map(|&b| ParamsEncoding::try_from(b)) {
+                let header = signature[0];
+                println!("encoding:   {:?} ({})", encoding, header);
+                if let Some(expected) = encoding.signature_size() {
+                    println!("size:       {} (expected {})", signature.len(), expected);
+                }
+            }
+            return Err(err.to_string());
+        }
+    };
+
+    if let Ok(summary) = Signature::try_from(signature.as_slice()) {
+        println!("{}", summary);
+    }
+    println!("encoding:   {:?} ({})", info.encoding, signature[0]);
+    println!("n:          {}", info.n);
+    println!("m:          {}", info.m);
+    println!("ladders:    {}", info.total);
+    println!("size:       {}", signature.len());
+    println!("p_seed:     {}", hex::encode(&info.p_seed));
+    if let Some(public_key) = &info.public_key {
+        println!("public key: {}", hex::encode(public_key));
+    }
+    for (i, point) in info.points.iter().enumerate() {
+        match &info.digits {
+            Some(digits) => println!(
+                "ladder {:>3}: {} digit {:>3}",
+                i,
+                hex::encode(point),
+                digits[i]
+            ),
+            None => println!("ladder {:>3}: {}", i, hex::encode(point)),
+        }
+    }
+    Ok(())
+}
+
+// with_key parses a key file and runs `f` on the key it describes.
+fn with_key<T>(key_file: &[u8], f: impl Fn(&dyn WotsSigner) -> T) -> Result<T, String> {
+    if key_file.len() != 1 + 2 * SEED_SIZE {
+        return Err(format!("key file must hold {} bytes", 1 + 2 * SEED_SIZE));
+    }
+    let mut seed = [0u8; SEED_SIZE];
+    let mut p_seed = [0u8; SEED_SIZE];
+    seed.copy_from_slice(&key_file[1..1 + SEED_SIZE]);
+    p_seed.copy_from_slice(&key_file[1 + SEED_SIZE..]);
+
+    match ParamsEncoding::try_from(key_file[0]) {
+        Ok(ParamsEncoding::Consensus) => {
+            let params = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
+            Ok(f(&derive(params, seed, p_seed)?))
+        }
+        Err(_) | Ok(ParamsEncoding::Custom) => {
+            Err(format!("unknown encoding byte {}", key_file[0]))
+        }
+        Ok(encoding) => {
+            let params = Params::<Blake2bHasher, Sha3_224Hasher>::new(encoding)
+                .map_err(|err| err.to_string())?;
+            Ok(f(&derive(params, seed, p_seed)?))
+        }
+    }
+}
+
+fn derive<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    params: Params<PRFH, MSGH>,
+    seed: [u8; SEED_SIZE],
+    p_seed: [u8; SEED_SIZE],
+) -> Result<Key<PRFH, MSGH>, String> {
+    Key::from_seed(params, seed, p_seed).map_err(|err| err.to_string())
+}
@@ -0,0 +1,155 @@
+use sha3::{Digest, Sha3_256};
+use subtle::ConstantTimeEq;
+
+use crate::keys::PK_SIZE;
+use crate::params::{WotsError, SEED_SIZE};
+use crate::security::ParamsEncoding;
+pub use crate::wire::{BUNDLE_SIZE, BUNDLE_VERSION, FINGERPRINT_SIZE};
+
+const FINGERPRINT_DOMAIN: &[u8] = b"W-OTS+ public bundle";
+
+/// Public material of a key, as carried out of an air-gapped key ceremony.
+///
+/// Encoded as version || encoding byte || `p_seed` || public key || fingerprint. The
+/// fingerprint is a SHA3-256 digest over everything before it and is meant to be read out
+/// and compared on both sides of the air gap. The bundle is not signed by the key itself, as
+/// that would spend its one signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicBundle {
+    pub encoding: ParamsEncoding,
+    pub p_seed: [u8; SEED_SIZE],
+    pub public_key: [u8; PK_SIZE],
+}
+
+impl PublicBundle {
+    /// Fails with `WotsError::InvalidParamsEncodingType` for `Custom`, whose `(n, m)` a
+    /// verifier could not recover from the encoding byte.
+    pub fn new(
+        encoding: ParamsEncoding,
+        p_seed: [u8; SEED_SIZE],
+        public_key: [u8; PK_SIZE],
+    ) -> Result<Self, WotsError> {
+        if encoding == ParamsEncoding::Custom {
+            return Err(WotsError::InvalidParamsEncodingType);
+        }
+        Ok(PublicBundle {
+            encoding,
+            p_seed,
+            public_key,
+        })
+    }
+
+    pub fn fingerprint(&self) -> [u8; FINGERPRINT_SIZE] {
+        fingerprint(&self.to_bytes()[..BUNDLE_SIZE - FINGERPRINT_SIZE])
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(BUNDLE_SIZE);
+        bytes.push(BUNDLE_VERSION);
+        bytes.push(u8::from(&self.encoding));
+        bytes.extend_from_slice(&self.p_seed);
+        bytes.extend_from_slice(&self.public_key);
+        let fp = fingerprint(&bytes);
+        bytes.extend_from_slice(&fp);
+        bytes
+    }
+}
+
+/// Parses and checks a bundle made by `Key::export_public_bundle`. When `expected_fingerprint`
+/// is given, e.g. as read out during the ceremony, the bundle must carry that fingerprint.
+pub fn verify_bundle(
+    bytes: &[u8],
+    expected_fingerprint: Option<&[u8]>,
+) -> Result<PublicBundle, WotsError> {
+    if bytes.is_empty() {
+        return Err(WotsError::InvalidBundle);
+    }
+    if bytes[0] != BUNDLE_VERSION {
+        return Err(WotsError::UnsupportedBundleVersion);
+    }
+    if bytes.len() != BUNDLE_SIZE {
+        return Err(WotsError::InvalidBundle);
+    }
+
+    let (body, fp) = bytes.split_at(BUNDLE_SIZE - FINGERPRINT_SIZE);
+    if !bool::from(fingerprint(body).ct_eq(fp)) {
+        return Err(WotsError::InvalidBundle);
+    }
+    if let Some(expected) = expected_fingerprint {
+        if !bool::from(expected.ct_eq(fp)) {
+            return Err(WotsError::InvalidBundle);
+        }
+    }
+
+    let mut p_seed = [0u8; SEED_SIZE];
+    p_seed.copy_from_slice(&body[2..2 + SEED_SIZE]);
+    let mut public_key = [0u8; PK_SIZE];
+    public_key.copy_from_slice(&body[2 + SEED_SIZE..]);
+    PublicBundle::new(ParamsEncoding::try_from(body[1])?, p_seed, public_key)
+}
+
+fn fingerprint(body: &[u8]) -> [u8; FINGERPRINT_SIZE] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(FINGERPRINT_DOMAIN);
+    hasher.update(body);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bundle::{verify_bundle, BUNDLE_SIZE, BUNDLE_VERSION};
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{Params, WotsError, SEED_SIZE};
+    use crate::security::{self, ParamsEncoding};
+
+    #[test]
+    fn export_verify_bundle() {
+        let params = security::level_2_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let bundle = key.export_public_bundle().unwrap();
+        assert_eq!(bundle.len(), BUNDLE_SIZE);
+        assert_eq!(bundle[0], BUNDLE_VERSION);
+
+        let parsed = verify_bundle(&bundle, None).unwrap();
+        assert_eq!(parsed.encoding, ParamsEncoding::Level2);
+        assert_eq!(parsed.p_seed, key.p_seed);
+        assert_eq!(&parsed.public_key[..], &key.public_key[..]);
+        let fp = parsed.fingerprint();
+        assert_eq!(verify_bundle(&bundle, Some(&fp)).unwrap(), parsed);
+
+        let mut other_fp = fp;
+        other_fp[0] ^= 1;
+        assert_eq!(
+            verify_bundle(&bundle, Some(&other_fp)),
+            Err(WotsError::InvalidBundle)
+        );
+        for i in [1, 2, BUNDLE_SIZE - 1] {
+            let mut tampered = bundle.clone();
+            tampered[i] ^= 1;
+            assert_eq!(
+                verify_bundle(&tampered, None),
+                Err(WotsError::InvalidBundle),
+                "byte {}",
+                i
+            );
+        }
+        let mut versioned = bundle.clone();
+        versioned[0] = 2;
+        assert_eq!(
+            verify_bundle(&versioned, None),
+            Err(WotsError::UnsupportedBundleVersion)
+        );
+        assert_eq!(
+            verify_bundle(&bundle[..BUNDLE_SIZE - 1], None),
+            Err(WotsError::InvalidBundle)
+        );
+
+        let custom = Params::<Blake2bHasher, Sha3_224Hasher>::new_from_values(20, 24).unwrap();
+        let key = Key::from_seed(custom, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        assert_eq!(
+            key.export_public_bundle(),
+            Err(WotsError::InvalidParamsEncodingType)
+        );
+    }
+}
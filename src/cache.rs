@@ -0,0 +1,226 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard};
+
+use sha3::{Digest, Sha3_256};
+
+use crate::keys::PK_SIZE;
+use crate::params::WotsError;
+use crate::security::{Verifier, VerifyPolicy};
+
+/// Hit and miss counts of a `VerifyCache`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Bounded LRU cache of verification results in front of a `Verifier`, for gossip layers that
+/// receive the same signature from many peers: only the first copy walks the ladders, later
+/// ones are answered from the cache. Results are keyed by the SHA3-256 hashes of the message
+/// and the signature together with the public key, and both valid and invalid results are
+/// kept, since verification is deterministic.
+///
+/// Safe to share between threads; verification runs outside the lock.
+#[derive(Debug)]
+pub struct VerifyCache {
+    verifier: Verifier,
+    capacity: usize,
+    inner: Mutex<Lru>,
+}
+
+// CacheKey identifies one verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct CacheKey {
+    msg: [u8; 32],
+    signature: [u8; 32],
+    public_key: [u8; PK_SIZE],
+}
+
+// Lru holds the results and the order they were last used in: `order` maps each entry's last
+// use to its key, so the first entry is the least recently used.
+#[derive(Debug, Default)]
+struct Lru {
+    entries: HashMap<CacheKey, (u64, Result<(), WotsError>)>,
+    order: BTreeMap<u64, CacheKey>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl VerifyCache {
+    /// Cache of up to `capacity` results in front of a verifier accepting every predefined
+    /// encoding, as `security::verify` does. A capacity of 0 caches nothing.
+    pub fn new(capacity: usize) -> Self {
+        VerifyCache {
+            verifier: Verifier::new(VerifyPolicy::all()),
+            capacity,
+            inner: Mutex::default(),
+        }
+    }
+
+    /// Verifies cache misses with `verifier`, e.g. one with a stricter policy.
+    pub fn with_verifier(mut self, verifier: Verifier) -> Self {
+        self.verifier = verifier;
+        self
+    }
+
+    /// `Verifier::verify`, answered from the cache when the same message, signature and
+    /// public key were verified before.
+    pub fn verify(
+        &self,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+    ) -> Result<(), WotsError> {
+        let (msg, signature, public_key) = (msg.as_ref(), signature.as_ref(), public_key.as_ref());
+        let Ok(public_key_bytes) = <[u8; PK_SIZE]>::try_from(public_key) else {
+            return self.verifier.verify(msg, signature, public_key);
+        };
+        let key = CacheKey {
+            msg: Sha3_256::digest(msg).into(),
+            signature: Sha3_256::digest(signature).into(),
+            public_key: public_key_bytes,
+        };
+        if let Some(result) = self.lock().get(&key) {
+            return result;
+        }
+        let result = self.verifier.verify(msg, signature, public_key);
+        self.lock().insert(key, result.clone(), self.capacity);
+        result
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats
+    }
+
+    /// Drops every cached result; the stats are kept.
+    pub fn clear(&self) {
+        let mut lru = self.lock();
+        lru.entries.clear();
+        lru.order.clear();
+    }
+
+    // lock takes the cache over if a panic poisoned it: every update leaves it consistent.
+    fn lock(&self) -> MutexGuard<'_, Lru> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Lru {
+    // get returns the cached result of `key`, marking it as the most recently used.
+    fn get(&mut self, key: &CacheKey) -> Option<Result<(), WotsError>> {
+        self.tick += 1;
+        let Some((used, result)) = self.entries.get_mut(key) else {
+            self.stats.misses += 1;
+            return None;
+        };
+        self.order.remove(used);
+        *used = self.tick;
+        self.order.insert(self.tick, *key);
+        self.stats.hits += 1;
+        Some(result.clone())
+    }
+
+    // insert caches `result`, evicting the least recently used entry when full.
+    fn insert(&mut self, key: CacheKey, result: Result<(), WotsError>, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((used, _)) = self.entries.insert(key, (self.tick, result)) {
+            // another thread verified the same signature meanwhile
+            self.order.remove(&used);
+        } else if self.entries.len() > capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.insert(self.tick, key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::{CacheStats, VerifyCache};
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::security::{self, ParamsEncoding, Verifier, VerifyPolicy};
+
+    #[test]
+    fn verify_cache() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let keys: Vec<_> = (0..3u8)
+            .map(|i| Key::from_seed(params.clone(), [i; SEED_SIZE], [2u8; SEED_SIZE]).unwrap())
+            .collect();
+        let signatures: Vec<_> = keys.iter().map(|key| key.sign(b"msg").unwrap()).collect();
+
+        let cache = VerifyCache::new(2);
+        cache
+            .verify(b"msg", &signatures[0], &keys[0].public_key)
+            .unwrap();
+        cache
+            .verify(b"msg", &signatures[0], &keys[0].public_key)
+            .unwrap();
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+
+        // the public key is part of the key: a cached valid result doesn't carry over
+        assert_eq!(
+            cache.verify(b"msg", &signatures[0], &keys[1].public_key),
+            Err(WotsError::InvalidSignature)
+        );
+        assert_eq!(
+            cache.verify(b"msg", &signatures[0], &keys[1].public_key),
+            Err(WotsError::InvalidSignature)
+        );
+        assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 2 });
+        assert_eq!(cache.len(), 2);
+
+        // the first entry was used last, so the second is evicted
+        cache
+            .verify(b"msg", &signatures[0], &keys[0].public_key)
+            .unwrap();
+        cache
+            .verify(b"msg", &signatures[2], &keys[2].public_key)
+            .unwrap();
+        assert_eq!(cache.len(), 2);
+        cache
+            .verify(b"msg", &signatures[0], &keys[0].public_key)
+            .unwrap();
+        assert_eq!(cache.stats(), CacheStats { hits: 4, misses: 3 });
+        assert_eq!(
+            cache.verify(b"msg", &signatures[0], &keys[1].public_key),
+            Err(WotsError::InvalidSignature)
+        );
+        assert_eq!(cache.stats(), CacheStats { hits: 4, misses: 4 });
+
+        assert_eq!(
+            cache.verify(b"msg", &signatures[0], &keys[0].public_key[1..]),
+            Err(WotsError::InvalidPublicKeySize)
+        );
+        cache.clear();
+        assert!(cache.is_empty());
+
+        let none = VerifyCache::new(0);
+        none.verify(b"msg", &signatures[0], &keys[0].public_key)
+            .unwrap();
+        assert!(none.is_empty());
+
+        let strict = VerifyCache::new(4).with_verifier(Verifier::new(VerifyPolicy {
+            allowed: vec![ParamsEncoding::Level3],
+        }));
+        assert_eq!(
+            strict.verify(b"msg", &signatures[0], &keys[0].public_key),
+            Err(WotsError::InvalidParamsEncodingType)
+        );
+    }
+}
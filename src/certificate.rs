@@ -0,0 +1,171 @@
+use crate::keys::{PublicKey, PK_SIZE};
+use crate::params::WotsError;
+use crate::security::{self, ParamsEncoding};
+use crate::signature::Signature;
+use crate::traits::WotsSigner;
+use crate::wire::CERTIFICATE_HEADER_SIZE;
+pub use crate::wire::CERTIFICATE_VERSION;
+
+const CERTIFICATE_DOMAIN: &[u8] = b"W-OTS+ certificate";
+
+/// A one-time public key (`subject`) vouched for by a signature of `issuer`, so a protocol can
+/// announce its next key through the current one. Issuing spends the issuer's one signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    pub issuer: PublicKey,
+    pub subject: PublicKey,
+    pub signature: Signature,
+}
+
+impl Certificate {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let signature = self.signature.as_bytes();
+        let mut bytes = Vec::with_capacity(CERTIFICATE_HEADER_SIZE + signature.len());
+        bytes.push(CERTIFICATE_VERSION);
+        bytes.extend_from_slice(self.issuer.as_bytes());
+        bytes.extend_from_slice(self.subject.as_bytes());
+        bytes.extend_from_slice(signature);
+        bytes
+    }
+
+    /// Parses the output of `to_bytes`, checking the structure of the signature but not
+    /// verifying it; see `verify_certificate`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WotsError> {
+        if bytes.len() < CERTIFICATE_HEADER_SIZE || bytes[0] != CERTIFICATE_VERSION {
+            return Err(WotsError::InvalidCertificate);
+        }
+        let (issuer, rest) = bytes[1..].split_at(PK_SIZE);
+        let (subject, signature) = rest.split_at(PK_SIZE);
+        Ok(Certificate {
+            issuer: PublicKey::try_from(issuer)?,
+            subject: PublicKey::try_from(subject)?,
+            signature: Signature::try_from(signature).map_err(|_| WotsError::InvalidCertificate)?,
+        })
+    }
+}
+
+/// Signs `pk` with `signer`, using up its one signature. Fails with
+/// `WotsError::InvalidParamsEncodingType`, before signing, if `verify_certificate` couldn't
+/// check the signature: when the signer's params aren't predefined ones (see
+/// `WotsSigner::canonical_encoding`).
+pub fn certify(pk: &PublicKey, signer: &mut impl WotsSigner) -> Result<Certificate, WotsError> {
+    if signer.canonical_encoding() == ParamsEncoding::Custom {
+        return Err(WotsError::InvalidParamsEncodingType);
+    }
+    let signature = signer.sign(&message(pk))?;
+    Ok(Certificate {
+        issuer: signer.public_key()?,
+        subject: *pk,
+        signature: Signature::try_from(signature)?,
+    })
+}
+
+/// Checks `certificate` was issued by `issuer` and returns the key it certifies.
+pub fn verify_certificate(
+    certificate: &Certificate,
+    issuer: &PublicKey,
+) -> Result<PublicKey, WotsError> {
+    if certificate.issuer != *issuer {
+        return Err(WotsError::IssuerMismatch);
+    }
+    security::verify(
+        message(&certificate.subject),
+        certificate.signature.as_bytes(),
+        issuer,
+    )?;
+    Ok(certificate.subject)
+}
+
+/// Follows a chain of certificates from `root`, each issued by the subject of the one before,
+/// and returns the last certified key; `root` itself for an empty chain.
+pub fn verify_chain(root: &PublicKey, chain: &[Certificate]) -> Result<PublicKey, WotsError> {
+    chain.iter().try_fold(*root, |issuer, certificate| {
+        verify_certificate(certificate, &issuer)
+    })
+}
+
+// message is what an issuer signs to certify `subject`.
+fn message(subject: &PublicKey) -> Vec<u8> {
+    let mut msg = CERTIFICATE_DOMAIN.to_vec();
+    msg.extend_from_slice(subject.as_bytes());
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::certificate::{certify, verify_certificate, verify_chain, Certificate};
+    use crate::codec::LengthPrefix;
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::{Key, KeyState};
+    use crate::params::{EncodingProfile, Params, WotsError, SEED_SIZE};
+    use crate::security;
+
+    #[test]
+    fn certificate_chain() {
+        let params = security::level_1_params::<Blake2bHasher, Sha3_224Hasher>();
+        let mut keys: Vec<_> = (1..=3u8)
+            .map(|i| Key::from_seed(params.clone(), [i; SEED_SIZE], [i + 10; SEED_SIZE]).unwrap())
+            .collect();
+        let pks: Vec<_> = keys
+            .iter()
+            .map(|key| key.verifying_key().unwrap())
+            .collect();
+
+        let first = certify(&pks[1], &mut keys[0]).unwrap();
+        assert_eq!(verify_certificate(&first, &pks[0]), Ok(pks[1]));
+        let parsed = Certificate::from_bytes(&first.to_bytes()).unwrap();
+        assert_eq!(parsed, first);
+        // the issuer's signature is spent
+        assert_eq!(
+            certify(&pks[2], &mut keys[0]).err(),
+            Some(WotsError::KeyAlreadyUsed)
+        );
+
+        let second = certify(&pks[2], &mut keys[1]).unwrap();
+        assert_eq!(
+            verify_chain(&pks[0], &[first.clone(), second.clone()]),
+            Ok(pks[2])
+        );
+        assert_eq!(
+            verify_chain(&pks[0], std::slice::from_ref(&second)),
+            Err(WotsError::IssuerMismatch)
+        );
+
+        let mut forged = second;
+        forged.subject = pks[0];
+        assert_eq!(
+            verify_certificate(&forged, &pks[1]),
+            Err(WotsError::InvalidSignature)
+        );
+        assert_eq!(
+            Certificate::from_bytes(&first.to_bytes()[..100]),
+            Err(WotsError::InvalidCertificate)
+        );
+    }
+
+    #[test]
+    fn certify_checks_issuer_params() {
+        let level1 = security::level_1_params::<Blake2bHasher, Sha3_224Hasher>();
+        let subject = Key::from_seed(level1.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE])
+            .unwrap()
+            .verifying_key()
+            .unwrap();
+        let custom = Params::<Blake2bHasher, Sha3_224Hasher>::new_from_values(24, 20).unwrap();
+        let profiled = level1.clone().with_profile(EncodingProfile {
+            bind_params: true,
+            ..EncodingProfile::default()
+        });
+        let coded = level1.with_codec(Arc::new(LengthPrefix));
+        for params in [custom, profiled, coded] {
+            let mut issuer = Key::from_seed(params, [3u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+            assert_eq!(
+                certify(&subject, &mut issuer).err(),
+                Some(WotsError::InvalidParamsEncodingType)
+            );
+            // refused before signing: the issuer keeps its one signature
+            assert_eq!(issuer.state(), KeyState::Fresh);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+
+use crate::params::{WotsError, CONTEXT_DOMAIN, MAX_CONTEXT_SIZE};
+
+/// Canonical encoding of messages before they are hashed into ladder digits, set with
+/// `Params::with_codec`, so that consensus code can enforce canonical preimages without
+/// wrapping every sign and verify call. Signers and verifiers must use the same codec.
+pub trait MessageCodec: fmt::Debug + Send + Sync {
+    /// Feeds the encoding of `msg` to `write`, in one or more pieces.
+    fn encode(&self, msg: &[u8], write: &mut dyn FnMut(&[u8]));
+
+    /// Bytes that are equal for two codecs exactly when they encode alike, so that params
+    /// holding separate instances of one codec compare equal. `None` compares instances by
+    /// address instead.
+    fn identity(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// `len(msg) || msg`, with the length as 4 big-endian bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LengthPrefix;
+
+impl MessageCodec for LengthPrefix {
+    fn encode(&self, msg: &[u8], write: &mut dyn FnMut(&[u8])) {
+        write(&(msg.len() as u32).to_be_bytes());
+        write(msg);
+    }
+
+    fn identity(&self) -> Option<Vec<u8>> {
+        Some(vec![0])
+    }
+}
+
+/// `domain || msg`. The domain isn't length-prefixed, so domains sharing a codec must not be
+/// prefixes of each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainPrefix(pub Vec<u8>);
+
+impl MessageCodec for DomainPrefix {
+    fn encode(&self, msg: &[u8], write: &mut dyn FnMut(&[u8])) {
+        write(&self.0);
+        write(msg);
+    }
+
+    fn identity(&self) -> Option<Vec<u8>> {
+        Some([&[1][..], &self.0].concat())
+    }
+}
+
+/// `CONTEXT_DOMAIN || len(context) || context || msg`, what `Key::sign_with_context` hashes, so
+/// params with this codec sign and verify context-bound messages through the plain methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningContext(Vec<u8>);
+
+impl SigningContext {
+    /// Fails with `WotsError::InvalidContextSize` for contexts over `MAX_CONTEXT_SIZE` bytes.
+    pub fn new(context: impl Into<Vec<u8>>) -> Result<Self, WotsError> {
+        let context = context.into();
+        if context.len() > MAX_CONTEXT_SIZE {
+            return Err(WotsError::InvalidContextSize);
+        }
+        Ok(SigningContext(context))
+    }
+
+    pub fn context(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl MessageCodec for SigningContext {
+    fn encode(&self, msg: &[u8], write: &mut dyn FnMut(&[u8])) {
+        write(&CONTEXT_DOMAIN);
+        write(&[self.0.len() as u8]);
+        write(&self.0);
+        write(msg);
+    }
+
+    fn identity(&self) -> Option<Vec<u8>> {
+        Some([&[2][..], &self.0].concat())
+    }
+}
+
+/// SCALE encoding of the message as a byte vector: its compact length, then its bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Scale;
+
+impl MessageCodec for Scale {
+    fn encode(&self, msg: &[u8], write: &mut dyn FnMut(&[u8])) {
+        write(&scale_compact(msg.len() as u64));
+        write(msg);
+    }
+
+    fn identity(&self) -> Option<Vec<u8>> {
+        Some(vec![3])
+    }
+}
+
+// scale_compact returns the SCALE compact encoding of `value`: the two low bits of the first
+// byte select single, two and four byte modes, or a little-endian integer of 4 to 8 bytes.
+fn scale_compact(value: u64) -> Vec<u8> {
+    match value {
+        0..=0x3f => vec![(value as u8) << 2],
+        0x40..=0x3fff => ((value as u16) << 2 | 0b01).to_le_bytes().to_vec(),
+        0x4000..=0x3fff_ffff => ((value as u32) << 2 | 0b10).to_le_bytes().to_vec(),
+        _ => {
+            let bytes = value.to_le_bytes();
+            let len = 8 - value.leading_zeros() as usize / 8;
+            let mut out = vec![((len - 4) as u8) << 2 | 0b11];
+            out.extend_from_slice(&bytes[..len]);
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::codec::{scale_compact, DomainPrefix, LengthPrefix, Scale};
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::security::{self, ParamsEncoding};
+
+    #[test]
+    fn scale_compact_lengths() {
+        assert_eq!(scale_compact(0), [0x00]);
+        assert_eq!(scale_compact(1), [0x04]);
+        assert_eq!(scale_compact(63), [0xfc]);
+        assert_eq!(scale_compact(64), [0x01, 0x01]);
+        assert_eq!(scale_compact(0x3fff), [0xfd, 0xff]);
+        assert_eq!(scale_compact(0x4000), [0x02, 0x00, 0x01, 0x00]);
+        assert_eq!(scale_compact(0x4000_0000), [0x03, 0x00, 0x00, 0x00, 0x40]);
+        assert_eq!(scale_compact(u64::MAX), [&[0x13][..], &[0xff; 8]].concat());
+    }
+
+    #[test]
+    fn codec_round_trip() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let msg = b"block 7";
+        let digits = params.msg_hash_and_compute_checksum(msg);
+        let codecs = [
+            params.clone().with_codec(Arc::new(LengthPrefix)),
+            params
+                .clone()
+                .with_codec(Arc::new(DomainPrefix(b"chain-1".to_vec()))),
+            params.clone().with_codec(Arc::new(Scale)),
+        ];
+        for coded in codecs {
+            assert_ne!(coded.msg_hash_and_compute_checksum(msg), digits);
+            assert_eq!(coded.canonical_encoding(), ParamsEncoding::Custom);
+
+            let key = Key::from_seed(coded.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+            let signature = key.sign(msg).unwrap();
+            assert_eq!(signature[0], u8::from(&ParamsEncoding::Custom));
+            coded.verify(msg, &signature[1..], &key.public_key).unwrap();
+            assert_eq!(
+                params.verify(msg, &signature[1..], &key.public_key),
+                Err(WotsError::InvalidSignature)
+            );
+        }
+
+        // the domain isn't length-prefixed: only `domain || msg` is hashed
+        let domain = params
+            .clone()
+            .with_codec(Arc::new(DomainPrefix(b"ab".to_vec())));
+        assert_eq!(
+            domain.msg_hash_and_compute_checksum(b"c"),
+            params.msg_hash_and_compute_checksum(b"abc")
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::hasher::{Hasher, HasherId};
+use crate::params::{EncodingProfile, Params, WotsError, SEED_SIZE};
+use crate::security::ParamsEncoding;
+
+/// File representation of `Params`, e.g. in TOML:
+///
+/// ```toml
+/// encoding = "level3"
+/// prf_hasher = "blake2b"
+/// msg_hasher = "sha3_224"
+/// ```
+///
+/// `n` and `m` are required for `custom` and, when given, must match any other encoding;
+/// `seed_size` and `profile` default to `SEED_SIZE` and this crate's encoding. The hashers are
+/// optional; when given they must be the ones `params` is instantiated with, so a service can't
+/// silently run with other hashers than configured.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParamsConfig {
+    pub encoding: ParamsEncoding,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub m: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<EncodingProfile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prf_hasher: Option<HasherId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub msg_hasher: Option<HasherId>,
+}
+
+impl ParamsConfig {
+    /// Validates the config and instantiates it with `PRFH` and `MSGH`.
+    pub fn params<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        &self,
+    ) -> Result<Params<PRFH, MSGH>, WotsError> {
+        let hasher_matches = |configured: Option<HasherId>, id: Option<HasherId>| {
+            configured.is_none() || configured == id
+        };
+        if !hasher_matches(self.prf_hasher, PRFH::ID) || !hasher_matches(self.msg_hasher, MSGH::ID)
+        {
+            return Err(WotsError::HasherMismatch);
+        }
+
+        let params = match (self.encoding.values(), self.n, self.m) {
+            (None, Some(n), Some(m)) => Params::new_from_values(n, m),
+            (None, _, _) => Err(WotsError::InvalidConfig),
+            (Some((n, m)), config_n, config_m)
+                if config_n.unwrap_or(n) == n && config_m.unwrap_or(m) == m =>
+            {
+                Params::new(self.encoding)
+            }
+            (Some(_), _, _) => Err(WotsError::InvalidConfig),
+        }?;
+        let params = params.with_profile(self.profile.unwrap_or_default());
+        match self.seed_size {
+            Some(seed_size) => params.with_seed_size(seed_size),
+            None => Ok(params),
+        }
+    }
+}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> From<&Params<PRFH, MSGH>> for ParamsConfig {
+    fn from(params: &Params<PRFH, MSGH>) -> Self {
+        let custom = params.encoding == ParamsEncoding::Custom;
+        ParamsConfig {
+            encoding: params.encoding,
+            n: custom.then_some(params.n),
+            m: custom.then_some(params.m),
+            seed_size: (params.seed_size != SEED_SIZE).then_some(params.seed_size),
+            profile: (params.profile != EncodingProfile::default()).then_some(params.profile),
+            prf_hasher: PRFH::ID,
+            msg_hasher: MSGH::ID,
+        }
+    }
+}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> Serialize for Params<PRFH, MSGH> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ParamsConfig::from(self).serialize(serializer)
+    }
+}
+
+impl<'de, PRFH: Hasher + Clone, MSGH: Hasher + Clone> Deserialize<'de> for Params<PRFH, MSGH> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ParamsConfig::deserialize(deserializer)?
+            .params()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::ParamsConfig;
+    use crate::hasher::{Blake2bHasher, HasherId, Sha3_224Hasher, Sha3_256Hasher};
+    use crate::params::{Params, WotsError};
+    use crate::security::{self, ParamsEncoding};
+
+    type Level = Params<Blake2bHasher, Sha3_224Hasher>;
+
+    #[test]
+    fn load_toml_and_json() {
+        let params: Level = toml::from_str(
+            "encoding = \"level3\"\nprf_hasher = \"blake2b\"\nmsg_hasher = \"sha3_224\"\n",
+        )
+        .unwrap();
+        assert_eq!(params.encoding, ParamsEncoding::Level3);
+        assert_eq!((params.n, params.m, params.seed_size), (32, 24, 32));
+
+        let custom: Level =
+            serde_json::from_str(r#"{"encoding": "custom", "n": 16, "m": 20, "seed_size": 64}"#)
+                .unwrap();
+        assert_eq!(
+            (custom.encoding, custom.n, custom.m, custom.seed_size),
+            (ParamsEncoding::Custom, 16, 20, 64)
+        );
+
+        let consensus = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
+        let json = serde_json::to_string(&consensus).unwrap();
+        assert_eq!(
+            json,
+            r#"{"encoding":"consensus","prf_hasher":"blake2b","msg_hasher":"sha3_256"}"#
+        );
+        let round_trip: Params<Blake2bHasher, Sha3_256Hasher> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip.total, consensus.total);
+    }
+
+    #[test]
+    fn validate_on_load() {
+        let config = |encoding, n, m, msg_hasher| ParamsConfig {
+            encoding,
+            n,
+            m,
+            seed_size: None,
+            profile: None,
+            prf_hasher: None,
+            msg_hasher,
+        };
+        let err = |config: ParamsConfig| config.params::<Blake2bHasher, Sha3_224Hasher>().err();
+
+        assert_eq!(
+            err(config(ParamsEncoding::Level0, Some(20), None, None)),
+            None
+        );
+        assert_eq!(
+            err(config(ParamsEncoding::Level0, Some(32), None, None)),
+            Some(WotsError::InvalidConfig)
+        );
+        assert_eq!(
+            err(config(ParamsEncoding::Custom, Some(32), None, None)),
+            Some(WotsError::InvalidConfig)
+        );
+        assert_eq!(
+            err(config(
+                ParamsEncoding::Level0,
+                None,
+                None,
+                Some(HasherId::Sha3_256)
+            )),
+            Some(WotsError::HasherMismatch)
+        );
+        // consensus needs a 32 byte message hash
+        assert_eq!(
+            err(config(ParamsEncoding::Consensus, None, None, None)),
+            Some(WotsError::InvalidHasher)
+        );
+
+        assert!(serde_json::from_str::<Level>(r#"{"encoding": "level9"}"#).is_err());
+        assert!(serde_json::from_str::<Level>(r#"{"encoding": "level0", "w": 16}"#).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use chacha20poly1305::aead::{AeadInPlace, KeyInit};
+use chacha20poly1305::{Tag, XChaCha20Poly1305, XNonce};
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use rand::rngs::OsRng;
+use sha3::{Digest, Sha3_512};
+
+use crate::entropy::EntropySource;
+use crate::envelope;
+use crate::params::WotsError;
+use crate::secret::SecretBytes;
+use crate::wire::COURIER_HEADER_SIZE;
+pub use crate::wire::COURIER_VERSION;
+
+/// Size of X25519 keys
+pub const X25519_KEY_SIZE: usize = 32;
+
+const TAG_SIZE: usize = 16;
+const KDF_DOMAIN: &[u8] = b"W-OTS+ courier";
+
+/// The X25519 public key of `secret`, to hand to signers that courier envelopes to it.
+pub fn recipient_public_key(secret: &[u8; X25519_KEY_SIZE]) -> [u8; X25519_KEY_SIZE] {
+    MontgomeryPoint::mul_base_clamped(*secret).to_bytes()
+}
+
+/// Encrypts a signed envelope made by `Key::sign_attached` to the X25519 key `recipient`,
+/// sealed-box style: under a fresh ephemeral key, so the sender can't decrypt it afterwards
+/// and nothing identifies the sender. It only hides and protects the envelope in transit; the
+/// recipient still verifies the signature with `envelope::open_attached`.
+pub fn seal_envelope(
+    envelope: &[u8],
+    recipient: &[u8; X25519_KEY_SIZE],
+) -> Result<Vec<u8>, WotsError> {
+    envelope::split(envelope)?;
+    let mut ephemeral = [0u8; X25519_KEY_SIZE];
+    EntropySource::new(OsRng).fill(&mut ephemeral)?;
+    let ephemeral_pk = recipient_public_key(&ephemeral);
+    let derived = derive(&ephemeral, recipient, &ephemeral_pk, recipient);
+    ephemeral.fill(0);
+    let (key, nonce) = derived?;
+
+    let mut sealed = Vec::with_capacity(COURIER_HEADER_SIZE + envelope.len() + TAG_SIZE);
+    sealed.push(COURIER_VERSION);
+    sealed.extend_from_slice(&ephemeral_pk);
+    let mut ciphertext = envelope.to_vec();
+    let tag = XChaCha20Poly1305::new((&*key).into())
+        .encrypt_in_place_detached(XNonce::from_slice(&nonce), &sealed, &mut ciphertext)
+        .map_err(|_| WotsError::InvalidEnvelope)?;
+    sealed.extend_from_slice(&ciphertext);
+    sealed.extend_from_slice(&tag);
+    Ok(sealed)
+}
+
+/// Decrypts the output of `seal_envelope` with the recipient's X25519 `secret`, returning the
+/// signed envelope. Fails with `WotsError::CourierOpenFailure` for a wrong key or tampered data.
+pub fn open_envelope(sealed: &[u8], secret: &[u8; X25519_KEY_SIZE]) -> Result<Vec<u8>, WotsError> {
+    if sealed.len() < COURIER_HEADER_SIZE + TAG_SIZE || sealed[0] != COURIER_VERSION {
+        return Err(WotsError::InvalidCourierEnvelope);
+    }
+    let (header, body) = sealed.split_at(COURIER_HEADER_SIZE);
+    let ephemeral_pk: &[u8; X25519_KEY_SIZE] = header[1..]
+        .try_into()
+        .map_err(|_| WotsError::InvalidCourierEnvelope)?;
+    let (ciphertext, tag) = body.split_at(body.len() - TAG_SIZE);
+    let (key, nonce) = derive(
+        secret,
+        ephemeral_pk,
+        ephemeral_pk,
+        &recipient_public_key(secret),
+    )?;
+
+    let mut envelope = ciphertext.to_vec();
+    XChaCha20Poly1305::new((&*key).into())
+        .decrypt_in_place_detached(
+            XNonce::from_slice(&nonce),
+            header,
+            &mut envelope,
+            Tag::from_slice(tag),
+        )
+        .map_err(|_| WotsError::CourierOpenFailure)?;
+    envelope::split(&envelope)?;
+    Ok(envelope)
+}
+
+// derive hashes the X25519 secret shared by `secret` and `peer`, bound to both public keys,
+// into the cipher key and nonce. Peers of small order, which force a known shared secret,
+// are rejected.
+fn derive(
+    secret: &[u8; X25519_KEY_SIZE],
+    peer: &[u8; X25519_KEY_SIZE],
+    ephemeral_pk: &[u8; X25519_KEY_SIZE],
+    recipient: &[u8; X25519_KEY_SIZE],
+) -> Result<(SecretBytes, [u8; 24]), WotsError> {
+    let shared = MontgomeryPoint(*peer).mul_clamped(*secret).to_bytes();
+    if shared == [0u8; X25519_KEY_SIZE] {
+        return Err(WotsError::CourierOpenFailure);
+    }
+    let mut hasher = Sha3_512::new();
+    Digest::update(&mut hasher, KDF_DOMAIN);
+    Digest::update(&mut hasher, shared);
+    Digest::update(&mut hasher, ephemeral_pk);
+    Digest::update(&mut hasher, recipient);
+    let okm = SecretBytes::from_slice(&hasher.finalize());
+    let mut nonce = [0u8; 24];
+    nonce.copy_from_slice(&okm[32..56]);
+    Ok((SecretBytes::from_slice(&okm[..32]), nonce))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::courier::{open_envelope, recipient_public_key, seal_envelope};
+    use crate::envelope::open_attached;
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::security;
+
+    #[test]
+    fn courier_round_trip() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let envelope = key.sign_attached(b"air gapped").unwrap();
+        let secret = [9u8; 32];
+        let recipient = recipient_public_key(&secret);
+
+        let sealed = seal_envelope(&envelope, &recipient).unwrap();
+        assert_ne!(sealed, seal_envelope(&envelope, &recipient).unwrap());
+        let opened = open_envelope(&sealed, &secret).unwrap();
+        assert_eq!(opened, envelope);
+        assert_eq!(
+            open_attached(&opened, &key.public_key).unwrap(),
+            b"air gapped"
+        );
+
+        assert_eq!(
+            open_envelope(&sealed, &[8u8; 32]),
+            Err(WotsError::CourierOpenFailure)
+        );
+        let mut tampered = sealed.clone();
+        tampered[40] ^= 1;
+        assert_eq!(
+            open_envelope(&tampered, &secret),
+            Err(WotsError::CourierOpenFailure)
+        );
+        assert_eq!(
+            open_envelope(&sealed[..40], &secret),
+            Err(WotsError::InvalidCourierEnvelope)
+        );
+        // a small order recipient key would fix the shared secret
+        assert_eq!(
+            seal_envelope(&envelope, &[0u8; 32]),
+            Err(WotsError::CourierOpenFailure)
+        );
+        assert_eq!(
+            seal_envelope(b"", &recipient),
+            Err(WotsError::InvalidEnvelope)
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Base-`w` digits of message digests and their checksums, as signed by the ladders of a
+//! Winternitz one-time signature. Nothing here depends on the hashers or on `Params`, so the
+//! same helpers serve W-OTS+ and any LM-OTS or XMSS style construction built on this crate.
+//!
+//! `w` is a power of two throughout: a digest is read `log2(w)` bits at a time, most
+//! significant first.
+
+use crate::params::{MAX_W, W};
+
+/// Number of base-`w` digits needed to hold the largest checksum of `m` digits, `m * (w - 1)`.
+pub const fn checksum_len(w: usize, m: usize) -> usize {
+    let mut max = (m as u64) * (w as u64 - 1) / w as u64;
+    let mut digits = 1;
+    while max > 0 {
+        max /= w as u64;
+        digits += 1;
+    }
+    digits
+}
+
+/// Number of base-`w` digits, and so message ladders, a `len` byte digest is split into:
+/// `log2(w)` bits each, the last one padded with zero bits.
+pub const fn base_w_len(w: usize, len: usize) -> usize {
+    (8 * len).div_ceil(w.trailing_zeros() as usize)
+}
+
+/// The `base_w_len(w, digest.len())` base-`w` digits of `digest`. For `w = W` every byte is a
+/// digit.
+///
+/// Panics if `w` is not a power of two in `2..=MAX_W`.
+pub fn base_w(digest: &[u8], w: usize) -> Vec<u16> {
+    assert!(valid_base(w), "w must be a power of two in 2..=65536");
+    let mut out = Vec::with_capacity(base_w_len(w, digest.len()));
+    write_base_w(digest, w, &mut out);
+    out
+}
+
+/// Packs base-`w` `digits` into bytes, `log2(w)` bits per digit, most significant first, and
+/// zero bits after the last one up to a whole byte. Inverse of `base_w`: the digits of the
+/// packed bytes start with `digits`, and packing the digits of a digest starts with the digest.
+///
+/// Panics if `w` is not a power of two in `2..=MAX_W` or a digit is not below `w`.
+pub fn pack_base_w(digits: &[u16], w: usize) -> Vec<u8> {
+    assert!(valid_base(w), "w must be a power of two in 2..=65536");
+    let bits = w.trailing_zeros();
+    let mut out = Vec::with_capacity((digits.len() * bits as usize).div_ceil(8));
+    let mut acc = 0u32;
+    let mut held = 0;
+    for &digit in digits {
+        assert!((digit as usize) < w, "digit out of range for w");
+        acc = acc << bits | digit as u32;
+        held += bits;
+        while held >= 8 {
+            held -= 8;
+            out.push((acc >> held) as u8);
+        }
+        acc &= (1 << held) - 1;
+    }
+    if held > 0 {
+        out.push((acc << (8 - held)) as u8);
+    }
+    out
+}
+
+/// Checksum of the base-`W` digits `msg`, as signed by the checksum ladders; the `w = W` case
+/// of `checksum_digits`.
+pub fn checksum(msg: &[u8]) -> Vec<u8> {
+    checksum_digits(msg, W)
+}
+
+/// Checksum of the base-`w` `digits`: the sum of `w - 1 - digit`, written as
+/// `checksum_len(w, digits.len())` base-`w` digits, most significant first. No digits have
+/// the one digit checksum `[0]`.
+///
+/// Panics if `w` is not in `2..=256` or a digit is not below `w`.
+pub fn checksum_digits(digits: &[u8], w: usize) -> Vec<u8> {
+    assert!((2..=W).contains(&w), "w must be in 2..=256");
+    let mut out = Vec::with_capacity(checksum_len(w, digits.len()));
+    write_checksum(digits.iter().map(|&digit| digit as u16), w, &mut out);
+    out.into_iter().map(|digit| digit as u8).collect()
+}
+
+/// Same as `checksum_digits`, for digits of any `w` up to `MAX_W`.
+///
+/// Panics if `w` is not in `2..=MAX_W` or a digit is not below `w`.
+pub fn wide_checksum(digits: &[u16], w: usize) -> Vec<u16> {
+    let mut out = Vec::with_capacity(checksum_len(w, digits.len()));
+    write_checksum(digits.iter().copied(), w, &mut out);
+    out
+}
+
+// write_checksum appends the base-`w` checksum digits of `digits` to `out`. The sum is kept in
+// a u64, which cannot overflow for any slice length.
+pub(crate) fn write_checksum(
+    digits: impl ExactSizeIterator<Item = u16>,
+    w: usize,
+    out: &mut impl Extend<u16>,
+) {
+    assert!((2..=MAX_W).contains(&w), "w must be in 2..=65536");
+    let count = checksum_len(w, digits.len());
+    let w = w as u64;
+    let mut sum = 0u64;
+    for digit in digits {
+        assert!((digit as u64) < w, "digit out of range for w");
+        sum += w - 1 - digit as u64;
+    }
+
+    let mut place = w.pow(count as u32 - 1);
+    for _ in 0..count {
+        out.extend(core::iter::once((sum / place % w) as u16));
+        place /= w;
+    }
+}
+
+// write_base_w appends the `base_w_len(w, digest.len())` digits of `digest` to `out`, reading
+// `log2(w)` bits at a time, most significant first.
+pub(crate) fn write_base_w(digest: &[u8], w: usize, out: &mut impl Extend<u16>) {
+    let bits = w.trailing_zeros();
+    let mut acc = 0u32;
+    let mut held = 0;
+    for &byte in digest {
+        acc = acc << 8 | byte as u32;
+        held += 8;
+        while held >= bits {
+            held -= bits;
+            out.extend(core::iter::once((acc >> held) as u16 & (w - 1) as u16));
+        }
+        acc &= (1 << held) - 1;
+    }
+    if held > 0 {
+        out.extend(core::iter::once((acc << (bits - held)) as u16));
+    }
+}
+
+// valid_base accepts the `w` that `base_w` and `pack_base_w` can split bytes by.
+const fn valid_base(w: usize) -> bool {
+    w.is_power_of_two() && 2 <= w && w <= MAX_W
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::digits::{
+        base_w, base_w_len, checksum_digits, checksum_len, pack_base_w, wide_checksum,
+    };
+    use crate::params::{MAX_W, W};
+
+    // every power of two `base_w` accepts
+    fn bases() -> impl Iterator<Item = usize> {
+        (1..=MAX_W.trailing_zeros()).map(|bits| 1 << bits)
+    }
+
+    #[test]
+    fn base_w_every_byte() {
+        for w in bases() {
+            for byte in 0..=255u8 {
+                let digits = base_w(&[byte], w);
+                assert_eq!(digits.len(), base_w_len(w, 1));
+                assert!(digits.iter().all(|&d| (d as usize) < w));
+                assert_eq!(pack_base_w(&digits, w)[0], byte, "w {}", w);
+            }
+        }
+        assert_eq!(base_w(&[0xb4], 4), [2, 3, 1, 0]);
+        assert_eq!(base_w(&[0xb4, 0x01], 16), [0xb, 0x4, 0x0, 0x1]);
+        assert_eq!(base_w(&[0xb4, 0x01], 32), [0x16, 0x10, 0x00, 0x10]);
+        assert_eq!(base_w(&[0xb4, 0x01], W), [0xb4, 0x01]);
+        assert_eq!(base_w(&[0xb4, 0x01], MAX_W), [0xb401]);
+        assert_eq!(base_w(&[0xb4], MAX_W), [0xb400]);
+        assert!(base_w(&[], 16).is_empty());
+    }
+
+    #[test]
+    fn pack_base_w_round_trip() {
+        let digest: Vec<u8> = (0..40u8).map(|i| i.wrapping_mul(97)).collect();
+        for w in bases() {
+            for len in 0..digest.len() {
+                let digits = base_w(&digest[..len], w);
+                let packed = pack_base_w(&digits, w);
+                assert_eq!(&packed[..len], &digest[..len], "w {} len {}", w, len);
+                assert_eq!(base_w(&packed, w)[..digits.len()], digits[..]);
+            }
+        }
+        assert_eq!(pack_base_w(&[1, 1, 1], 8), [0x24, 0x80]);
+    }
+
+    #[test]
+    fn checksum_every_digit_vector() {
+        // every message of 3 base-4, 2 base-16 and 8 base-2 digits, against a plain sum
+        for (w, m) in [(4usize, 3usize), (16, 2), (2, 8)] {
+            for n in 0..w.pow(m as u32) {
+                let digits: Vec<u16> = (0..m).map(|i| (n / w.pow(i as u32) % w) as u16).collect();
+                let sum: usize = digits.iter().map(|&d| w - 1 - d as usize).sum();
+                let checksum = wide_checksum(&digits, w);
+                assert_eq!(checksum.len(), checksum_len(w, m));
+                let value = checksum.iter().fold(0, |acc, &d| acc * w + d as usize);
+                assert_eq!(value, sum, "w {} digits {:?}", w, digits);
+
+                let narrow: Vec<u8> = digits.iter().map(|&d| d as u8).collect();
+                let narrow_checksum: Vec<u16> = checksum_digits(&narrow, w)
+                    .into_iter()
+                    .map(u16::from)
+                    .collect();
+                assert_eq!(narrow_checksum, checksum);
+            }
+        }
+        assert_eq!(wide_checksum(&[0; 2], MAX_W), [1, 0xfffe]);
+    }
+
+    #[test]
+    #[should_panic(expected = "power of two")]
+    fn base_w_rejects_w() {
+        base_w(&[1], 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "digit out of range")]
+    fn pack_base_w_rejects_digits() {
+        pack_base_w(&[16], 16);
+    }
+}
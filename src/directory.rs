@@ -0,0 +1,353 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::bundle::{PublicBundle, FINGERPRINT_SIZE};
+use crate::hasher::Hasher;
+use crate::keys::{Key, KeyState, PublicKey, PK_SIZE};
+use crate::params::{ChecksumOrder, EncodingProfile, Params, WotsError, MAX_SEED_SIZE, SEED_SIZE};
+use crate::secret::SecretSeed;
+use crate::security::ParamsEncoding;
+use crate::wire::RECORD_HEADER_SIZE;
+pub use crate::wire::RECORD_VERSION;
+
+/// A stored key with its used-state and metadata.
+#[derive(Debug, Clone)]
+pub struct KeyRecord {
+    pub encoding: ParamsEncoding,
+    pub profile: EncodingProfile,
+    /// set by `KeyDirectory::mark_used`, never cleared
+    pub used: bool,
+    pub seed: SecretSeed,
+    pub p_seed: [u8; SEED_SIZE],
+    pub public_key: PublicKey,
+    /// opaque to the directory, e.g. a label or creation time
+    pub metadata: Vec<u8>,
+}
+
+impl KeyRecord {
+    /// Fingerprint of the key's public bundle; see `bundle::PublicBundle`.
+    pub fn fingerprint(&self) -> [u8; FINGERPRINT_SIZE] {
+        PublicBundle::new(self.encoding, self.p_seed, *self.public_key.as_bytes())
+            .expect("records are never custom")
+            .fingerprint()
+    }
+
+    /// Rebuilds the key. Fails with `WotsError::InvalidKeyRecord` if it doesn't derive the
+    /// stored public key, e.g. when instantiated with other hashers than it was stored with.
+    pub fn key<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        &self,
+    ) -> Result<Key<PRFH, MSGH>, WotsError> {
+        let key = Key::from_secret_seed(self.params()?, self.seed.clone(), self.p_seed)?;
+        if key.verifying_key()? != self.public_key {
+            return Err(WotsError::InvalidKeyRecord);
+        }
+        Ok(key)
+    }
+
+    // params rebuilds the params the key is stored with.
+    fn params<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        &self,
+    ) -> Result<Params<PRFH, MSGH>, WotsError> {
+        Params::new(self.encoding)?
+            .with_profile(self.profile)
+            .with_seed_size(self.seed.len())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            RECORD_HEADER_SIZE + self.seed.len() + SEED_SIZE + PK_SIZE + self.metadata.len(),
+        );
+        bytes.push(RECORD_VERSION);
+        bytes.push(u8::from(&self.encoding));
+        // bit 0 is the checksum order, bit 1 `bind_params`
+        let order = match self.profile.checksum_order {
+            ChecksumOrder::BigEndian => 0,
+            ChecksumOrder::LittleEndian => 1,
+        };
+        bytes.push(order | (self.profile.bind_params as u8) << 1);
+        bytes.push(self.used as u8);
+        bytes.push(self.seed.len() as u8);
+        bytes.extend_from_slice(&self.seed);
+        bytes.extend_from_slice(&self.p_seed);
+        bytes.extend_from_slice(self.public_key.as_bytes());
+        bytes.extend_from_slice(&self.metadata);
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, WotsError> {
+        if bytes.len() < RECORD_HEADER_SIZE || bytes[0] != RECORD_VERSION {
+            return Err(WotsError::InvalidKeyRecord);
+        }
+        let encoding =
+            ParamsEncoding::try_from(bytes[1]).map_err(|_| WotsError::InvalidKeyRecord)?;
+        if bytes[2] > 3 {
+            return Err(WotsError::InvalidKeyRecord);
+        }
+        let checksum_order = match bytes[2] & 1 {
+            0 => ChecksumOrder::BigEndian,
+            _ => ChecksumOrder::LittleEndian,
+        };
+        let used = match bytes[3] {
+            0 => false,
+            1 => true,
+            _ => return Err(WotsError::InvalidKeyRecord),
+        };
+        let seed_size = bytes[4] as usize;
+        let metadata_start = RECORD_HEADER_SIZE + seed_size + SEED_SIZE + PK_SIZE;
+        if encoding.values().is_none()
+            || !(SEED_SIZE..=MAX_SEED_SIZE).contains(&seed_size)
+            || bytes.len() < metadata_start
+        {
+            return Err(WotsError::InvalidKeyRecord);
+        }
+
+        let (seed, rest) = bytes[RECORD_HEADER_SIZE..].split_at(seed_size);
+        let (p_seed, rest) = rest.split_at(SEED_SIZE);
+        let (public_key, metadata) = rest.split_at(PK_SIZE);
+        Ok(KeyRecord {
+            encoding,
+            profile: EncodingProfile {
+                checksum_order,
+                bind_params: bytes[2] & 2 != 0,
+            },
+            used,
+            seed: SecretSeed::from_slice(seed)?,
+            p_seed: p_seed.try_into().expect("split at SEED_SIZE"),
+            public_key: PublicKey::try_from(public_key)?,
+            metadata: metadata.to_vec(),
+        })
+    }
+}
+
+/// File based store of one-time keys, one record per file named by the hex of the key's
+/// fingerprint.
+///
+/// Records hold the secret seed in the clear, so the directory must be protected like any
+/// other key material. Records are replaced atomically, but the directory takes no locks: it
+/// must only be used by one process at a time.
+#[derive(Debug, Clone)]
+pub struct KeyDirectory {
+    root: PathBuf,
+}
+
+impl KeyDirectory {
+    /// Opens the directory at `root`, creating it if needed.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, WotsError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root).map_err(io_error)?;
+        Ok(KeyDirectory { root })
+    }
+
+    /// Stores a new key and returns its fingerprint. A key that has signed already is stored
+    /// used, so it can never be handed out to sign again. Fails with
+    /// `WotsError::SeedUnavailable` for keys derived through a `SeedProvider`, with
+    /// `WotsError::InvalidParamsEncodingType` for params a record can't restore (`Custom`
+    /// ones, or ones with a codec or another aggregation hasher), and with
+    /// `WotsError::KeyExists` if the key is stored already, so its used-state is never reset.
+    pub fn insert<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        &self,
+        key: &Key<PRFH, MSGH>,
+        metadata: &[u8],
+    ) -> Result<[u8; FINGERPRINT_SIZE], WotsError> {
+        let seed = key.seed.clone().ok_or(WotsError::SeedUnavailable)?;
+        let params = key.params();
+        if params.encoding == ParamsEncoding::Custom {
+            return Err(WotsError::InvalidParamsEncodingType);
+        }
+        let record = KeyRecord {
+            encoding: params.encoding,
+            profile: params.profile,
+            used: key.state() >= KeyState::Signed,
+            seed,
+            p_seed: key.p_seed,
+            public_key: key.verifying_key()?,
+            metadata: metadata.to_vec(),
+        };
+        // the record must restore the key as it signs
+        if record.params::<PRFH, MSGH>()? != *params {
+            return Err(WotsError::InvalidParamsEncodingType);
+        }
+        let fingerprint = record.fingerprint();
+        let tmp = self.write_tmp(&fingerprint, &record)?;
+        // linking fails if the record exists, where a rename would replace it
+        let linked = fs::hard_link(&tmp, self.path(&fingerprint));
+        let _ = fs::remove_file(&tmp);
+        match linked {
+            Ok(()) => Ok(fingerprint),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => Err(WotsError::KeyExists),
+            Err(err) => Err(io_error(err)),
+        }
+    }
+
+    pub fn get(
+        &self,
+        fingerprint: &[u8; FINGERPRINT_SIZE],
+    ) -> Result<Option<KeyRecord>, WotsError> {
+        let mut bytes = match fs::read(self.path(fingerprint)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(io_error(err)),
+        };
+        let record = KeyRecord::from_bytes(&bytes);
+        bytes.fill(0);
+        let record = record?;
+        if &record.fingerprint() != fingerprint {
+            return Err(WotsError::InvalidKeyRecord);
+        }
+        Ok(Some(record))
+    }
+
+    /// Marks the key used and returns its record, to sign with only once this has returned.
+    /// Fails with `WotsError::KeyAlreadyUsed` on every later call.
+    pub fn mark_used(&self, fingerprint: &[u8; FINGERPRINT_SIZE]) -> Result<KeyRecord, WotsError> {
+        let mut record = self.get(fingerprint)?.ok_or(WotsError::KeyNotFound)?;
+        if record.used {
+            return Err(WotsError::KeyAlreadyUsed);
+        }
+        record.used = true;
+        self.replace(fingerprint, &record)?;
+        Ok(record)
+    }
+
+    pub fn set_metadata(
+        &self,
+        fingerprint: &[u8; FINGERPRINT_SIZE],
+        metadata: &[u8],
+    ) -> Result<(), WotsError> {
+        let mut record = self.get(fingerprint)?.ok_or(WotsError::KeyNotFound)?;
+        record.metadata = metadata.to_vec();
+        self.replace(fingerprint, &record)
+    }
+
+    /// Fingerprints of all stored keys, in no particular order.
+    pub fn fingerprints(&self) -> Result<Vec<[u8; FINGERPRINT_SIZE]>, WotsError> {
+        let mut fingerprints = Vec::new();
+        for entry in fs::read_dir(&self.root).map_err(io_error)? {
+            let name = entry.map_err(io_error)?.file_name();
+            let mut fingerprint = [0u8; FINGERPRINT_SIZE];
+            if let Some(name) = name.to_str() {
+                if hex::decode_to_slice(name, &mut fingerprint).is_ok() {
+                    fingerprints.push(fingerprint);
+                }
+            }
+        }
+        Ok(fingerprints)
+    }
+
+    fn path(&self, fingerprint: &[u8; FINGERPRINT_SIZE]) -> PathBuf {
+        self.root.join(hex::encode(fingerprint))
+    }
+
+    fn replace(
+        &self,
+        fingerprint: &[u8; FINGERPRINT_SIZE],
+        record: &KeyRecord,
+    ) -> Result<(), WotsError> {
+        let tmp = self.write_tmp(fingerprint, record)?;
+        fs::rename(&tmp, self.path(fingerprint)).map_err(io_error)
+    }
+
+    // write_tmp writes and syncs `record` next to its final path.
+    fn write_tmp(
+        &self,
+        fingerprint: &[u8; FINGERPRINT_SIZE],
+        record: &KeyRecord,
+    ) -> Result<PathBuf, WotsError> {
+        let tmp = self.path(fingerprint).with_extension("tmp");
+        let mut bytes = record.to_bytes();
+        let written = fs::File::create(&tmp).and_then(|mut file| {
+            file.write_all(&bytes)?;
+            file.sync_all()
+        });
+        bytes.fill(0);
+        written.map_err(io_error)?;
+        Ok(tmp)
+    }
+}
+
+fn io_error(_: io::Error) -> WotsError {
+    WotsError::DirectoryIo
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::Arc;
+
+    use crate::codec::LengthPrefix;
+    use crate::directory::KeyDirectory;
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::security;
+
+    #[test]
+    fn store_and_use_keys() {
+        let root = std::env::temp_dir().join(format!("wots-directory-{}", std::process::id()));
+        let directory = KeyDirectory::open(&root).unwrap();
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let fingerprint = directory.insert(&key, b"first").unwrap();
+        assert_eq!(directory.insert(&key, b"again"), Err(WotsError::KeyExists));
+        assert_eq!(directory.fingerprints().unwrap(), vec![fingerprint]);
+
+        directory.set_metadata(&fingerprint, b"renamed").unwrap();
+        let record = directory.mark_used(&fingerprint).unwrap();
+        assert_eq!(record.metadata, b"renamed");
+        let loaded = record.key::<Blake2bHasher, Sha3_224Hasher>().unwrap();
+        assert_eq!(loaded.sign(b"msg").unwrap(), key.sign(b"msg").unwrap());
+        assert!(directory.get(&fingerprint).unwrap().unwrap().used);
+        assert_eq!(
+            directory.mark_used(&fingerprint).err(),
+            Some(WotsError::KeyAlreadyUsed)
+        );
+        assert_eq!(
+            directory.mark_used(&[0u8; 32]).err(),
+            Some(WotsError::KeyNotFound)
+        );
+
+        let provider_key = Key::from_seed_provider(
+            params,
+            &crate::secret::SecretSeed::new([3u8; SEED_SIZE]),
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        assert_eq!(
+            directory.insert(&provider_key, b""),
+            Err(WotsError::SeedUnavailable)
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn insert_checks_key() {
+        let root = std::env::temp_dir().join(format!("wots-insert-{}", std::process::id()));
+        let directory = KeyDirectory::open(&root).unwrap();
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+
+        // a key that signed is stored used, so it can't be reloaded to sign again
+        let signed = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        signed.sign(b"msg").unwrap();
+        let fingerprint = directory.insert(&signed, b"").unwrap();
+        assert!(directory.get(&fingerprint).unwrap().unwrap().used);
+        assert_eq!(
+            directory.mark_used(&fingerprint).err(),
+            Some(WotsError::KeyAlreadyUsed)
+        );
+
+        // a record can't hold a codec, so it would restore a key signing other messages
+        let coded = Key::from_seed(
+            params.with_codec(Arc::new(LengthPrefix)),
+            [3u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        assert_eq!(
+            directory.insert(&coded, b""),
+            Err(WotsError::InvalidParamsEncodingType)
+        );
+        assert_eq!(directory.fingerprints().unwrap(), vec![fingerprint]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
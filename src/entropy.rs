@@ -0,0 +1,160 @@
+use rand::RngCore;
+
+use crate::params::WotsError;
+
+/// Longest run of identical bytes a healthy source may produce in one draw. A run of 8 or more
+/// in a 32-byte draw of uniform bytes happens with probability below 2^-50.
+pub const REPETITION_CUTOFF: usize = 8;
+
+/// Smallest draw the stuck-bit test is applied to; shorter draws are too likely to share a bit
+/// by chance.
+pub const STUCK_BIT_MIN_LEN: usize = 32;
+
+/// Wraps an RNG with continuous health tests on every draw, in the spirit of the SP 800-90B
+/// repetition count test. A draw fails with `WotsError::EntropyFailure` when it
+/// - contains a run of `REPETITION_CUTOFF` identical bytes,
+/// - has a bit position stuck at 0 or 1 in every byte (draws of at least `STUCK_BIT_MIN_LEN`
+///   bytes), or
+/// - starts with the same bytes as the previous draw.
+///
+/// A failed source stays failed, as a stuck RNG should not be retried into producing a seed.
+pub struct EntropySource<R: RngCore> {
+    rng: R,
+    // first bytes of the previous draw
+    last: Option<[u8; 16]>,
+    failed: bool,
+}
+
+impl<R: RngCore> EntropySource<R> {
+    pub fn new(rng: R) -> Self {
+        EntropySource {
+            rng,
+            last: None,
+            failed: false,
+        }
+    }
+
+    /// Startup self-test: draws and checks `STUCK_BIT_MIN_LEN` bytes twice, for deployments
+    /// required to test the RNG before first use.
+    pub fn self_test(&mut self) -> Result<(), WotsError> {
+        let mut sample = [0u8; STUCK_BIT_MIN_LEN];
+        self.fill(&mut sample)?;
+        self.fill(&mut sample)
+    }
+
+    /// Fills `out` from the RNG, failing if the draw doesn't pass the health tests.
+    pub fn fill(&mut self, out: &mut [u8]) -> Result<(), WotsError> {
+        if self.failed {
+            return Err(WotsError::EntropyFailure);
+        }
+        self.rng
+            .try_fill_bytes(out)
+            .map_err(|_| WotsError::EntropyFailure)?;
+
+        let mut prefix = [0u8; 16];
+        let len = out.len().min(prefix.len());
+        prefix[..len].copy_from_slice(&out[..len]);
+        let repeated = len == prefix.len() && self.last == Some(prefix);
+        self.last = Some(prefix);
+
+        if repeated || longest_run(out) >= REPETITION_CUTOFF || stuck_bits(out) {
+            self.failed = true;
+            out.fill(0);
+            return Err(WotsError::EntropyFailure);
+        }
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+fn longest_run(bytes: &[u8]) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut prev = None;
+    for &b in bytes {
+        run = if prev == Some(b) { run + 1 } else { 1 };
+        prev = Some(b);
+        longest = longest.max(run);
+    }
+    longest
+}
+
+fn stuck_bits(bytes: &[u8]) -> bool {
+    if bytes.len() < STUCK_BIT_MIN_LEN {
+        return false;
+    }
+    let (any, all) = bytes
+        .iter()
+        .fold((0u8, 0xffu8), |(any, all), &b| (any | b, all & b));
+    any != 0xff || all != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::mock::StepRng;
+    use rand::rngs::OsRng;
+
+    use crate::entropy::EntropySource;
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{WotsError, MAX_SEED_SIZE};
+    use crate::security;
+
+    #[test]
+    fn entropy_source() {
+        let mut entropy = EntropySource::new(OsRng);
+        entropy.self_test().unwrap();
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        Key::from_entropy(params.clone(), &mut entropy).unwrap();
+        let mut oversized = params.clone();
+        oversized.seed_size = MAX_SEED_SIZE + 1;
+        assert_eq!(
+            Key::from_entropy(oversized, &mut entropy).err(),
+            Some(WotsError::InconsistentParams)
+        );
+
+        // stuck at zero
+        let mut stuck = EntropySource::new(StepRng::new(0, 0));
+        assert_eq!(stuck.self_test(), Err(WotsError::EntropyFailure));
+        // a counter has long runs of zero bytes and its high bits never set
+        let mut counter = EntropySource::new(StepRng::new(1, 1));
+        assert_eq!(
+            Key::from_entropy(params, &mut counter).err(),
+            Some(WotsError::EntropyFailure)
+        );
+        // failures are sticky
+        let mut buf = [0u8; 4];
+        assert_eq!(counter.fill(&mut buf), Err(WotsError::EntropyFailure));
+    }
+
+    #[test]
+    fn repeated_draws() {
+        // the same 16 bytes over and over pass the per-draw tests but not the comparison
+        struct Replay;
+        impl rand::RngCore for Replay {
+            fn next_u32(&mut self) -> u32 {
+                unimplemented!()
+            }
+            fn next_u64(&mut self) -> u64 {
+                unimplemented!()
+            }
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                for (i, b) in dest.iter_mut().enumerate() {
+                    *b = (i as u8).wrapping_mul(37) ^ 0x5a;
+                }
+            }
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+        let mut entropy = EntropySource::new(Replay);
+        let mut buf = [0u8; 16];
+        entropy.fill(&mut buf).unwrap();
+        assert_eq!(entropy.fill(&mut buf), Err(WotsError::EntropyFailure));
+        assert_eq!(buf, [0u8; 16]);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::params::{WotsError, MAX_MSG_SIZE};
+use crate::security;
+use crate::wire::ENVELOPE_HEADER_SIZE;
+pub use crate::wire::ENVELOPE_VERSION;
+
+// with_message allocates an envelope for `msg` and a signature of `signature_size` bytes,
+// returning it with the offset the signature goes at.
+pub(crate) fn with_message(
+    msg: &[u8],
+    signature_size: usize,
+) -> Result<(Vec<u8>, usize), WotsError> {
+    if msg.len() > MAX_MSG_SIZE {
+        return Err(WotsError::InvalidMessageSize);
+    }
+
+    let sig_start = ENVELOPE_HEADER_SIZE + msg.len();
+    let mut envelope = vec![0u8; sig_start + signature_size];
+    envelope[0] = ENVELOPE_VERSION;
+    envelope[1] = msg.len() as u8;
+    envelope[ENVELOPE_HEADER_SIZE..sig_start].copy_from_slice(msg);
+    Ok((envelope, sig_start))
+}
+
+/// Verifies an envelope made by `Key::sign_attached` against `public_key` and returns the
+/// message it carries.
+pub fn open_attached(envelope: &[u8], public_key: &[u8]) -> Result<Vec<u8>, WotsError> {
+    let (msg, signature) = split(envelope)?;
+    security::verify(msg, signature, public_key)?;
+    Ok(msg.to_vec())
+}
+
+// split checks the envelope header and returns the message and signature it holds.
+pub(crate) fn split(envelope: &[u8]) -> Result<(&[u8], &[u8]), WotsError> {
+    if envelope.len() < ENVELOPE_HEADER_SIZE {
+        return Err(WotsError::InvalidEnvelope);
+    }
+    if envelope[0] != ENVELOPE_VERSION {
+        return Err(WotsError::UnsupportedEnvelopeVersion);
+    }
+    let msg_len = envelope[1] as usize;
+    if msg_len > MAX_MSG_SIZE || envelope.len() < ENVELOPE_HEADER_SIZE + msg_len {
+        return Err(WotsError::InvalidEnvelope);
+    }
+    Ok(envelope[ENVELOPE_HEADER_SIZE..].split_at(msg_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::envelope::{open_attached, ENVELOPE_VERSION};
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::security;
+
+    #[test]
+    fn sign_open_attached() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let other = Key::from_seed(
+            security::level_0_params::<Blake2bHasher, Sha3_224Hasher>(),
+            [3u8; SEED_SIZE],
+            [4u8; SEED_SIZE],
+        )
+        .unwrap();
+        let msg = b"XX NETWORK";
+
+        let envelope = key.sign_attached(msg).unwrap();
+        assert_eq!(envelope[0], ENVELOPE_VERSION);
+        assert_eq!(&envelope[2 + msg.len()..], &key.sign(msg).unwrap()[..]);
+        assert_eq!(open_attached(&envelope, &key.public_key).unwrap(), msg);
+
+        assert_eq!(
+            open_attached(&envelope, &other.public_key),
+            Err(WotsError::InvalidSignature)
+        );
+
+        let mut tampered = envelope.clone();
+        tampered[2] ^= 1;
+        assert_eq!(
+            open_attached(&tampered, &key.public_key),
+            Err(WotsError::InvalidSignature)
+        );
+
+        let mut versioned = envelope.clone();
+        versioned[0] = 2;
+        assert_eq!(
+            open_attached(&versioned, &key.public_key),
+            Err(WotsError::UnsupportedEnvelopeVersion)
+        );
+
+        for len in [0, 1, 2 + msg.len() - 1] {
+            assert_eq!(
+                open_attached(&envelope[..len], &key.public_key),
+                Err(WotsError::InvalidEnvelope),
+                "truncated to {}",
+                len
+            );
+        }
+        assert_eq!(
+            open_attached(&envelope[..envelope.len() - 1], &key.public_key),
+            Err(WotsError::InvalidSignatureSize)
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Fixed key pairs, one per predefined encoding, shared by the benches and integration tests
+//! so timings and outputs of different crate versions are measured on the same keys. The
+//! seeds are public: nothing here is fit for production keys.
+
+use crate::hasher::{Blake2bHasher, Hasher, Sha3_224Hasher, Sha3_256Hasher};
+use crate::keys::Key;
+use crate::params::{Params, WotsError, SEED_SIZE};
+use crate::security::{self, ParamsEncoding};
+use crate::traits::WotsSigner;
+
+/// Message the fixtures are signed over in benches and tests.
+pub const FIXTURE_MSG: &[u8] = b"XX NETWORK";
+
+/// Seeds of the fixed key pair of `encoding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fixture {
+    pub encoding: ParamsEncoding,
+    pub seed: [u8; SEED_SIZE],
+    pub p_seed: [u8; SEED_SIZE],
+}
+
+/// The fixture of every predefined encoding, weakest first. Never change a seed here: the
+/// public keys they derive are pinned by the tests.
+pub const FIXTURES: [Fixture; 5] = [
+    Fixture {
+        encoding: ParamsEncoding::Level0,
+        seed: [0x10; SEED_SIZE],
+        p_seed: [0x11; SEED_SIZE],
+    },
+    Fixture {
+        encoding: ParamsEncoding::Level1,
+        seed: [0x20; SEED_SIZE],
+        p_seed: [0x21; SEED_SIZE],
+    },
+    Fixture {
+        encoding: ParamsEncoding::Level2,
+        seed: [0x30; SEED_SIZE],
+        p_seed: [0x31; SEED_SIZE],
+    },
+    Fixture {
+        encoding: ParamsEncoding::Level3,
+        seed: [0x40; SEED_SIZE],
+        p_seed: [0x41; SEED_SIZE],
+    },
+    Fixture {
+        encoding: ParamsEncoding::Consensus,
+        seed: [0x50; SEED_SIZE],
+        p_seed: [0x51; SEED_SIZE],
+    },
+];
+
+impl Fixture {
+    /// Fixture of `encoding`; `None` for `Custom`.
+    pub fn of(encoding: ParamsEncoding) -> Option<Fixture> {
+        FIXTURES
+            .iter()
+            .find(|fixture| fixture.encoding == encoding)
+            .copied()
+    }
+
+    /// Key pair of the fixture under `params`, which must have the fixture's encoding.
+    pub fn key<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        &self,
+        params: Params<PRFH, MSGH>,
+    ) -> Result<Key<PRFH, MSGH>, WotsError> {
+        if params.encoding != self.encoding {
+            return Err(WotsError::InvalidParamsEncodingType);
+        }
+        Key::from_seed(params, self.seed, self.p_seed)
+    }
+
+    /// Key pair of the fixture with the hashers `security::verify` dispatches its encoding to.
+    pub fn signer(&self) -> Box<dyn WotsSigner> {
+        type Level = Params<Blake2bHasher, Sha3_224Hasher>;
+        let signer: Result<Box<dyn WotsSigner>, WotsError> = match self.encoding {
+            ParamsEncoding::Consensus => self
+                .key(security::consensus_params::<Blake2bHasher, Sha3_256Hasher>())
+                .map(|key| Box::new(key) as Box<dyn WotsSigner>),
+            encoding => Level::new(encoding)
+                .and_then(|params| self.key(params))
+                .map(|key| Box::new(key) as Box<dyn WotsSigner>),
+        };
+        signer.expect("fixtures have predefined encodings")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{Fixture, FIXTURES, FIXTURE_MSG};
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::params::WotsError;
+    use crate::security::{self, ParamsEncoding};
+
+    // public keys of FIXTURES, in order
+    const PUBLIC_KEYS: [&str; 5] = [
+        "fd8a32720c72eb8cb4f24afb21d72e6928b83cc79995ff347be04afdeb1365fb",
+        "563108f5f31b0bae2a170b3a14bab2ffa634b5fb25526d2ae65c8db4a2191a47",
+        "432c5f771ebd70a866d680bf5093e9e3b833d2dafe53f6f9f17571f5f83c9877",
+        "9427c065295e2c12b8e1f80aaf93aa729e1b65956653e4176e7e76603a18eb0c",
+        "33534cda3633d0f9cc4ee15f4a8923ab821126fbac850ce82c3f55980b602d7c",
+    ];
+
+    #[test]
+    fn fixtures() {
+        assert!(FIXTURES
+            .iter()
+            .zip(&FIXTURES[1..])
+            .all(|(a, b)| a.encoding < b.encoding));
+        assert_eq!(Fixture::of(ParamsEncoding::Custom), None);
+
+        for (fixture, expected) in FIXTURES.iter().zip(PUBLIC_KEYS) {
+            assert_eq!(Fixture::of(fixture.encoding), Some(*fixture));
+            let signer = fixture.signer();
+            let public_key = signer.public_key().unwrap();
+            assert_eq!(
+                hex::encode(public_key.as_bytes()),
+                expected,
+                "{}",
+                fixture.encoding
+            );
+            let signature = signer.sign(FIXTURE_MSG).unwrap();
+            security::verify(FIXTURE_MSG, &signature, public_key.as_bytes()).unwrap();
+        }
+
+        let level1 = Fixture::of(ParamsEncoding::Level1).unwrap();
+        let res = level1.key(security::level_0_params::<Blake2bHasher, Sha3_224Hasher>());
+        assert_eq!(res.err(), Some(WotsError::InvalidParamsEncodingType));
+    }
+}
@@ -1,36 +1,80 @@
-use blake2::digest::{Update, VariableOutput};
-use blake2::Blake2bVar;
+use blake2::digest::consts::U32;
+use blake2::Blake2b;
 use sha3::{Digest, Sha3_224, Sha3_256};
 
+/// Names a built-in hasher in configuration files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum HasherId {
+    Blake2b,
+    Sha3_224,
+    Sha3_256,
+}
+
+impl HasherId {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            HasherId::Blake2b => "blake2b-256",
+            HasherId::Sha3_224 => "sha3-224",
+            HasherId::Sha3_256 => "sha3-256",
+        }
+    }
+}
+
 pub trait Hasher {
+    /// Output size in bytes
+    const SIZE: usize;
+    /// Configuration name; `None` for hashers outside this crate
+    const ID: Option<HasherId> = None;
+    /// Name hashed into every message under `EncodingProfile::bind_params`, `HasherId::name`
+    /// for the built-in hashers. Hashers outside this crate must set a name of their own, not
+    /// shared with any other hasher, to sign or verify with `bind_params`.
+    const NAME: Option<&'static str> = match Self::ID {
+        Some(id) => Some(id.name()),
+        None => None,
+    };
+
     fn new() -> Self;
-    fn write(&mut self, data: Vec<u8>);
+    fn write(&mut self, data: &[u8]);
+    /// Writes the first `out.len()` bytes of the digest, at most `SIZE`, into `out`. Never
+    /// panics, whatever the size of `out`.
     fn sum(self, out: &mut [u8]);
-    fn size() -> usize;
+    fn size() -> usize {
+        Self::SIZE
+    }
+}
+
+// copy_prefix fills as much of `out` as `digest` covers.
+fn copy_prefix(digest: &[u8], out: &mut [u8]) {
+    let len = digest.len().min(out.len());
+    out[..len].copy_from_slice(&digest[..len]);
 }
 
 #[derive(Debug, Clone)]
 pub struct Blake2bHasher {
-    hasher: blake2::Blake2bVar,
+    hasher: Blake2b<U32>,
 }
 
 impl Hasher for Blake2bHasher {
+    const SIZE: usize = 32;
+    const ID: Option<HasherId> = Some(HasherId::Blake2b);
+
     fn new() -> Blake2bHasher {
         Blake2bHasher {
-            hasher: Blake2bVar::new(32).expect("instantiating blake2b should not fail"),
+            hasher: Blake2b::new(),
         }
     }
 
-    fn size() -> usize {
-        32
-    }
-
-    fn write(&mut self, data: Vec<u8>) {
-        self.hasher.update(&data);
+    fn write(&mut self, data: &[u8]) {
+        Digest::update(&mut self.hasher, data);
     }
 
     fn sum(self, out: &mut [u8]) {
-        self.hasher.finalize_variable(out).unwrap();
+        copy_prefix(&self.hasher.finalize(), out);
     }
 }
 
@@ -40,23 +84,21 @@ pub struct Sha3_224Hasher {
 }
 
 impl Hasher for Sha3_224Hasher {
+    const SIZE: usize = 28;
+    const ID: Option<HasherId> = Some(HasherId::Sha3_224);
+
     fn new() -> Sha3_224Hasher {
         Sha3_224Hasher {
             hasher: Sha3_224::new(),
         }
     }
 
-    fn size() -> usize {
-        28
-    }
-
-    fn write(&mut self, data: Vec<u8>) {
-        Digest::update(&mut self.hasher, &data);
+    fn write(&mut self, data: &[u8]) {
+        Digest::update(&mut self.hasher, data);
     }
 
     fn sum(self, out: &mut [u8]) {
-        let res = self.hasher.finalize();
-        out.copy_from_slice(&res);
+        copy_prefix(&self.hasher.finalize(), out);
     }
 }
 
@@ -66,22 +108,20 @@ pub struct Sha3_256Hasher {
 }
 
 impl Hasher for Sha3_256Hasher {
+    const SIZE: usize = 32;
+    const ID: Option<HasherId> = Some(HasherId::Sha3_256);
+
     fn new() -> Sha3_256Hasher {
         Sha3_256Hasher {
             hasher: Sha3_256::new(),
         }
     }
 
-    fn size() -> usize {
-        32
-    }
-
-    fn write(&mut self, data: Vec<u8>) {
-        Digest::update(&mut self.hasher, &data);
+    fn write(&mut self, data: &[u8]) {
+        Digest::update(&mut self.hasher, data);
     }
 
     fn sum(self, out: &mut [u8]) {
-        let res = self.hasher.finalize();
-        out.copy_from_slice(&res);
+        copy_prefix(&self.hasher.finalize(), out);
     }
 }
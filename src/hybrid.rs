@@ -0,0 +1,137 @@
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+
+use crate::hasher::Hasher;
+use crate::keys::{Key, PK_SIZE};
+use crate::params::WotsError;
+use crate::security;
+pub use crate::wire::HYBRID_VERSION;
+
+/// Size of a hybrid public key: the W-OTS+ public key followed by the Ed25519 one.
+pub const HYBRID_PK_SIZE: usize = PK_SIZE + ed25519_dalek::PUBLIC_KEY_LENGTH;
+
+// Both components sign under this label, so neither can be stripped from a hybrid signature
+// and passed off as a standalone one.
+const HYBRID_CONTEXT: &[u8] = b"W-OTS+/Ed25519 hybrid v1";
+
+// Envelope layout: version (1 byte) || W-OTS+ signature || Ed25519 signature (64 bytes). The
+// W-OTS+ signature's size follows from its encoding byte.
+const ED25519_SIG_SIZE: usize = ed25519_dalek::SIGNATURE_LENGTH;
+
+/// A W-OTS+ key paired with an Ed25519 key; its signatures only verify if both halves do, for
+/// a transition period in which classical verifiability must be kept.
+pub struct HybridKey<PRFH: Hasher + Clone, MSGH: Hasher + Clone> {
+    pub wots: Key<PRFH, MSGH>,
+    pub ed25519: SigningKey,
+}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> HybridKey<PRFH, MSGH> {
+    pub fn new(wots: Key<PRFH, MSGH>, ed25519: SigningKey) -> Self {
+        HybridKey { wots, ed25519 }
+    }
+
+    /// W-OTS+ public key || Ed25519 public key
+    pub fn public_key(&self) -> Vec<u8> {
+        let mut public_key = self.wots.public_key.clone();
+        public_key.extend_from_slice(self.ed25519.verifying_key().as_bytes());
+        public_key
+    }
+
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, WotsError> {
+        let wots = self.wots.sign_with_context(msg, HYBRID_CONTEXT)?;
+        let ed25519 = self.ed25519.sign(&ed25519_message(msg));
+
+        let mut signature = Vec::with_capacity(1 + wots.len() + ED25519_SIG_SIZE);
+        signature.push(HYBRID_VERSION);
+        signature.extend_from_slice(&wots);
+        signature.extend_from_slice(&ed25519.to_bytes());
+        Ok(signature)
+    }
+}
+
+/// Verifies a `HybridKey` signature against a `HybridKey::public_key`. Both the W-OTS+ and the
+/// Ed25519 signature must be valid.
+pub fn verify(msg: &[u8], signature: &[u8], public_key: &[u8]) -> Result<(), WotsError> {
+    if public_key.len() != HYBRID_PK_SIZE {
+        return Err(WotsError::InvalidPublicKeySize);
+    }
+    if signature.len() < 1 + ED25519_SIG_SIZE {
+        return Err(WotsError::InvalidSignatureSize);
+    }
+    if signature[0] != HYBRID_VERSION {
+        return Err(WotsError::UnsupportedEnvelopeVersion);
+    }
+
+    let (wots, ed25519) = signature[1..].split_at(signature.len() - 1 - ED25519_SIG_SIZE);
+    let (wots_pk, ed25519_pk) = public_key.split_at(PK_SIZE);
+
+    // check the cheap classical half first
+    let ed25519_pk =
+        VerifyingKey::try_from(ed25519_pk).map_err(|_| WotsError::InvalidPublicKeySize)?;
+    let ed25519 = ed25519_dalek::Signature::from_slice(ed25519)
+        .map_err(|_| WotsError::InvalidSignatureSize)?;
+    ed25519_pk
+        .verify_strict(&ed25519_message(msg), &ed25519)
+        .map_err(|_| WotsError::InvalidSignature)?;
+
+    security::verify_with_context(msg, HYBRID_CONTEXT, wots, wots_pk)
+}
+
+fn ed25519_message(msg: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(HYBRID_CONTEXT.len() + msg.len());
+    data.extend_from_slice(HYBRID_CONTEXT);
+    data.extend_from_slice(msg);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::SigningKey;
+
+    use crate::hasher::{Blake2bHasher, Sha3_256Hasher};
+    use crate::hybrid::{self, HybridKey, HYBRID_PK_SIZE};
+    use crate::keys::{Key, PK_SIZE};
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::security;
+
+    fn hybrid_key(seed: u8) -> HybridKey<Blake2bHasher, Sha3_256Hasher> {
+        let params = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
+        let wots = Key::from_seed(params, [seed; SEED_SIZE], [seed ^ 0xff; SEED_SIZE]).unwrap();
+        HybridKey::new(wots, SigningKey::from_bytes(&[seed; 32]))
+    }
+
+    #[test]
+    fn hybrid_sign_verify() {
+        let key = hybrid_key(1);
+        let other = hybrid_key(2);
+        let msg = b"XX NETWORK";
+        let public_key = key.public_key();
+        assert_eq!(public_key.len(), HYBRID_PK_SIZE);
+
+        let signature = key.sign(msg).unwrap();
+        hybrid::verify(msg, &signature, &public_key).unwrap();
+        assert!(hybrid::verify(b"XX NETWORL", &signature, &public_key).is_err());
+
+        // both halves must verify
+        let mut mixed = other.public_key();
+        mixed[PK_SIZE..].copy_from_slice(&public_key[PK_SIZE..]);
+        assert_eq!(
+            hybrid::verify(msg, &signature, &mixed),
+            Err(WotsError::InvalidSignature)
+        );
+        let mut mixed = public_key.clone();
+        mixed[PK_SIZE..].copy_from_slice(&other.public_key()[PK_SIZE..]);
+        assert_eq!(
+            hybrid::verify(msg, &signature, &mixed),
+            Err(WotsError::InvalidSignature)
+        );
+
+        // the W-OTS+ half alone doesn't pass as a plain signature
+        let wots = &signature[1..signature.len() - 64];
+        assert!(security::verify(msg, wots, &public_key[..PK_SIZE]).is_err());
+
+        assert_eq!(
+            hybrid::verify(msg, &signature[..64], &public_key),
+            Err(WotsError::InvalidSignatureSize)
+        );
+    }
+}
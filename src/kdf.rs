@@ -0,0 +1,233 @@
+use core::fmt;
+
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+
+use crate::hasher::Hasher;
+use crate::keys::Key;
+use crate::params::{Params, WotsError, SEED_SIZE};
+use crate::secret::{SecretBytes, SecretSeed};
+
+/// HMAC key deriving the master node from a wallet seed, in place of the curve name of
+/// SLIP-0010 (e.g. `ed25519 seed`)
+pub const MASTER_KEY: &[u8] = b"W-OTS+ seed";
+
+/// Offset of hardened indices; `ExtendedSeed` only derives hardened children
+pub const HARDENED: u32 = 1 << 31;
+
+/// HKDF info `ExtendedSeed::key` expands a node into a key pair with
+const KEY_INFO: &[u8] = b"W-OTS+ key";
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> Key<PRFH, MSGH> {
+    /// Derives a key pair with HKDF-SHA256 (RFC 5869): `ikm` and `salt` are extracted into a
+    /// pseudorandom key, which is expanded with `info` into `params.seed_size + SEED_SIZE`
+    /// bytes, the secret seed followed by `p_seed`. Give every one-time key its own `info`.
+    pub fn from_hkdf(
+        params: Params<PRFH, MSGH>,
+        ikm: &[u8],
+        salt: &[u8],
+        info: &[u8],
+    ) -> Result<Self, WotsError> {
+        params.check()?;
+        let mut okm = SecretBytes::zeroed(params.seed_size + SEED_SIZE);
+        Hkdf::<Sha256>::new(Some(salt), ikm)
+            .expand(info, &mut okm)
+            .map_err(|_| WotsError::InvalidSeedSize)?;
+        let (seed, p_seed) = okm.split_at(params.seed_size);
+        let seed = SecretSeed::from_slice(seed)?;
+        let p_seed = <[u8; SEED_SIZE]>::try_from(p_seed).expect("split at SEED_SIZE from the end");
+        Key::from_secret_seed(params, seed, p_seed)
+    }
+}
+
+/// Node of a SLIP-0010 style derivation tree, for wallets deriving one-time keys from the seed
+/// of their existing hierarchy. Nodes are derived with HMAC-SHA512 exactly as SLIP-0010 does
+/// for ed25519, hardened children only, with `MASTER_KEY` as the curve name; `key` turns a
+/// node into a key pair with `Key::from_hkdf`.
+///
+/// The node secret is zeroed on drop.
+#[derive(Clone)]
+pub struct ExtendedSeed {
+    secret: SecretBytes,
+    chain_code: [u8; 32],
+}
+
+impl ExtendedSeed {
+    /// Master node of the wallet seed `seed`.
+    pub fn master(seed: &[u8]) -> Self {
+        ExtendedSeed::from_hmac(MASTER_KEY, &[seed])
+    }
+
+    /// Hardened child `index'`; fails with `WotsError::InvalidDerivationPath` unless `index`
+    /// is below `HARDENED`.
+    pub fn derive(&self, index: u32) -> Result<Self, WotsError> {
+        if index >= HARDENED {
+            return Err(WotsError::InvalidDerivationPath);
+        }
+        Ok(ExtendedSeed::from_hmac(
+            &self.chain_code,
+            &[&[0u8], &self.secret, &(index | HARDENED).to_be_bytes()],
+        ))
+    }
+
+    /// Walks a path such as `m/44'/0'/7'` down from this node. Every index must be hardened,
+    /// marked with `'`, `h` or `H`.
+    pub fn derive_path(&self, path: &str) -> Result<Self, WotsError> {
+        let mut segments = path.split('/');
+        if segments.next() != Some("m") {
+            return Err(WotsError::InvalidDerivationPath);
+        }
+        segments.try_fold(self.clone(), |node, segment| {
+            let index = segment
+                .strip_suffix(['\'', 'h', 'H'])
+                .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|digits| digits.parse().ok())
+                .ok_or(WotsError::InvalidDerivationPath)?;
+            node.derive(index)
+        })
+    }
+
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    /// Key pair of this node: `Key::from_hkdf` with the node secret as input keying material
+    /// and its chain code as salt.
+    pub fn key<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        &self,
+        params: Params<PRFH, MSGH>,
+    ) -> Result<Key<PRFH, MSGH>, WotsError> {
+        Key::from_hkdf(params, &self.secret, &self.chain_code, KEY_INFO)
+    }
+
+    // from_hmac splits `HMAC-SHA512(key, data)` into the node secret and chain code.
+    fn from_hmac(key: &[u8], data: &[&[u8]]) -> Self {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC takes keys of any size");
+        for part in data {
+            mac.update(part);
+        }
+        let out = SecretBytes::from_slice(&mac.finalize().into_bytes());
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&out[32..]);
+        ExtendedSeed {
+            secret: SecretBytes::from_slice(&out[..32]),
+            chain_code,
+        }
+    }
+}
+
+impl fmt::Debug for ExtendedSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ExtendedSeed(..)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::kdf::{ExtendedSeed, HARDENED};
+    use crate::keys::Key;
+    use crate::params::WotsError;
+    use crate::security;
+
+    #[test]
+    fn from_hkdf() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_hkdf(params.clone(), b"ikm", b"salt", b"key 0").unwrap();
+        let again = Key::from_hkdf(params.clone(), b"ikm", b"salt", b"key 0").unwrap();
+        let other = Key::from_hkdf(params.clone(), b"ikm", b"salt", b"key 1").unwrap();
+        assert_eq!(key.public_key, again.public_key);
+        assert_ne!(key.public_key, other.public_key);
+        assert_ne!(key.p_seed, other.p_seed);
+
+        let mut oversized = params.clone();
+        oversized.seed_size = usize::MAX;
+        assert_eq!(
+            Key::from_hkdf(oversized, b"ikm", b"", b"key 0").err(),
+            Some(WotsError::InconsistentParams)
+        );
+
+        let long = params.with_seed_size(64).unwrap();
+        let key = Key::from_hkdf(long, b"ikm", b"", b"key 0").unwrap();
+        let signature = key.sign(b"msg").unwrap();
+        key.params()
+            .verify(b"msg", &signature[1..], &key.public_key)
+            .unwrap();
+    }
+
+    #[test]
+    fn slip10_vectors() {
+        // SLIP-0010 test vector 1 for ed25519, which derives nodes the same way
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = ExtendedSeed::from_hmac(b"ed25519 seed", &[&seed]);
+        assert_eq!(
+            hex::encode(&master.secret[..]),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        assert_eq!(
+            hex::encode(master.chain_code()),
+            "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb"
+        );
+        let child = master.derive_path("m/0'").unwrap();
+        assert_eq!(
+            hex::encode(&child.secret[..]),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        assert_eq!(
+            hex::encode(child.chain_code()),
+            "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69"
+        );
+    }
+
+    #[test]
+    fn derive_path() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let master = ExtendedSeed::master(b"wallet seed");
+        let node = master.derive_path("m/44'/7h/0H").unwrap();
+        let walked = master
+            .derive(44)
+            .and_then(|node| node.derive(7))
+            .and_then(|node| node.derive(0))
+            .unwrap();
+        assert_eq!(node.chain_code(), walked.chain_code());
+        assert_eq!(
+            node.key(params.clone()).unwrap().public_key,
+            walked.key(params.clone()).unwrap().public_key
+        );
+        assert_ne!(
+            node.key(params.clone()).unwrap().public_key,
+            master
+                .derive_path("m/44'/7'/1'")
+                .unwrap()
+                .key(params)
+                .unwrap()
+                .public_key
+        );
+        assert_eq!(
+            master.derive_path("m").unwrap().chain_code(),
+            master.chain_code()
+        );
+
+        for path in [
+            "",
+            "44'",
+            "m/44",
+            "m/-1'",
+            "m/+1'",
+            "m/2147483648'",
+            "m//0'",
+        ] {
+            assert_eq!(
+                master.derive_path(path).unwrap_err(),
+                WotsError::InvalidDerivationPath,
+                "{}",
+                path
+            );
+        }
+        assert_eq!(
+            master.derive(HARDENED).unwrap_err(),
+            WotsError::InvalidDerivationPath
+        );
+    }
+}
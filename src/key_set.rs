@@ -0,0 +1,197 @@
+use crate::keys::{PublicKey, PK_SIZE};
+use crate::params::WotsError;
+use crate::wire::KEY_SET_HEADER_SIZE;
+pub use crate::wire::KEY_SET_VERSION;
+
+/// A set of public keys, e.g. the current one-time keys of a validator set, kept sorted so
+/// lookups are binary searches and the encoding is canonical.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PublicKeySet {
+    keys: Vec<PublicKey>,
+}
+
+impl PublicKeySet {
+    pub fn new() -> Self {
+        PublicKeySet::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Adds `key`, returning whether it was not in the set yet.
+    pub fn insert(&mut self, key: PublicKey) -> bool {
+        match self.search(&key) {
+            Ok(_) => false,
+            Err(at) => {
+                self.keys.insert(at, key);
+                true
+            }
+        }
+    }
+
+    /// Removes `key`, returning whether it was in the set.
+    pub fn remove(&mut self, key: &PublicKey) -> bool {
+        match self.search(key) {
+            Ok(at) => {
+                self.keys.remove(at);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn contains(&self, key: &PublicKey) -> bool {
+        self.search(key).is_ok()
+    }
+
+    /// Index of `key` in the sorted order of `iter`, e.g. to look up the validator it belongs
+    /// to in a table kept alongside the set.
+    pub fn position(&self, key: &PublicKey) -> Option<usize> {
+        self.search(key).ok()
+    }
+
+    /// Keys in ascending byte order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &PublicKey> {
+        self.keys.iter()
+    }
+
+    /// Encodes the set as version || number of keys (u32 BE) || keys in ascending order.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(KEY_SET_HEADER_SIZE + self.keys.len() * PK_SIZE);
+        bytes.push(KEY_SET_VERSION);
+        bytes.extend_from_slice(&(self.keys.len() as u32).to_be_bytes());
+        for key in &self.keys {
+            bytes.extend_from_slice(key.as_bytes());
+        }
+        bytes
+    }
+
+    /// Parses `to_bytes`, failing with `WotsError::InvalidKeySet` for any other encoding,
+    /// including keys out of order or repeated, so a set has a single encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WotsError> {
+        if bytes.len() < KEY_SET_HEADER_SIZE || bytes[0] != KEY_SET_VERSION {
+            return Err(WotsError::InvalidKeySet);
+        }
+        let count = u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as usize;
+        let body = &bytes[KEY_SET_HEADER_SIZE..];
+        if count.checked_mul(PK_SIZE) != Some(body.len()) {
+            return Err(WotsError::InvalidKeySet);
+        }
+        let keys: Vec<PublicKey> = body
+            .chunks(PK_SIZE)
+            .map(|chunk| PublicKey::try_from(chunk).expect("chunks are PK_SIZE bytes"))
+            .collect();
+        if keys
+            .windows(2)
+            .any(|pair| pair[0].as_bytes() >= pair[1].as_bytes())
+        {
+            return Err(WotsError::InvalidKeySet);
+        }
+        Ok(PublicKeySet { keys })
+    }
+
+    fn search(&self, key: &PublicKey) -> Result<usize, usize> {
+        self.keys
+            .binary_search_by(|probe| probe.as_bytes().cmp(key.as_bytes()))
+    }
+}
+
+impl FromIterator<PublicKey> for PublicKeySet {
+    fn from_iter<I: IntoIterator<Item = PublicKey>>(iter: I) -> Self {
+        let mut keys: Vec<PublicKey> = iter.into_iter().collect();
+        keys.sort_unstable_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        keys.dedup();
+        PublicKeySet { keys }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher, Sha3_256Hasher};
+    use crate::key_set::PublicKeySet;
+    use crate::keys::{Key, PublicKey, PK_SIZE};
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::security;
+
+    #[test]
+    fn public_key_set() {
+        let keys: Vec<PublicKey> = (0..5u8)
+            .map(|i| PublicKey::from([i * 50; PK_SIZE]))
+            .collect();
+        let mut set: PublicKeySet = keys.iter().rev().chain(&keys[..2]).copied().collect();
+        assert_eq!(set.len(), 5);
+        assert!(set.iter().map(PublicKey::as_bytes).is_sorted());
+        assert_eq!(set.position(&keys[3]), Some(3));
+
+        let missing = PublicKey::from([1u8; PK_SIZE]);
+        assert!(!set.contains(&missing));
+        assert!(set.insert(missing));
+        assert!(!set.insert(missing));
+        assert_eq!(set.position(&missing), Some(1));
+        assert!(set.remove(&keys[0]));
+        assert!(!set.remove(&keys[0]));
+        assert_eq!(set.position(&missing), Some(0));
+
+        let bytes = set.to_bytes();
+        assert_eq!(PublicKeySet::from_bytes(&bytes), Ok(set.clone()));
+        assert_eq!(
+            PublicKeySet::from_bytes(&PublicKeySet::new().to_bytes()),
+            Ok(PublicKeySet::new())
+        );
+
+        // out of order, truncated, miscounted
+        let mut swapped = bytes.clone();
+        swapped[5..5 + 2 * PK_SIZE].rotate_left(PK_SIZE);
+        let mut miscounted = bytes.clone();
+        miscounted[4] += 1;
+        for bad in [
+            &swapped,
+            &bytes[..bytes.len() - 1],
+            &miscounted,
+            &bytes[..4],
+        ] {
+            assert_eq!(PublicKeySet::from_bytes(bad), Err(WotsError::InvalidKeySet));
+        }
+    }
+
+    #[test]
+    fn verify_against_set() {
+        let key = Key::from_seed(
+            security::level_1_params::<Blake2bHasher, Sha3_224Hasher>(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let consensus = Key::from_seed(
+            security::consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+            [3u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let set: PublicKeySet = [&key.public_key, &consensus.public_key]
+            .into_iter()
+            .map(|pk| PublicKey::try_from(&pk[..]).unwrap())
+            .collect();
+
+        let signature = key.sign(b"msg").unwrap();
+        let signer = security::verify_against_set(b"msg", &signature, &set).unwrap();
+        assert_eq!(&signer.as_bytes()[..], &key.public_key[..]);
+        let signature = consensus.sign(b"msg").unwrap();
+        let signer = security::verify_against_set(b"msg", &signature, &set).unwrap();
+        assert_eq!(&signer.as_bytes()[..], &consensus.public_key[..]);
+
+        assert_eq!(
+            security::verify_against_set(b"msh", &signature, &set),
+            Err(WotsError::InvalidSignature)
+        );
+        assert_eq!(
+            security::verify_against_set(b"msg", &signature[1..], &set),
+            Err(WotsError::InvalidSignatureSize)
+        );
+    }
+}
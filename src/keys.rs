@@ -1,20 +1,173 @@
+#[cfg(feature = "rayon")]
+use crate::backend::PoolBackend;
+#[cfg(feature = "std")]
+use crate::backend::ThreadedBackend;
+use crate::backend::{walk_chain, ChainBackend, SoftwareBackend};
+use crate::bundle::PublicBundle;
+use crate::entropy::EntropySource;
+use crate::envelope;
 use crate::hasher::Hasher;
-use crate::params::{ComputeLaddersMode, Params, WotsError, MAX_MSG_SIZE, SEED_SIZE};
+use crate::ladders::{ChainCache, LadderPoints};
+use crate::params::{
+    ComputeLaddersMode, Message, Params, WotsError, MAX_MSG_SIZE, MAX_SEED_SIZE, SEED_SIZE,
+};
+use crate::randomness::PublicRandomness;
+use crate::secret::{SecretBytes, SecretKey, SecretSeed, SeedProvider};
+use crate::security::{self, ParamsEncoding};
+use crate::signature::{check_p_seed, Signature, SignatureParts};
+use crate::wire::{CHAIN_CACHE_HEADER_SIZE, CHAIN_CACHE_TAG_SIZE, CHECKPOINT_HEADER_SIZE};
+pub use crate::wire::{CHAIN_CACHE_VERSION, CHECKPOINT_VERSION};
+use sha3::{Digest, Sha3_256};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use subtle::{Choice, ConstantTimeEq};
 
 #[cfg(feature = "std")]
-use rand::{rngs::OsRng, RngCore};
+use rand::rngs::OsRng;
+use rand::RngCore;
 
 /// Size of WOTS+ public keys
 pub const PK_SIZE: usize = 32;
 
+/// How much of the ladders `Key::generate_with_strategy` keeps around for fast signing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainCacheStrategy {
+    /// Keep every ladder level (`w * n * total` bytes); signing is a plain lookup.
+    Full,
+    /// Keep every k-th ladder level; signing recomputes the randomizers and up to k - 1
+    /// chain steps per ladder.
+    Sparse(usize),
+}
+
+impl ChainCacheStrategy {
+    fn stride(&self) -> usize {
+        match self {
+            ChainCacheStrategy::Full => 1,
+            ChainCacheStrategy::Sparse(k) => *k,
+        }
+    }
+}
+
+/// A W-OTS+ public key. Verifying with it dispatches on the signature's encoding byte, so
+/// no `Params` are needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PublicKey([u8; PK_SIZE]);
+
+impl PublicKey {
+    pub fn as_bytes(&self) -> &[u8; PK_SIZE] {
+        &self.0
+    }
+
+    /// Verifies `signature` on `msg`; see `security::verify`.
+    pub fn verify(&self, msg: impl AsRef<[u8]>, signature: &Signature) -> Result<(), WotsError> {
+        security::verify(msg.as_ref(), signature.as_bytes(), self.0)
+    }
+}
+
+impl From<[u8; PK_SIZE]> for PublicKey {
+    fn from(bytes: [u8; PK_SIZE]) -> Self {
+        PublicKey(bytes)
+    }
+}
+
+impl TryFrom<&[u8]> for PublicKey {
+    type Error = WotsError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let bytes =
+            <[u8; PK_SIZE]>::try_from(bytes).map_err(|_| WotsError::InvalidPublicKeySize)?;
+        Ok(PublicKey(bytes))
+    }
+}
+
+impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in &self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+// short_hex writes the first and last two bytes of `bytes` as hex, e.g. `ab12…ef90`.
+pub(crate) fn short_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    let (head, tail) = match bytes.len() {
+        0..=4 => (bytes, &[][..]),
+        len => (&bytes[..2], &bytes[len - 2..]),
+    };
+    for b in head {
+        write!(f, "{:02x}", b)?;
+    }
+    if !tail.is_empty() {
+        f.write_str("…")?;
+    }
+    for b in tail {
+        write!(f, "{:02x}", b)?;
+    }
+    Ok(())
+}
+
+/// Where a key is in its one-time life, see `Key::state`. Moves only go forward, in the order
+/// of the variants: `generate` caches the ladders of a fresh key, the first signature fixes
+/// the one message the key may sign, and `Key::destroy` wipes it for good. The state is shared
+/// with the key's clones and kept as is when the chain cache is dropped by hand.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KeyState {
+    /// derived, no ladders cached
+    #[default]
+    Fresh,
+    /// ladders cached for fast signing, through `generate`, `resume_generate` or
+    /// `import_chain_cache`
+    Generated,
+    /// has signed; signing any other message fails with `WotsError::KeyAlreadyUsed`
+    Signed,
+    /// secrets wiped; everything but verification fails with `WotsError::KeyDestroyed`
+    Destroyed,
+}
+
+// Lifecycle records what a key has done. It is shared with the key's clones, which hold the
+// same one-time secret.
+#[derive(Debug, Default)]
+struct Lifecycle {
+    state: KeyState,
+    // digits of the signed message, which signing again must match, and the signature made,
+    // which signing again returns
+    signed: Option<(Vec<u16>, Vec<u8>)>,
+    // the signed message when it had no context, which `sign_many_unique` compares retries to
+    message: Option<Vec<u8>>,
+}
+
+impl Lifecycle {
+    // advance moves to `state` unless the key is already past it: a key that signed stays
+    // `Signed` when it generates again, and a destroyed one stays destroyed.
+    fn advance(&mut self, state: KeyState) {
+        self.state = self.state.max(state);
+    }
+
+    fn destroyed(&self) -> bool {
+        self.state == KeyState::Destroyed
+    }
+}
+
 #[derive(Clone)]
 pub struct Key<PRFH: Hasher + Clone, MSGH: Hasher + Clone> {
-    pub seed: [u8; SEED_SIZE],
+    /// `None` for keys derived through an external `SeedProvider`
+    pub seed: Option<SecretSeed>,
     pub p_seed: [u8; SEED_SIZE],
-    pub chains: Option<Vec<Vec<u8>>>,
-    pub secret_key: Vec<u8>,
+    pub chains: Option<ChainCache>,
+    pub secret_key: SecretKey,
     pub public_key: Vec<u8>,
     params: Params<PRFH, MSGH>,
+    cache_strategy: ChainCacheStrategy,
+    lifecycle: Arc<Mutex<Lifecycle>>,
     prf_hash: std::marker::PhantomData<PRFH>,
     msg_hash: std::marker::PhantomData<MSGH>,
 }
@@ -29,15 +182,52 @@ impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> Key<PRFH, MSGH> {
         seed: [u8; SEED_SIZE],
         p_seed: [u8; SEED_SIZE],
     ) -> Result<Self, WotsError> {
-        let sk = calculate_secret_key::<PRFH, MSGH>(&params, &seed);
+        Self::from_secret_seed(params, SecretSeed::new(seed), p_seed)
+    }
+
+    /// Same as `from_seed`, for seeds of `params.seed_size` bytes.
+    pub fn from_secret_seed(
+        params: Params<PRFH, MSGH>,
+        seed: SecretSeed,
+        p_seed: [u8; SEED_SIZE],
+    ) -> Result<Self, WotsError> {
+        if seed.len() != params.seed_size {
+            return Err(WotsError::InvalidSeedSize);
+        }
+        let mut key = Self::from_seed_provider(params, &seed, p_seed)?;
+        key.seed = Some(seed);
+        Ok(key)
+    }
+
+    /// Derives the key pair from the PRF outputs of `provider`, which may keep the seed in
+    /// hardware. The derived secret ladder starts are still held in memory.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "wots.keygen",
+            level = "debug",
+            skip_all,
+            fields(encoding = ?params.get_encoding()),
+            err,
+        )
+    )]
+    pub fn from_seed_provider<S: SeedProvider<PRFH>>(
+        params: Params<PRFH, MSGH>,
+        provider: &S,
+        p_seed: [u8; SEED_SIZE],
+    ) -> Result<Self, WotsError> {
+        check_p_seed(&p_seed)?;
+        let sk = calculate_secret_key::<PRFH, MSGH, S>(&params, provider)?;
         let public_key = calculate_public_key(&params, &p_seed, &sk)?;
         Ok(Key::<PRFH, MSGH> {
-            seed,
+            seed: None,
             p_seed,
             chains: None,
             secret_key: sk,
             public_key,
             params,
+            cache_strategy: ChainCacheStrategy::Full,
+            lifecycle: Arc::default(),
             prf_hash: std::marker::PhantomData::<PRFH>,
             msg_hash: std::marker::PhantomData::<MSGH>,
         })
@@ -45,82 +235,829 @@ impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> Key<PRFH, MSGH> {
 
     #[cfg(feature = "std")]
     pub fn new(params: Params<PRFH, MSGH>) -> Result<Self, WotsError> {
-        let mut seed = [0u8; SEED_SIZE];
-        OsRng.fill_bytes(&mut seed);
+        Self::from_entropy(params, &mut EntropySource::new(OsRng))
+    }
+
+    /// Generates a key pair from seeds drawn from `entropy`, failing with
+    /// `WotsError::EntropyFailure` if its output does not pass the health tests.
+    pub fn from_entropy<R: RngCore>(
+        params: Params<PRFH, MSGH>,
+        entropy: &mut EntropySource<R>,
+    ) -> Result<Self, WotsError> {
+        // `seed_size` is public: check it before slicing by it
+        params.check()?;
+        let mut seed = [0u8; MAX_SEED_SIZE];
         let mut p_seed = [0u8; SEED_SIZE];
-        OsRng.fill_bytes(&mut p_seed);
-        Self::from_seed(params, seed, p_seed)
+        entropy.fill(&mut seed[..params.seed_size])?;
+        entropy.fill(&mut p_seed)?;
+        let secret_seed = SecretSeed::from_slice(&seed[..params.seed_size]);
+        seed.fill(0);
+        Self::from_secret_seed(params, secret_seed?, p_seed)
     }
 
     pub fn generate(&mut self) -> Result<(), WotsError> {
-        if self.chains.is_some() {
+        self.generate_with_strategy(ChainCacheStrategy::Full)
+    }
+
+    /// Computes and caches the ladders for fast signing, keeping only the levels selected
+    /// by `strategy`.
+    pub fn generate_with_strategy(
+        &mut self,
+        strategy: ChainCacheStrategy,
+    ) -> Result<(), WotsError> {
+        self.generate_with(strategy, &SoftwareBackend)
+    }
+
+    /// Same as `generate`, but walks the ladders on `threads` threads.
+    #[cfg(feature = "std")]
+    pub fn generate_parallel(&mut self, threads: usize) -> Result<(), WotsError> {
+        self.generate_with(ChainCacheStrategy::Full, &ThreadedBackend::new(threads))
+    }
+
+    /// Same as `generate`, but walks the ladders on the threads of `pool`.
+    #[cfg(feature = "rayon")]
+    pub fn generate_in_pool(&mut self, pool: &rayon::ThreadPool) -> Result<(), WotsError> {
+        self.generate_with(ChainCacheStrategy::Full, &PoolBackend::new(pool))
+    }
+
+    /// Computes and caches the ladders for fast signing on `backend`, keeping only the
+    /// levels selected by `strategy`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "wots.generate",
+            level = "debug",
+            skip_all,
+            fields(encoding = ?self.params.get_encoding(), strategy = ?strategy),
+            err,
+        )
+    )]
+    pub fn generate_with<B: ChainBackend<PRFH>>(
+        &mut self,
+        strategy: ChainCacheStrategy,
+        backend: &B,
+    ) -> Result<(), WotsError> {
+        self.generate_using(strategy, backend, None)
+    }
+
+    /// Same as `generate_with_strategy`, with the randomizers of this key's `p_seed`
+    /// precomputed.
+    pub fn generate_with_randomness(
+        &mut self,
+        strategy: ChainCacheStrategy,
+        randomness: &PublicRandomness,
+    ) -> Result<(), WotsError> {
+        self.generate_using(strategy, &SoftwareBackend, Some(randomness))
+    }
+
+    fn generate_using<B: ChainBackend<PRFH>>(
+        &mut self,
+        strategy: ChainCacheStrategy,
+        backend: &B,
+        randomness: Option<&PublicRandomness>,
+    ) -> Result<(), WotsError> {
+        self.check_alive()?;
+        if self.chains.is_some() && self.cache_strategy == strategy {
             return Ok(());
         }
 
-        let (_, chains) = self.params.compute_ladders(
-            &self.p_seed,
+        let stride = strategy.stride();
+        if stride == 0 {
+            return Err(WotsError::InvalidCacheStrategy);
+        }
+
+        let mut chains = ChainCache::new(self.params.n, self.params.w, self.params.total, stride);
+        let mut pk = vec![0u8; PK_SIZE];
+        self.params.compute_ladders_into(
+            backend,
+            &*self.randomness_or_derive(randomness)?,
             None,
-            &self.secret_key,
+            self.secret_points()?,
             ComputeLaddersMode::Generate,
+            &mut pk,
+            Some(chains.rows_mut()),
         )?;
         self.chains = Some(chains);
+        self.cache_strategy = strategy;
+        self.lifecycle().advance(KeyState::Generated);
+        Ok(())
+    }
+
+    /// Starts a resumable `generate_with_strategy`, to be driven by `resume_generate`.
+    pub fn start_generate(
+        &self,
+        strategy: ChainCacheStrategy,
+    ) -> Result<GenerateCheckpoint, WotsError> {
+        self.check_alive()?;
+        let stride = strategy.stride();
+        if stride == 0 {
+            return Err(WotsError::InvalidCacheStrategy);
+        }
+        Ok(GenerateCheckpoint {
+            public_key: *self.verifying_key()?.as_bytes(),
+            next: 0,
+            chains: ChainCache::new(self.params.n, self.params.w, self.params.total, stride),
+        })
+    }
+
+    /// Walks up to `max_ladders` more ladders of `checkpoint`. Returns the checkpoint to
+    /// resume from, or `None` once every ladder is walked and the chains are cached, exactly
+    /// as `generate_with_strategy` would have.
+    pub fn resume_generate(
+        &mut self,
+        mut checkpoint: GenerateCheckpoint,
+        max_ladders: usize,
+    ) -> Result<Option<GenerateCheckpoint>, WotsError> {
+        self.check_alive()?;
+        let (n, total) = (self.params.n, self.params.total);
+        let rows = checkpoint.chains.rows();
+        if checkpoint.public_key[..] != self.public_key[..]
+            || checkpoint.chains.n() != n
+            || !checkpoint.chains.fits_w(self.params.w)
+            || rows[0].len() != n * total
+        {
+            return Err(WotsError::InvalidCheckpoint);
+        }
+
+        let randomness = self.public_randomness();
+        let end = total.min(checkpoint.next.saturating_add(max_ladders));
+        let secret = self.secret_points()?;
+        let mut value = vec![0u8; n];
+        for i in checkpoint.next..end {
+            value.copy_from_slice(secret.ladder(i));
+            let mut chains = checkpoint.chains.rows_mut();
+            chains.rows[0][i * n..(i + 1) * n].copy_from_slice(&value);
+            self.params.compute_chain(
+                &randomness,
+                &mut value,
+                Some(&mut chains),
+                i,
+                (0, (self.params.w - 1) as u16),
+            );
+        }
+        value.fill(0);
+        checkpoint.next = end;
+        if end < total {
+            return Ok(Some(checkpoint));
+        }
+
+        self.cache_strategy = match checkpoint.chains.stride() {
+            1 => ChainCacheStrategy::Full,
+            k => ChainCacheStrategy::Sparse(k),
+        };
+        self.chains = Some(checkpoint.chains);
+        self.lifecycle().advance(KeyState::Generated);
+        Ok(None)
+    }
+
+    pub fn sign(&self, msg: impl AsRef<[u8]>) -> Result<Vec<u8>, WotsError> {
+        let mut sig = vec![0u8; self.params.signature_size()];
+        self.sign_into(msg.as_ref(), &mut sig)?;
+        Ok(sig)
+    }
+
+    /// Same as `sign`, but writes the signature into `out`, which must be exactly
+    /// `Params::signature_size` bytes.
+    pub fn sign_into(&self, msg: impl AsRef<[u8]>, out: &mut [u8]) -> Result<(), WotsError> {
+        self.sign_message_into(&SoftwareBackend, Message::plain(msg.as_ref()), out, None)
+    }
+
+    /// Serializes the cached ladders, so a restarted signer can skip `generate`, with a
+    /// per-ladder tag keyed by the secret key so `import_chain_cache` catches a corrupted or
+    /// tampered file. Holds secret ladder values: store it as carefully as the key.
+    pub fn export_chain_cache(&self) -> Result<Vec<u8>, WotsError> {
+        self.check_alive()?;
+        let chains = self.chains.as_ref().ok_or(WotsError::ChainsNotSet)?;
+        let rows = chains.rows();
+        let (n, total) = (self.params.n, self.params.total);
+        let mut bytes = Vec::with_capacity(
+            CHAIN_CACHE_HEADER_SIZE + rows.len() * n * total + total * CHAIN_CACHE_TAG_SIZE,
+        );
+        bytes.push(CHAIN_CACHE_VERSION);
+        bytes.extend_from_slice(&self.public_key);
+        for field in [n, total, chains.stride()] {
+            bytes.extend_from_slice(&(field as u16).to_be_bytes());
+        }
+        for row in rows {
+            bytes.extend_from_slice(row);
+        }
+        for tag in self.chain_cache_tags(chains) {
+            bytes.extend_from_slice(&tag);
+        }
+        Ok(bytes)
+    }
+
+    /// Restores ladders written by `export_chain_cache` of this key. Fails with
+    /// `ChainCacheTampered` if the tag of any ladder doesn't match its values.
+    pub fn import_chain_cache(&mut self, bytes: &[u8]) -> Result<(), WotsError> {
+        self.check_alive()?;
+        if bytes.len() < CHAIN_CACHE_HEADER_SIZE
+            || bytes[0] != CHAIN_CACHE_VERSION
+            || bytes[1..1 + PK_SIZE] != self.public_key[..]
+        {
+            return Err(WotsError::InvalidChainCache);
+        }
+        let field = |i: usize| {
+            let at = 1 + PK_SIZE + 2 * i;
+            u16::from_be_bytes([bytes[at], bytes[at + 1]]) as usize
+        };
+        let (n, total, stride) = (field(0), field(1), field(2));
+        if n != self.params.n || total != self.params.total || stride == 0 {
+            return Err(WotsError::InvalidChainCache);
+        }
+
+        let row_len = n * total;
+        let rows_len = row_len * ((self.params.w - 1) / stride + 1);
+        let body = &bytes[CHAIN_CACHE_HEADER_SIZE..];
+        if body.len() != rows_len + total * CHAIN_CACHE_TAG_SIZE {
+            return Err(WotsError::InvalidChainCache);
+        }
+        let rows = body[..rows_len]
+            .chunks(row_len)
+            .map(<[u8]>::to_vec)
+            .collect();
+        let chains = ChainCache::from_rows(rows, n, stride).ok_or(WotsError::InvalidChainCache)?;
+
+        let mut valid = Choice::from(1);
+        for (tag, stored) in self
+            .chain_cache_tags(&chains)
+            .iter()
+            .zip(body[rows_len..].chunks(CHAIN_CACHE_TAG_SIZE))
+        {
+            valid &= tag[..].ct_eq(stored);
+        }
+        if !bool::from(valid) {
+            return Err(WotsError::ChainCacheTampered);
+        }
+
+        self.cache_strategy = match stride {
+            1 => ChainCacheStrategy::Full,
+            k => ChainCacheStrategy::Sparse(k),
+        };
+        self.chains = Some(chains);
+        self.lifecycle().advance(KeyState::Generated);
         Ok(())
     }
 
-    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, WotsError> {
+    // chain_cache_tags returns the tag of every ladder of `chains`: the first
+    // `CHAIN_CACHE_TAG_SIZE` bytes of SHA3-256(mac key || stride || i || cached levels of i),
+    // under a mac key derived from the secret key.
+    fn chain_cache_tags(&self, chains: &ChainCache) -> Vec<[u8; CHAIN_CACHE_TAG_SIZE]> {
+        let mut hasher = Sha3_256::new();
+        hasher.update(CHAIN_CACHE_DOMAIN);
+        hasher.update(&self.public_key);
+        hasher.update(&*self.secret_key);
+        let mac_key = SecretBytes::from_slice(&hasher.finalize());
+
+        let n = self.params.n;
+        (0..self.params.total)
+            .map(|i| {
+                let mut hasher = Sha3_256::new();
+                hasher.update(&*mac_key);
+                hasher.update((chains.stride() as u16).to_be_bytes());
+                hasher.update((i as u16).to_be_bytes());
+                for row in chains.rows() {
+                    hasher.update(&row[i * n..(i + 1) * n]);
+                }
+                let mut tag = [0u8; CHAIN_CACHE_TAG_SIZE];
+                tag.copy_from_slice(&hasher.finalize()[..CHAIN_CACHE_TAG_SIZE]);
+                tag
+            })
+            .collect()
+    }
+
+    /// Same as `sign`, with the randomizers of this key's `p_seed` precomputed. They're only
+    /// needed when the ladders aren't fully cached.
+    pub fn sign_with_randomness(
+        &self,
+        msg: impl AsRef<[u8]>,
+        randomness: &PublicRandomness,
+    ) -> Result<Vec<u8>, WotsError> {
+        let mut sig = vec![0u8; self.params.signature_size()];
+        self.sign_message_into(
+            &SoftwareBackend,
+            Message::plain(msg.as_ref()),
+            &mut sig,
+            Some(randomness),
+        )?;
+        Ok(sig)
+    }
+
+    /// The randomizers of this key's `p_seed`, for the `*_with_randomness` methods.
+    pub fn public_randomness(&self) -> PublicRandomness {
+        self.params.public_randomness(&self.p_seed)
+    }
+
+    /// Same as `sign`, but walks the ladders on `backend` when they aren't cached.
+    pub fn sign_with<B: ChainBackend<PRFH>>(
+        &self,
+        msg: impl AsRef<[u8]>,
+        backend: &B,
+    ) -> Result<Vec<u8>, WotsError> {
+        let mut sig = vec![0u8; self.params.signature_size()];
+        self.sign_message_into(backend, Message::plain(msg.as_ref()), &mut sig, None)?;
+        Ok(sig)
+    }
+
+    /// Fails with `InvalidPublicKeySize` if `public_key` was replaced by one of the wrong size.
+    pub fn verifying_key(&self) -> Result<PublicKey, WotsError> {
+        PublicKey::try_from(self.public_key.as_slice())
+    }
+
+    pub fn params(&self) -> &Params<PRFH, MSGH> {
+        &self.params
+    }
+
+    pub fn encoding(&self) -> &ParamsEncoding {
+        &self.params.encoding
+    }
+
+    /// Size of the signatures this key makes, encoding byte included.
+    pub fn signature_size(&self) -> usize {
+        self.params.signature_size()
+    }
+
+    pub fn public_key_size(&self) -> usize {
+        PK_SIZE
+    }
+
+    pub fn secret_key_size(&self) -> usize {
+        self.params.secret_key_size()
+    }
+
+    pub fn p_seed(&self) -> &[u8; SEED_SIZE] {
+        &self.p_seed
+    }
+
+    /// `None` for keys derived through an external `SeedProvider`.
+    pub fn seed(&self) -> Option<&SecretSeed> {
+        self.seed.as_ref()
+    }
+
+    /// `None` until the ladders are cached by `generate`.
+    pub fn chains(&self) -> Option<&ChainCache> {
+        self.chains.as_ref()
+    }
+
+    /// Same as `sign`, with the signature split into its encoding, `p_seed` and points.
+    pub fn sign_parts(&self, msg: impl AsRef<[u8]>) -> Result<SignatureParts, WotsError> {
+        let signature = self.sign(msg.as_ref())?;
+        Ok(SignatureParts {
+            encoding: *self.params.get_encoding(),
+            p_seed: self.p_seed,
+            points: signature[1 + SEED_SIZE..].to_vec(),
+        })
+    }
+
+    /// Same as `sign`, but leaves out `p_seed`: encoding byte || ladder points, `SEED_SIZE`
+    /// bytes shorter. For protocols that bind `p_seed` to the registered public key; verify
+    /// with `security::verify_with_seed`.
+    pub fn sign_detached_seed(&self, msg: impl AsRef<[u8]>) -> Result<Vec<u8>, WotsError> {
+        let mut signature = self.sign(msg.as_ref())?;
+        signature.drain(1..1 + SEED_SIZE);
+        Ok(signature)
+    }
+
+    /// Exports the public key, `p_seed` and encoding in a fingerprinted bundle, to be checked
+    /// with `bundle::verify_bundle`. Fails for custom params.
+    pub fn export_public_bundle(&self) -> Result<Vec<u8>, WotsError> {
+        let public_key = self.verifying_key()?;
+        let bundle = PublicBundle::new(*self.params.get_encoding(), self.p_seed, public_key.0)?;
+        Ok(bundle.to_bytes())
+    }
+
+    /// Signs `msg` and bundles it with the signature in a versioned envelope, to be checked
+    /// and unpacked with `envelope::open_attached`.
+    pub fn sign_attached(&self, msg: impl AsRef<[u8]>) -> Result<Vec<u8>, WotsError> {
+        let msg = msg.as_ref();
+        let (mut envelope, sig_start) = envelope::with_message(msg, self.params.signature_size())?;
+        self.sign_into(msg, &mut envelope[sig_start..])?;
+        Ok(envelope)
+    }
+
+    /// Signs `msg` bound to `context` (at most `MAX_CONTEXT_SIZE` bytes), ed25519ctx-style.
+    /// The signature only verifies through `verify_with_context` with the same context, so it
+    /// can't be replayed in a protocol using another context, or none.
+    pub fn sign_with_context(
+        &self,
+        msg: impl AsRef<[u8]>,
+        context: impl AsRef<[u8]>,
+    ) -> Result<Vec<u8>, WotsError> {
+        let mut sig = vec![0u8; self.params.signature_size()];
+        self.sign_message_into(
+            &SoftwareBackend,
+            Message::with_context(msg.as_ref(), context.as_ref())?,
+            &mut sig,
+            None,
+        )?;
+        Ok(sig)
+    }
+
+    /// Signs `msg` for callers that may retry: the first call signs and records the
+    /// signature, a call with the same message returns that signature again, and any other
+    /// message fails with `WotsError::KeyAlreadyUsed`, even one `sign` would accept because
+    /// its digits are equal. A message signed first through `sign` counts as the same; a
+    /// context-bound one doesn't.
+    pub fn sign_many_unique(&self, msg: impl AsRef<[u8]>) -> Result<Vec<u8>, WotsError> {
+        let msg = msg.as_ref();
+        let mut lifecycle = self.lifecycle();
+        if lifecycle.destroyed() {
+            return Err(WotsError::KeyDestroyed);
+        }
+        match (&lifecycle.message, &lifecycle.signed) {
+            (Some(signed), Some((_, sig))) if signed == msg => return Ok(sig.clone()),
+            (None, None) => {}
+            _ => return Err(WotsError::KeyAlreadyUsed),
+        }
+        let mut sig = vec![0u8; self.params.signature_size()];
+        self.sign_locked(
+            &mut lifecycle,
+            &SoftwareBackend,
+            Message::plain(msg),
+            &mut sig,
+            None,
+        )?;
+        Ok(sig)
+    }
+
+    fn sign_message_into<B: ChainBackend<PRFH>>(
+        &self,
+        backend: &B,
+        msg: Message,
+        out: &mut [u8],
+        randomness: Option<&PublicRandomness>,
+    ) -> Result<(), WotsError> {
+        // held while signing, so that clones on other threads can't sign two messages at once
+        let mut lifecycle = self.lifecycle();
+        self.sign_locked(&mut lifecycle, backend, msg, out, randomness)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "wots.sign",
+            level = "debug",
+            skip_all,
+            fields(key = %self, msg_len = msg.len(), cached = self.chains.is_some()),
+            err,
+        )
+    )]
+    // sign_locked signs `msg` into `out` with the lifecycle lock held by the caller.
+    fn sign_locked<B: ChainBackend<PRFH>>(
+        &self,
+        lifecycle: &mut Lifecycle,
+        backend: &B,
+        msg: Message,
+        out: &mut [u8],
+        randomness: Option<&PublicRandomness>,
+    ) -> Result<(), WotsError> {
         if msg.len() > MAX_MSG_SIZE {
             return Err(WotsError::InvalidMessageSize);
         }
 
+        if out.len() != self.params.signature_size() {
+            return Err(WotsError::InvalidSignatureSize);
+        }
+
+        if lifecycle.destroyed() {
+            return Err(WotsError::KeyDestroyed);
+        }
+        let digits = self.params.message_digits(msg).to_vec();
+        if let Some(randomness) = randomness {
+            randomness.matches(self.params.n, self.params.w, &self.p_seed)?;
+        }
+        // the signature only depends on the digits, so a retry gets the recorded one back
+        match &lifecycle.signed {
+            Some((signed, sig)) if *signed == digits => {
+                out.copy_from_slice(sig);
+                return Ok(());
+            }
+            Some(_) => return Err(WotsError::KeyAlreadyUsed),
+            None => {}
+        }
+        self.write_signature(backend, msg, out, randomness)?;
+        lifecycle.signed = Some((digits, out.to_vec()));
+        lifecycle.message = msg.plain_bytes().map(<[u8]>::to_vec);
+        lifecycle.advance(KeyState::Signed);
+        Ok(())
+    }
+
+    // write_signature signs `msg` into `out`, which `sign_message_into` checked the size of.
+    fn write_signature<B: ChainBackend<PRFH>>(
+        &self,
+        backend: &B,
+        msg: Message,
+        out: &mut [u8],
+        randomness: Option<&PublicRandomness>,
+    ) -> Result<(), WotsError> {
+        out[0] = self.params.get_encoding().into();
+        out[1..1 + SEED_SIZE].copy_from_slice(&self.p_seed);
+        let points = &mut out[1 + SEED_SIZE..];
+
         if self.chains.is_some() {
-            return self.fast_sign(msg);
+            return self.fast_sign(msg, points, randomness);
         }
 
-        let (signature, _) = self.params.compute_ladders(
-            &self.p_seed,
-            Some(msg.to_vec()),
-            &self.secret_key,
+        self.params.compute_ladders_into(
+            backend,
+            &*self.randomness_or_derive(randomness)?,
+            Some(msg),
+            self.secret_points()?,
             ComputeLaddersMode::Sign,
-        )?;
-        Ok(self.build_signature(&signature))
+            points,
+            None,
+        )
+    }
+
+    /// The current lifecycle state.
+    pub fn state(&self) -> KeyState {
+        self.lifecycle().state
+    }
+
+    /// Wipes the seed, secret key and chain cache, and moves this key and its clones to
+    /// `KeyState::Destroyed`. Clones stop signing too, but their copies of the secrets are
+    /// only wiped when they are dropped.
+    pub fn destroy(&mut self) {
+        self.lifecycle().advance(KeyState::Destroyed);
+        self.seed = None;
+        self.chains = None;
+        self.secret_key = SecretKey::zeroed(0);
+    }
+
+    // lifecycle locks the lifecycle record. A panic while it was held can't leave it
+    // inconsistent, so a poisoned lock is taken over.
+    fn lifecycle(&self) -> MutexGuard<'_, Lifecycle> {
+        self.lifecycle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // check_alive fails with `KeyDestroyed` once the key was destroyed.
+    fn check_alive(&self) -> Result<(), WotsError> {
+        if self.lifecycle().destroyed() {
+            return Err(WotsError::KeyDestroyed);
+        }
+        Ok(())
+    }
+
+    fn secret_points(&self) -> Result<LadderPoints<'_>, WotsError> {
+        LadderPoints::new(&self.secret_key, self.params.n, self.params.total)
+    }
+
+    // randomness_or_derive checks `randomness` belongs to this key, or derives it when not given.
+    fn randomness_or_derive<'a>(
+        &self,
+        randomness: Option<&'a PublicRandomness>,
+    ) -> Result<Cow<'a, PublicRandomness>, WotsError> {
+        match randomness {
+            Some(randomness) => {
+                randomness.matches(self.params.n, self.params.w, &self.p_seed)?;
+                Ok(Cow::Borrowed(randomness))
+            }
+            None => Ok(Cow::Owned(self.public_randomness())),
+        }
     }
 
-    fn fast_sign(&self, msg: &[u8]) -> Result<Vec<u8>, WotsError> {
-        let data = self.params.msg_hash_and_compute_checksum(msg);
-        let mut sig = vec![0u8; self.params.n * self.params.total];
+    fn fast_sign(
+        &self,
+        msg: Message,
+        points: &mut [u8],
+        randomness: Option<&PublicRandomness>,
+    ) -> Result<(), WotsError> {
+        let data = self.params.message_digits(msg);
         let chains = self.chains.as_ref().ok_or(WotsError::ChainsNotSet)?;
-        for i in 0..self.params.total {
+        let stride = chains.stride();
+
+        // levels between the cached ones have to be walked up to from the nearest cached level
+        if let Some(randomness) = randomness {
+            randomness.matches(self.params.n, self.params.w, &self.p_seed)?;
+        }
+        let derived;
+        let randomness = match (randomness, stride) {
+            (None, 1) => None,
+            (None, _) => {
+                derived = self.public_randomness();
+                Some(&derived)
+            }
+            (given, _) => given,
+        };
+
+        for (i, &digit) in data.iter().enumerate().take(self.params.total) {
             let start = i * self.params.n;
             let end = (i + 1) * self.params.n;
-            sig[start..end].copy_from_slice(&chains[data[i] as usize][start..end]);
+            let (level, cached) = chains.nearest_level(digit as usize);
+            points[start..end].copy_from_slice(cached.ladder(i));
+            if level != digit as usize {
+                self.params.compute_chain(
+                    randomness.ok_or(WotsError::ChainsNotSet)?,
+                    &mut points[start..end],
+                    None,
+                    i,
+                    (level as u16, digit),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One-line summary without secrets, e.g. `wots:consensus pk=ab12…ef90`.
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> fmt::Display for Key<PRFH, MSGH> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "wots:{} pk=", self.params.get_encoding())?;
+        short_hex(f, &self.public_key)
+    }
+}
+
+const CHAIN_CACHE_DOMAIN: &[u8] = b"W-OTS+ chain cache tag";
+
+/// Progress of a `Key::resume_generate` job, serializable so a preempted keygen job can pick
+/// up where it left off. It holds secret ladder values: store it as carefully as the key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct GenerateCheckpoint {
+    public_key: [u8; PK_SIZE],
+    next: usize,
+    chains: ChainCache,
+}
+
+impl GenerateCheckpoint {
+    /// Number of ladders walked so far.
+    pub fn ladders_done(&self) -> usize {
+        self.next
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let rows = self.chains.rows();
+        let mut bytes = Vec::with_capacity(CHECKPOINT_HEADER_SIZE + rows.len() * rows[0].len());
+        bytes.push(CHECKPOINT_VERSION);
+        bytes.extend_from_slice(&self.public_key);
+        let total = rows[0].len() / self.chains.n();
+        for field in [self.chains.n(), total, self.chains.stride(), self.next] {
+            bytes.extend_from_slice(&(field as u16).to_be_bytes());
+        }
+        for row in rows {
+            bytes.extend_from_slice(row);
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WotsError> {
+        if bytes.len() < CHECKPOINT_HEADER_SIZE || bytes[0] != CHECKPOINT_VERSION {
+            return Err(WotsError::InvalidCheckpoint);
+        }
+        let mut public_key = [0u8; PK_SIZE];
+        public_key.copy_from_slice(&bytes[1..1 + PK_SIZE]);
+        let field = |i: usize| {
+            let at = 1 + PK_SIZE + 2 * i;
+            u16::from_be_bytes([bytes[at], bytes[at + 1]]) as usize
+        };
+        let (n, total, stride, next) = (field(0), field(1), field(2), field(3));
+        if stride == 0 || next > total {
+            return Err(WotsError::InvalidCheckpoint);
+        }
+
+        // the number of rows depends on `w`, checked by `resume_generate`
+        let body = &bytes[CHECKPOINT_HEADER_SIZE..];
+        let row_len = n * total;
+        if row_len == 0 || body.is_empty() || !body.len().is_multiple_of(row_len) {
+            return Err(WotsError::InvalidCheckpoint);
+        }
+        let rows = body.chunks(row_len).map(<[u8]>::to_vec).collect();
+        let chains = ChainCache::from_rows(rows, n, stride).ok_or(WotsError::InvalidCheckpoint)?;
+        Ok(GenerateCheckpoint {
+            public_key,
+            next,
+            chains,
+        })
+    }
+}
+
+impl fmt::Debug for GenerateCheckpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenerateCheckpoint")
+            .field("ladders_done", &self.next)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Derives only the public key of the key pair `Key::from_secret_seed` would make, for
+/// enrollment on machines that never sign. The secret ladder starts are zeroed before this
+/// returns.
+pub fn public_key_from_seeds<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    params: &Params<PRFH, MSGH>,
+    seed: &SecretSeed,
+    p_seed: &[u8; SEED_SIZE],
+) -> Result<PublicKey, WotsError> {
+    if seed.len() != params.seed_size {
+        return Err(WotsError::InvalidSeedSize);
+    }
+    check_p_seed(p_seed)?;
+    let sk = calculate_secret_key::<PRFH, MSGH, _>(params, seed)?;
+    let public_key = calculate_public_key(params, p_seed, &sk)?;
+    PublicKey::try_from(public_key.as_slice())
+}
+
+/// Derives the secret start of ladder `i` (`n` bytes), the chunk a key's `secret_key` holds at
+/// `i * n..(i + 1) * n`, so a signer can hold one ladder's secret at a time.
+pub fn derive_sk_chunk<PRFH: Hasher + Clone, MSGH: Hasher + Clone, S: SeedProvider<PRFH>>(
+    params: &Params<PRFH, MSGH>,
+    provider: &S,
+    i: usize,
+) -> Result<SecretKey, WotsError> {
+    params.check()?;
+    if i >= params.total {
+        return Err(WotsError::InvalidLadderIndex);
+    }
+    let mut buf = SecretKey::zeroed(PRFH::size());
+    provider.prf(i as u8, buf.as_mut_bytes())?;
+    let mut sk = SecretKey::zeroed(params.n);
+    sk.as_mut_bytes().copy_from_slice(&buf[..params.n]);
+    Ok(sk)
+}
+
+/// Iterator over the secret ladder starts of a key, derived on demand; see `derive_sk_chunk`.
+pub struct SecretKeyChunks<'a, PRFH: Hasher + Clone, MSGH: Hasher + Clone, S> {
+    params: &'a Params<PRFH, MSGH>,
+    provider: &'a S,
+    next: usize,
+}
+
+impl<'a, PRFH: Hasher + Clone, MSGH: Hasher + Clone, S: SeedProvider<PRFH>>
+    SecretKeyChunks<'a, PRFH, MSGH, S>
+{
+    pub fn new(params: &'a Params<PRFH, MSGH>, provider: &'a S) -> Self {
+        SecretKeyChunks {
+            params,
+            provider,
+            next: 0,
+        }
+    }
+}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone, S: SeedProvider<PRFH>> Iterator
+    for SecretKeyChunks<'_, PRFH, MSGH, S>
+{
+    type Item = Result<SecretKey, WotsError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.params.total {
+            return None;
         }
-        Ok(self.build_signature(&sig))
+        self.next += 1;
+        Some(derive_sk_chunk(self.params, self.provider, self.next - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.params.total - self.next;
+        (left, Some(left))
+    }
+}
+
+/// Signs `msg` with the key `provider` derives for `p_seed`, holding one ladder's secret at a
+/// time: each ladder start is derived, walked up to its digit in place in the signature and
+/// zeroed before the next, so signing takes no `n * total` byte secret key. The signature is
+/// the one `Key::sign` makes. No `Key` records the signature, so the caller has to make sure
+/// the key signs one message only.
+pub fn sign_streaming<PRFH: Hasher + Clone, MSGH: Hasher + Clone, S: SeedProvider<PRFH>>(
+    params: &Params<PRFH, MSGH>,
+    provider: &S,
+    p_seed: &[u8; SEED_SIZE],
+    msg: impl AsRef<[u8]>,
+) -> Result<Vec<u8>, WotsError> {
+    params.check()?;
+    check_p_seed(p_seed)?;
+    let msg = msg.as_ref();
+    if msg.len() > MAX_MSG_SIZE {
+        return Err(WotsError::InvalidMessageSize);
     }
+    let digits = params.message_digits(Message::plain(msg));
+    let randomness = params.public_randomness(p_seed);
 
-    fn build_signature(&self, sig: &[u8]) -> Vec<u8> {
-        let encoding = self.params.get_encoding();
-        let mut sig_full = vec![0u8; 1 + SEED_SIZE + sig.len()];
-        sig_full[0] = encoding.into();
-        sig_full[1..1 + SEED_SIZE].copy_from_slice(&self.p_seed);
-        sig_full[1 + SEED_SIZE..].copy_from_slice(sig);
-        sig_full
+    let mut signature = vec![0u8; params.signature_size()];
+    signature[0] = params.get_encoding().into();
+    signature[1..1 + SEED_SIZE].copy_from_slice(p_seed);
+    let points = signature[1 + SEED_SIZE..].chunks_mut(params.n);
+    for (i, (point, sk)) in points
+        .zip(SecretKeyChunks::new(params, provider))
+        .enumerate()
+    {
+        point.copy_from_slice(&sk?);
+        walk_chain::<PRFH>(params.n, &randomness, point, None, i, (0, digits[i]));
     }
+    Ok(signature)
 }
 
-fn calculate_secret_key<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+fn calculate_secret_key<PRFH: Hasher + Clone, MSGH: Hasher + Clone, S: SeedProvider<PRFH>>(
     params: &Params<PRFH, MSGH>,
-    seed: &[u8],
-) -> Vec<u8> {
-    let mut sks = vec![0u8; params.n * params.total];
-    let mut buf = vec![0u8; PRFH::size()];
-    for i in 0..params.total {
-        let mut hasher = PRFH::new();
-        hasher.write(seed.to_vec());
-        hasher.write(vec![i as u8]);
-        hasher.sum(&mut buf);
-        sks[i * params.n..(i + 1) * params.n].copy_from_slice(&buf[0..params.n]);
+    provider: &S,
+) -> Result<SecretKey, WotsError> {
+    params.check()?;
+    let mut sks = SecretKey::zeroed(params.n * params.total);
+    let chunks = SecretKeyChunks::new(params, provider);
+    for (sk, chunk) in sks.as_mut_bytes().chunks_mut(params.n).zip(chunks) {
+        sk.copy_from_slice(&chunk?);
     }
-    sks
+    Ok(sks)
 }
 
 fn calculate_public_key<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
@@ -139,10 +1076,149 @@ fn calculate_public_key<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
 
 #[cfg(test)]
 mod tests {
+    use crate::backend::SoftwareBackend;
+    use crate::hasher::Sha3_224Hasher;
     use crate::hasher::{Blake2bHasher, Sha3_256Hasher};
-    use crate::keys::{Key, PK_SIZE};
-    use crate::params::{MAX_MSG_SIZE, SEED_SIZE};
-    use crate::security;
+    use crate::keys::{
+        derive_sk_chunk, public_key_from_seeds, sign_streaming, ChainCacheStrategy,
+        GenerateCheckpoint, Key, KeyState, PublicKey, SecretKeyChunks, PK_SIZE,
+    };
+    use crate::params::{WotsError, MAX_MSG_SIZE, MAX_SEED_SIZE, SEED_SIZE, W};
+    use crate::secret::{SecretKey, SecretSeed};
+    use crate::security::{self, ParamsEncoding};
+    use crate::signature::Signature;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn secret_key_chunks() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let seed = key.seed.as_ref().unwrap();
+
+        let chunks: Vec<_> = SecretKeyChunks::new(&params, seed)
+            .map(|chunk| chunk.unwrap().to_vec())
+            .collect();
+        assert_eq!(chunks.len(), params.total);
+        assert_eq!(chunks.concat(), key.secret_key.to_vec());
+        assert_eq!(
+            &derive_sk_chunk(&params, seed, 7).unwrap()[..],
+            &key.secret_key[7 * params.n..8 * params.n]
+        );
+        assert_eq!(
+            derive_sk_chunk(&params, seed, params.total).err(),
+            Some(WotsError::InvalidLadderIndex)
+        );
+    }
+
+    #[test]
+    fn streaming_sign() {
+        let params = security::level_1_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let seed = key.seed.as_ref().unwrap();
+        let p_seed = [2u8; SEED_SIZE];
+
+        let signature = sign_streaming(&params, seed, &p_seed, b"streamed").unwrap();
+        assert_eq!(signature, key.sign(b"streamed").unwrap());
+        security::verify(b"streamed", &signature, &key.public_key).unwrap();
+
+        let long = [0u8; MAX_MSG_SIZE + 1];
+        assert_eq!(
+            sign_streaming(&params, seed, &p_seed, long).err(),
+            Some(WotsError::InvalidMessageSize)
+        );
+        assert_eq!(
+            sign_streaming(&params, seed, &[0u8; SEED_SIZE], b"streamed").err(),
+            Some(WotsError::DegenerateSeed)
+        );
+    }
+
+    #[test]
+    fn long_seed() {
+        let params = security::level_3_params::<Blake2bHasher, Sha3_224Hasher>()
+            .with_seed_size(48)
+            .unwrap();
+        let seed = SecretSeed::from_slice(&[1u8; 48]).unwrap();
+        let key = Key::from_secret_seed(params.clone(), seed, [2u8; SEED_SIZE]).unwrap();
+        let short = Key::from_seed(
+            security::level_3_params::<Blake2bHasher, Sha3_224Hasher>(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        assert_ne!(key.public_key, short.public_key);
+        let signature = key.sign(b"hello").unwrap();
+        security::verify(b"hello", &signature, &key.public_key).unwrap();
+
+        assert_eq!(
+            Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).err(),
+            Some(WotsError::InvalidSeedSize)
+        );
+        assert_eq!(Key::new(params).unwrap().seed.unwrap().len(), 48);
+        assert!(security::level_3_params::<Blake2bHasher, Sha3_224Hasher>()
+            .with_seed_size(MAX_SEED_SIZE + 1)
+            .is_err());
+        assert!(SecretSeed::from_slice(&[1u8; SEED_SIZE - 1]).is_err());
+    }
+
+    #[test]
+    fn resume_generate() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let mut key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let mut resumed = key.clone();
+        key.generate_with_strategy(ChainCacheStrategy::Sparse(8))
+            .unwrap();
+
+        // walk 5 ladders at a time, round-tripping the checkpoint through bytes in between
+        let mut checkpoint = resumed
+            .start_generate(ChainCacheStrategy::Sparse(8))
+            .unwrap();
+        let mut steps = 0;
+        while let Some(pending) = resumed.resume_generate(checkpoint, 5).unwrap() {
+            assert_eq!(pending.ladders_done(), 5 * (steps + 1));
+            checkpoint = GenerateCheckpoint::from_bytes(&pending.to_bytes()).unwrap();
+            steps += 1;
+        }
+        assert_eq!(steps, params.total / 5);
+        assert_eq!(resumed.chains, key.chains);
+        assert_eq!(resumed.sign(b"msg").unwrap(), key.sign(b"msg").unwrap());
+
+        let other = Key::from_seed(params, [3u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let checkpoint = other.start_generate(ChainCacheStrategy::Full).unwrap();
+        assert_eq!(
+            resumed.resume_generate(checkpoint.clone(), 1).err(),
+            Some(WotsError::InvalidCheckpoint)
+        );
+        let bytes = checkpoint.to_bytes();
+        assert_eq!(
+            GenerateCheckpoint::from_bytes(&bytes[..bytes.len() - 1]).err(),
+            Some(WotsError::InvalidCheckpoint)
+        );
+    }
+
+    #[test]
+    fn public_key_verify() {
+        let params = security::level_1_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let public_key = key.verifying_key().unwrap();
+        let signature = Signature::try_from(key.sign(b"hello").unwrap()).unwrap();
+
+        public_key.verify(b"hello", &signature).unwrap();
+        assert_eq!(
+            public_key.verify(b"hellp", &signature),
+            Err(WotsError::InvalidSignature)
+        );
+
+        assert_eq!(
+            PublicKey::try_from(&key.public_key[..]).unwrap(),
+            public_key
+        );
+        assert_eq!(
+            PublicKey::try_from(&key.public_key[1..]),
+            Err(WotsError::InvalidPublicKeySize)
+        );
+        assert_eq!(public_key.to_string(), hex::encode(&key.public_key));
+        assert_eq!(public_key.as_ref(), &key.public_key[..]);
+    }
 
     #[test]
     fn key_generate() {
@@ -151,6 +1227,21 @@ mod tests {
         key.generate().unwrap();
     }
 
+    #[test]
+    fn key_generate_parallel() {
+        let params = security::consensus_params();
+        let mut key = Key::<Blake2bHasher, Sha3_256Hasher>::new(params).unwrap();
+        let mut parallel_key = key.clone();
+        key.generate().unwrap();
+
+        // should match the sequential chains regardless of how ladders are split
+        for threads in [0, 1, 3, 64] {
+            parallel_key.chains = None;
+            parallel_key.generate_parallel(threads).unwrap();
+            assert_eq!(parallel_key.chains, key.chains);
+        }
+    }
+
     #[test]
     fn key_public_key() {
         let params = security::consensus_params();
@@ -159,6 +1250,35 @@ mod tests {
         // TODO: should pubkey size still be 32 even w/ level0 etc. params?
     }
 
+    #[test]
+    fn public_key_only() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let seed = SecretSeed::new([1u8; SEED_SIZE]);
+        let public_key = public_key_from_seeds(&params, &seed, &[2u8; SEED_SIZE]).unwrap();
+        assert_eq!(public_key, key.verifying_key().unwrap());
+        assert_eq!(
+            public_key_from_seeds(&params, &seed, &[0u8; SEED_SIZE]),
+            Err(WotsError::DegenerateSeed)
+        );
+    }
+
+    #[test]
+    fn key_getters() {
+        let params = security::level_2_params::<Blake2bHasher, Sha3_224Hasher>();
+        let mut key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        assert_eq!(key.encoding(), &ParamsEncoding::Level2);
+        assert_eq!(key.params().n, params.n);
+        assert_eq!(key.signature_size(), key.sign(b"msg").unwrap().len());
+        assert_eq!(key.public_key_size(), key.public_key.len());
+        assert_eq!(key.secret_key_size(), key.secret_key.len());
+        assert_eq!(key.p_seed(), &[2u8; SEED_SIZE]);
+        assert_eq!(&key.seed().unwrap()[..], &[1u8; SEED_SIZE]);
+        assert!(key.chains().is_none());
+        key.generate().unwrap();
+        assert!(key.chains().is_some());
+    }
+
     #[test]
     fn key_public_key_generate() {
         let params = security::consensus_params();
@@ -183,6 +1303,186 @@ mod tests {
         let msg = vec![99u8; MAX_MSG_SIZE];
         let res = key.sign(&msg).unwrap();
         assert_eq!(res.len(), sig_size);
+
+        // owned and borrowed messages, signatures and keys are all accepted
+        let sig = key.sign(msg.clone()).unwrap();
+        let pk: [u8; 32] = *key.verifying_key().unwrap().as_bytes();
+        key.params.verify(msg, &sig[1..], pk).unwrap();
+        let other = Key::<Blake2bHasher, Sha3_256Hasher>::new(key.params.clone()).unwrap();
+        let sig = other.sign("text").unwrap();
+        security::verify("text", sig, other.verifying_key().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn key_sign_into() {
+        let params = security::consensus_params();
+        let sig_size = params.signature_size();
+        let mut key = Key::<Blake2bHasher, Sha3_256Hasher>::new(params).unwrap();
+        let msg = vec![99u8; MAX_MSG_SIZE];
+
+        // should fail with a wrongly sized buffer
+        let mut out = vec![0u8; sig_size - 1];
+        assert!(key.sign_into(&msg, &mut out).is_err());
+
+        // should match sign, with and without cached chains
+        let mut out = vec![0u8; sig_size];
+        key.sign_into(&msg, &mut out).unwrap();
+        assert_eq!(out, key.sign(&msg).unwrap());
+
+        key.generate().unwrap();
+        let mut fast_out = vec![0u8; sig_size];
+        key.sign_into(&msg, &mut fast_out).unwrap();
+        assert_eq!(out, fast_out);
+    }
+
+    #[test]
+    fn key_sign_sparse_cache() {
+        let params = security::consensus_params();
+        let mut key = Key::<Blake2bHasher, Sha3_256Hasher>::new(params).unwrap();
+        let msg = vec![99u8; MAX_MSG_SIZE];
+        let sig = key.sign(&msg).unwrap();
+
+        // should fail with a zero stride
+        let res = key.generate_with_strategy(ChainCacheStrategy::Sparse(0));
+        assert!(res.is_err());
+
+        // should keep every 16th level and still produce identical signatures
+        key.generate_with_strategy(ChainCacheStrategy::Sparse(16))
+            .unwrap();
+        assert_eq!(key.chains.as_ref().unwrap().levels(), 16);
+        assert_eq!(key.sign(&msg).unwrap(), sig);
+
+        // should replace the sparse cache when asking for the full one
+        key.generate().unwrap();
+        assert_eq!(key.chains.as_ref().unwrap().levels(), W);
+        assert_eq!(key.sign(&msg).unwrap(), sig);
+    }
+
+    #[test]
+    fn key_chain_cache_export() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let mut key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        assert_eq!(key.export_chain_cache(), Err(WotsError::ChainsNotSet));
+        key.generate_with_strategy(ChainCacheStrategy::Sparse(16))
+            .unwrap();
+        let exported = key.export_chain_cache().unwrap();
+
+        let mut restored =
+            Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        restored.import_chain_cache(&exported).unwrap();
+        assert_eq!(restored.chains, key.chains);
+        assert_eq!(restored.sign(b"msg").unwrap(), key.sign(b"msg").unwrap());
+
+        // a flipped bit in any ladder level is caught before it is used for signing
+        let mut corrupted = exported.clone();
+        corrupted[exported.len() / 2] ^= 1;
+        let mut fresh = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        assert_eq!(
+            fresh.import_chain_cache(&corrupted),
+            Err(WotsError::ChainCacheTampered)
+        );
+        assert!(fresh.chains.is_none());
+
+        let mut other = Key::from_seed(params, [3u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        assert_eq!(
+            other.import_chain_cache(&exported),
+            Err(WotsError::InvalidChainCache)
+        );
+    }
+
+    #[test]
+    fn key_lifecycle() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let mut key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        assert_eq!(key.state(), KeyState::Fresh);
+        key.generate().unwrap();
+        assert_eq!(key.state(), KeyState::Generated);
+        // the state is recorded, not read off the cache
+        let cache = key.export_chain_cache().unwrap();
+        key.chains = None;
+        assert_eq!(key.state(), KeyState::Generated);
+
+        let mut imported =
+            Key::from_seed(key.params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        assert_eq!(imported.state(), KeyState::Fresh);
+        imported.import_chain_cache(&cache).unwrap();
+        assert_eq!(imported.state(), KeyState::Generated);
+        let mut resumed =
+            Key::from_seed(key.params.clone(), [3u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let checkpoint = resumed.start_generate(ChainCacheStrategy::Full).unwrap();
+        let checkpoint = resumed.resume_generate(checkpoint, 1).unwrap().unwrap();
+        assert_eq!(resumed.state(), KeyState::Fresh);
+        assert!(resumed
+            .resume_generate(checkpoint, usize::MAX)
+            .unwrap()
+            .is_none());
+        assert_eq!(resumed.state(), KeyState::Generated);
+
+        let sig = key.sign(b"once").unwrap();
+        assert_eq!(key.state(), KeyState::Signed);
+        // states never go back
+        key.generate().unwrap();
+        assert_eq!(key.state(), KeyState::Signed);
+        assert_eq!(key.sign(b"once").unwrap(), sig);
+        assert_eq!(key.sign(b"twice"), Err(WotsError::KeyAlreadyUsed));
+        // clones hold the same one-time key
+        let clone = key.clone();
+        assert_eq!(clone.sign(b"twice"), Err(WotsError::KeyAlreadyUsed));
+
+        key.destroy();
+        assert_eq!(key.state(), KeyState::Destroyed);
+        assert_eq!(clone.state(), KeyState::Destroyed);
+        assert!(key.seed.is_none() && key.chains.is_none() && key.secret_key.is_empty());
+        assert_eq!(key.sign(b"once"), Err(WotsError::KeyDestroyed));
+        assert_eq!(key.generate(), Err(WotsError::KeyDestroyed));
+        security::verify(b"once", &sig, &key.public_key).unwrap();
+    }
+
+    #[test]
+    fn sign_replays_signature() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let mut key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let sig = key.sign(b"once").unwrap();
+        // signing again returns the recorded signature instead of computing it
+        key.secret_key = SecretKey::zeroed(key.secret_key.len());
+        assert_eq!(key.sign(b"once").unwrap(), sig);
+        assert_eq!(key.sign_with(b"once", &SoftwareBackend).unwrap(), sig);
+        let mut out = vec![0u8; sig.len()];
+        key.clone().sign_into(b"once", &mut out).unwrap();
+        assert_eq!(out, sig);
+        assert_eq!(key.sign(b"twice"), Err(WotsError::KeyAlreadyUsed));
+    }
+
+    #[test]
+    fn sign_many_unique() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let sig = key.sign_many_unique(b"once").unwrap();
+        assert_eq!(key.state(), KeyState::Signed);
+        assert_eq!(key.clone().sign_many_unique(b"once").unwrap(), sig);
+        assert_eq!(
+            key.sign_many_unique(b"twice"),
+            Err(WotsError::KeyAlreadyUsed)
+        );
+        assert_eq!(key.sign(b"twice"), Err(WotsError::KeyAlreadyUsed));
+        security::verify(b"once", &sig, &key.public_key).unwrap();
+
+        // a message first signed through `sign` can be retried
+        let key = Key::from_seed(params.clone(), [3u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let sig = key.sign(b"once").unwrap();
+        assert_eq!(key.sign_many_unique(b"once").unwrap(), sig);
+        assert_eq!(
+            key.sign_many_unique(b"twice"),
+            Err(WotsError::KeyAlreadyUsed)
+        );
+
+        // but not one signed with a context
+        let key = Key::from_seed(params, [4u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        key.sign_with_context(b"once", b"ctx").unwrap();
+        assert_eq!(
+            key.sign_many_unique(b"once"),
+            Err(WotsError::KeyAlreadyUsed)
+        );
     }
 
     #[test]
@@ -0,0 +1,157 @@
+use crate::backend::ChainRows;
+use crate::params::WotsError;
+
+/// Read-only view of one value per ladder, e.g. the points of a signature or the secret
+/// ladder starts: `total` ladders of `n` bytes each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LadderPoints<'a> {
+    bytes: &'a [u8],
+    n: usize,
+}
+
+impl<'a> LadderPoints<'a> {
+    /// Fails with `WotsError::InvalidPointsSize` unless `bytes` is exactly `n * total` bytes.
+    pub fn new(bytes: &'a [u8], n: usize, total: usize) -> Result<Self, WotsError> {
+        if n == 0 || bytes.len() != n * total {
+            return Err(WotsError::InvalidPointsSize);
+        }
+        Ok(LadderPoints { bytes, n })
+    }
+
+    /// Value of ladder `i`; panics if `i >= len()`.
+    pub fn ladder(&self, i: usize) -> &'a [u8] {
+        &self.bytes[i * self.n..(i + 1) * self.n]
+    }
+
+    /// Number of ladders.
+    pub fn len(&self) -> usize {
+        self.bytes.len() / self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a [u8]> {
+        self.bytes.chunks(self.n)
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+/// Ladder levels cached by `Key::generate_with_strategy`: every `stride`-th level of every
+/// ladder, starting from level 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainCache {
+    rows: Vec<Vec<u8>>,
+    n: usize,
+    stride: usize,
+}
+
+impl ChainCache {
+    // zeroed cache for `total` ladders of `n` bytes and `w` levels, filled in through
+    // `rows_mut`
+    pub(crate) fn new(n: usize, w: usize, total: usize, stride: usize) -> Self {
+        ChainCache {
+            rows: vec![vec![0u8; n * total]; (w - 1) / stride + 1],
+            n,
+            stride,
+        }
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Number of cached levels.
+    pub fn levels(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Values of every ladder at level `j`; `None` unless `j` is a cached level.
+    pub fn level(&self, j: usize) -> Option<LadderPoints<'_>> {
+        if !j.is_multiple_of(self.stride) {
+            return None;
+        }
+        let row = self.rows.get(j / self.stride)?;
+        Some(LadderPoints {
+            bytes: row,
+            n: self.n,
+        })
+    }
+
+    /// Nearest cached level at or below `j`, with the values of every ladder at it.
+    pub fn nearest_level(&self, j: usize) -> (usize, LadderPoints<'_>) {
+        let level = (j / self.stride).min(self.rows.len() - 1) * self.stride;
+        (level, self.level(level).expect("level is cached"))
+    }
+
+    pub(crate) fn n(&self) -> usize {
+        self.n
+    }
+
+    // whether the cache holds the levels of ladders of `w` levels
+    pub(crate) fn fits_w(&self, w: usize) -> bool {
+        self.rows.len() == (w - 1) / self.stride + 1
+    }
+
+    pub(crate) fn rows(&self) -> &[Vec<u8>] {
+        &self.rows
+    }
+
+    // rebuilds a cache from rows written out by `rows`; `None` if they don't fit the shape.
+    // The number of rows depends on `w`, which callers check.
+    pub(crate) fn from_rows(rows: Vec<Vec<u8>>, n: usize, stride: usize) -> Option<Self> {
+        let row_len = rows.first()?.len();
+        let valid = n > 0
+            && stride > 0
+            && row_len.is_multiple_of(n)
+            && rows.iter().all(|row| row.len() == row_len);
+        valid.then_some(ChainCache { rows, n, stride })
+    }
+
+    pub(crate) fn rows_mut(&mut self) -> ChainRows<'_> {
+        ChainRows {
+            rows: self.rows.iter_mut().map(Vec::as_mut_slice).collect(),
+            stride: self.stride,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ladders::{ChainCache, LadderPoints};
+    use crate::params::{WotsError, W};
+
+    #[test]
+    fn ladder_points() {
+        let bytes: Vec<u8> = (0..12).collect();
+        let points = LadderPoints::new(&bytes, 4, 3).unwrap();
+        assert_eq!(points.len(), 3);
+        assert_eq!(points.ladder(1), &[4, 5, 6, 7]);
+        assert_eq!(points.iter().count(), 3);
+
+        assert_eq!(
+            LadderPoints::new(&bytes, 4, 4),
+            Err(WotsError::InvalidPointsSize)
+        );
+        assert_eq!(
+            LadderPoints::new(&bytes, 0, 3),
+            Err(WotsError::InvalidPointsSize)
+        );
+    }
+
+    #[test]
+    fn chain_cache() {
+        let cache = ChainCache::new(4, W, 3, 16);
+        assert_eq!(cache.levels(), 16);
+        assert!(cache.level(32).is_some());
+        assert!(cache.level(33).is_none());
+        assert!(cache.level(W).is_none());
+        assert_eq!(cache.nearest_level(33).0, 32);
+        assert_eq!(cache.nearest_level(W - 1).0, 240);
+        assert_eq!(cache.level(0).unwrap().len(), 3);
+    }
+}
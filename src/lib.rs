@@ -1,8 +1,52 @@
+pub mod analysis;
+pub mod backend;
+pub mod bundle;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod certificate;
+pub mod codec;
+#[cfg(feature = "serde")]
+pub mod config;
+#[cfg(feature = "courier")]
+pub mod courier;
+pub mod digits;
+#[cfg(feature = "std")]
+pub mod directory;
+pub mod entropy;
+pub mod envelope;
+pub mod fixtures;
 pub mod hasher;
+#[cfg(feature = "hybrid")]
+pub mod hybrid;
+#[cfg(feature = "kdf")]
+pub mod kdf;
+pub mod key_set;
 pub mod keys;
+pub mod ladders;
+pub mod metrics;
+mod negative_vectors;
 pub mod params;
+#[cfg(all(kani, feature = "verify-proofs"))]
+mod proofs;
+#[cfg(feature = "r1cs")]
+pub mod r1cs;
+pub mod randomness;
+#[cfg(feature = "seal")]
+pub mod seal;
+pub mod secret;
 pub mod security;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod signature;
+#[cfg(feature = "sss")]
+pub mod sss;
+#[cfg(feature = "test_utils")]
+pub mod test_utils;
 mod test_vectors;
+pub mod trace;
+pub mod traits;
+pub mod verify_table;
+pub mod wire;
 
 #[cfg(not(feature = "std"))]
 mod std {
@@ -0,0 +1,304 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::backend::{ChainBackend, ChainRows, LadderJob};
+use crate::hasher::Hasher;
+use crate::params::WotsError;
+#[cfg(feature = "std")]
+use crate::params::{hash_buf, StepIndex};
+
+/// Operations whose latency `Metrics::operation` reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    KeyGen,
+    Generate,
+    Sign,
+    Verify,
+}
+
+impl Operation {
+    fn index(self) -> usize {
+        match self {
+            Operation::KeyGen => 0,
+            Operation::Generate => 1,
+            Operation::Sign => 2,
+            Operation::Verify => 3,
+        }
+    }
+}
+
+/// Sink for hash counts and latencies, e.g. to export them to Prometheus. Every method
+/// defaults to doing nothing.
+pub trait Metrics {
+    /// Called once per ladder walk with the randomizer PRF calls it relied on and the chain
+    /// steps (one hash each) it took.
+    fn ladder_walk(&self, _prf_calls: u64, _steps: u64) {}
+
+    /// Called with the latency of an operation run through `timed`.
+    #[cfg(feature = "std")]
+    fn operation(&self, _op: Operation, _elapsed: Duration) {}
+}
+
+/// Runs `f`, reporting its latency to `metrics` as `op`.
+#[cfg(feature = "std")]
+pub fn timed<T>(metrics: &dyn Metrics, op: Operation, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let res = f();
+    metrics.operation(op, start.elapsed());
+    res
+}
+
+/// Backend reporting every walk to `metrics` before handing it to `inner`.
+pub struct MeteredBackend<'a, B> {
+    inner: B,
+    metrics: &'a dyn Metrics,
+}
+
+impl<'a, B> MeteredBackend<'a, B> {
+    pub fn new(inner: B, metrics: &'a dyn Metrics) -> Self {
+        MeteredBackend { inner, metrics }
+    }
+}
+
+impl<H: Hasher, B: ChainBackend<H>> ChainBackend<H> for MeteredBackend<'_, B> {
+    fn walk_ladders(
+        &self,
+        job: &LadderJob,
+        values: &mut [u8],
+        chains: Option<&mut ChainRows>,
+    ) -> Result<(), WotsError> {
+        let steps = job
+            .ranges
+            .iter()
+            .map(|&(begin, end)| end.saturating_sub(begin) as u64)
+            .sum();
+        self.metrics.ladder_walk(job.randomness.len() as u64, steps);
+        self.inner.walk_ladders(job, values, chains)
+    }
+}
+
+/// `Metrics` keeping running totals in atomics.
+#[derive(Debug, Default)]
+pub struct Counters {
+    pub prf_calls: AtomicU64,
+    pub ladder_steps: AtomicU64,
+    operations: [AtomicU64; 4],
+    nanos: [AtomicU64; 4],
+}
+
+impl Counters {
+    /// Number of `op` operations timed so far.
+    pub fn operations(&self, op: Operation) -> u64 {
+        self.operations[op.index()].load(Ordering::Relaxed)
+    }
+
+    /// Total time spent in `op` operations, in nanoseconds.
+    pub fn nanos(&self, op: Operation) -> u64 {
+        self.nanos[op.index()].load(Ordering::Relaxed)
+    }
+}
+
+impl Metrics for Counters {
+    fn ladder_walk(&self, prf_calls: u64, steps: u64) {
+        self.prf_calls
+            .fetch_add(prf_calls + steps, Ordering::Relaxed);
+        self.ladder_steps.fetch_add(steps, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "std")]
+    fn operation(&self, op: Operation, elapsed: Duration) {
+        self.operations[op.index()].fetch_add(1, Ordering::Relaxed);
+        self.nanos[op.index()].fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// Where the time of one ladder walk went, as reported by `ProfiledBackend`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LadderProfile {
+    pub ladder: usize,
+
+    /// chain steps taken, one hash each
+    pub hashes: u64,
+
+    /// instantiating the hasher
+    pub init: Duration,
+
+    /// xoring the value with its randomizer
+    pub xor: Duration,
+
+    /// writing `p_seed`, the step and the value into the hasher
+    pub absorb: Duration,
+
+    /// finalizing the hasher and truncating its output
+    pub finalize: Duration,
+}
+
+#[cfg(feature = "std")]
+impl LadderProfile {
+    pub fn total(&self) -> Duration {
+        self.init + self.xor + self.absorb + self.finalize
+    }
+}
+
+/// Callback receiving the profile of every ladder `ProfiledBackend` walks.
+#[cfg(feature = "std")]
+pub trait ChainBackendMetrics {
+    fn ladder(&self, profile: &LadderProfile);
+}
+
+#[cfg(feature = "std")]
+impl<F: Fn(&LadderProfile)> ChainBackendMetrics for F {
+    fn ladder(&self, profile: &LadderProfile) {
+        self(profile)
+    }
+}
+
+/// Backend walking the ladders like `SoftwareBackend`, timing each phase of every step, to
+/// see whether hasher instantiation, XOR or finalization dominates on a target before
+/// choosing a hasher. Reading the clock four times per step slows the walk down noticeably,
+/// so compare phases with each other rather than with unprofiled runs.
+#[cfg(feature = "std")]
+pub struct ProfiledBackend<'a> {
+    metrics: &'a dyn ChainBackendMetrics,
+}
+
+#[cfg(feature = "std")]
+impl<'a> ProfiledBackend<'a> {
+    pub fn new(metrics: &'a dyn ChainBackendMetrics) -> Self {
+        ProfiledBackend { metrics }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: Hasher> ChainBackend<H> for ProfiledBackend<'_> {
+    fn walk_ladders(
+        &self,
+        job: &LadderJob,
+        values: &mut [u8],
+        mut chains: Option<&mut ChainRows>,
+    ) -> Result<(), WotsError> {
+        let randomness = job.randomness;
+        let mut preimage = hash_buf(job.n);
+        let mut buf = hash_buf(H::size());
+
+        for (i, value) in values.chunks_mut(job.n).enumerate() {
+            let (begin, end) = job.ranges[i];
+            let mut profile = LadderProfile {
+                ladder: i,
+                ..LadderProfile::default()
+            };
+            for j in begin..end {
+                let start = Instant::now();
+                let mut hasher = H::new();
+                let instantiated = Instant::now();
+                preimage
+                    .iter_mut()
+                    .zip(value.iter().zip(randomness.element(j as usize)))
+                    .for_each(|(p, (&x1, &x2))| *p = x1 ^ x2);
+                let xored = Instant::now();
+                hasher.write(randomness.p_seed());
+                hasher.write(StepIndex::new(randomness.w(), j as usize + 1).as_bytes());
+                hasher.write(&preimage);
+                let absorbed = Instant::now();
+                hasher.sum(&mut buf);
+                value.copy_from_slice(&buf[0..job.n]);
+                let finalized = Instant::now();
+
+                profile.hashes += 1;
+                profile.init += instantiated - start;
+                profile.xor += xored - instantiated;
+                profile.absorb += absorbed - xored;
+                profile.finalize += finalized - absorbed;
+                if let Some(chains) = chains.as_deref_mut() {
+                    chains.record(j as usize + 1, i, value);
+                }
+            }
+            self.metrics.ladder(&profile);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use crate::backend::SoftwareBackend;
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::{ChainCacheStrategy, Key};
+    use crate::metrics::{
+        self, Counters, LadderProfile, MeteredBackend, Operation, ProfiledBackend,
+    };
+    use crate::params::{SEED_SIZE, W};
+    use crate::security;
+
+    #[test]
+    fn metered_operations() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let counters = Counters::default();
+        let backend = MeteredBackend::new(SoftwareBackend, &counters);
+        let mut key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let msg = b"XX NETWORK";
+        let digits = params.msg_hash_and_compute_checksum(msg);
+
+        let signature =
+            metrics::timed(&counters, Operation::Sign, || key.sign_with(msg, &backend)).unwrap();
+        let sign_steps: u64 = digits.iter().map(|&d| d as u64).sum();
+        assert_eq!(counters.ladder_steps.load(Ordering::Relaxed), sign_steps);
+        assert_eq!(counters.operations(Operation::Sign), 1);
+
+        metrics::timed(&counters, Operation::Verify, || {
+            params.verify_with(&backend, msg, &signature[1..], &key.public_key)
+        })
+        .unwrap();
+        let total_steps = (params.total * (W - 1)) as u64;
+        assert_eq!(counters.ladder_steps.load(Ordering::Relaxed), total_steps);
+        assert_eq!(
+            counters.prf_calls.load(Ordering::Relaxed),
+            total_steps + 2 * (W as u64 - 1)
+        );
+
+        key.generate_with(ChainCacheStrategy::Full, &backend)
+            .unwrap();
+        assert_eq!(
+            counters.ladder_steps.load(Ordering::Relaxed),
+            2 * total_steps
+        );
+        assert_eq!(counters.operations(Operation::Verify), 1);
+        assert_eq!(counters.operations(Operation::Generate), 0);
+    }
+
+    #[test]
+    fn profiled_backend() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let msg = b"XX NETWORK";
+        let signature = key.sign(msg).unwrap();
+
+        let profiles = std::sync::Mutex::new(Vec::new());
+        let record = |profile: &LadderProfile| profiles.lock().unwrap().push(*profile);
+        let backend = ProfiledBackend::new(&record);
+        params
+            .verify_with(&backend, msg, &signature[1..], &key.public_key)
+            .unwrap();
+
+        let profiles = profiles.into_inner().unwrap();
+        let digits = params.msg_hash_and_compute_checksum(msg);
+        assert_eq!(profiles.len(), params.total);
+        for (i, profile) in profiles.iter().enumerate() {
+            assert_eq!(profile.ladder, i);
+            assert_eq!(profile.hashes, (W - 1) as u64 - digits[i] as u64);
+            assert!(profile.total() >= profile.finalize);
+        }
+
+        // cached ladders match the ones `SoftwareBackend` records
+        let ignore = |_: &LadderProfile| {};
+        let mut cached = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        cached
+            .generate_with(ChainCacheStrategy::Full, &ProfiledBackend::new(&ignore))
+            .unwrap();
+        assert_eq!(cached.sign(msg).unwrap(), signature);
+    }
+}
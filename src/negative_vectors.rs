@@ -0,0 +1,308 @@
+#[cfg(test)]
+mod tests {
+    use crate::hasher::{Blake2bHasher, Hasher, Sha3_224Hasher, Sha3_256Hasher};
+    use crate::keys::{ChainCacheStrategy, Key, PK_SIZE};
+    use crate::params::{ComputeLaddersMode, Params, WotsError, MAX_MSG_SIZE, SEED_SIZE, W};
+    use crate::security::{self, verify, verify_no_consensus, ParamsEncoding};
+    use crate::signature::SignatureHeader;
+    use crate::wire::SIGNATURE_HEADER_VERSION;
+
+    const MSG: &[u8; 10] = b"XX NETWORK";
+
+    // A signature mutation and the error verifying the mutated signature must produce.
+    struct Case {
+        comment: String,
+        signature: Vec<u8>,
+        msg: Vec<u8>,
+        public_key: Vec<u8>,
+        expected: WotsError,
+    }
+
+    fn key<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        params: Params<PRFH, MSGH>,
+        seed: u8,
+    ) -> Key<PRFH, MSGH> {
+        Key::from_seed(params, [seed; SEED_SIZE], [seed ^ 0xff; SEED_SIZE]).unwrap()
+    }
+
+    // structural_cases builds the mutations of a valid `signature` that every encoding must
+    // reject.
+    fn structural_cases<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        params: &Params<PRFH, MSGH>,
+        signature: &[u8],
+        public_key: &[u8],
+        other_p_seed: &[u8],
+    ) -> Vec<Case> {
+        let case = |comment: String, signature: Vec<u8>, expected| Case {
+            comment,
+            signature,
+            msg: MSG.to_vec(),
+            public_key: public_key.to_vec(),
+            expected,
+        };
+        let mut cases = Vec::new();
+
+        for i in 0..params.total {
+            let mut sig = signature.to_vec();
+            sig[1 + SEED_SIZE + i * params.n] ^= 0x80;
+            cases.push(case(
+                format!("bit flipped in ladder {}", i),
+                sig,
+                WotsError::InvalidSignature,
+            ));
+        }
+
+        for len in [0, 1, SEED_SIZE, SEED_SIZE + 1, signature.len() - 1] {
+            cases.push(case(
+                format!("truncated to {} bytes", len),
+                signature[..len].to_vec(),
+                WotsError::InvalidSignatureSize,
+            ));
+        }
+
+        let mut sig = signature.to_vec();
+        sig.push(0);
+        cases.push(case(
+            "trailing byte".to_string(),
+            sig,
+            WotsError::InvalidSignatureSize,
+        ));
+
+        let mut sig = signature.to_vec();
+        sig[1..1 + SEED_SIZE].copy_from_slice(other_p_seed);
+        cases.push(case(
+            "p_seed of another key".to_string(),
+            sig,
+            WotsError::InvalidSignature,
+        ));
+
+        for byte in 0..=255u8 {
+            if byte == signature[0] {
+                continue;
+            }
+            let mut sig = signature.to_vec();
+            sig[0] = byte;
+            let expected = match SignatureHeader::from_byte(byte) {
+                Ok(header) if header.version != SIGNATURE_HEADER_VERSION => {
+                    WotsError::UnsupportedSignatureVersion(header.version)
+                }
+                Ok(SignatureHeader {
+                    encoding: ParamsEncoding::Custom,
+                    ..
+                }) => WotsError::InvalidParamsEncodingType,
+                // every predefined encoding has a different signature size
+                Ok(_) => WotsError::InvalidSignatureSize,
+                Err(err) => err,
+            };
+            cases.push(case(format!("encoding byte {}", byte), sig, expected));
+        }
+
+        let mut msg = MSG.to_vec();
+        msg[0] ^= 0x01;
+        cases.push(Case {
+            comment: "different message".to_string(),
+            signature: signature.to_vec(),
+            msg,
+            public_key: public_key.to_vec(),
+            expected: WotsError::InvalidSignature,
+        });
+
+        for len in [0, PK_SIZE - 1, PK_SIZE + 1] {
+            cases.push(Case {
+                comment: format!("public key of {} bytes", len),
+                signature: signature.to_vec(),
+                msg: MSG.to_vec(),
+                public_key: vec![7u8; len],
+                expected: WotsError::InvalidPublicKeySize,
+            });
+        }
+
+        cases
+    }
+
+    fn run_cases(cases: &[Case]) {
+        for case in cases {
+            let res = verify(&case.msg, &case.signature, &case.public_key);
+            assert_eq!(res, Err(case.expected.clone()), "{}", case.comment);
+        }
+    }
+
+    fn check_encoding<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(params: Params<PRFH, MSGH>) {
+        let signer = key(params.clone(), 1);
+        let other = key(params.clone(), 2);
+        let signature = signer.sign(MSG).unwrap();
+        verify(MSG, &signature, &signer.public_key).unwrap();
+
+        run_cases(&structural_cases(
+            &params,
+            &signature,
+            &signer.public_key,
+            &other.p_seed,
+        ));
+    }
+
+    #[test]
+    fn mutated_signatures_test() {
+        check_encoding::<Blake2bHasher, Sha3_224Hasher>(security::level_0_params());
+        check_encoding::<Blake2bHasher, Sha3_224Hasher>(security::level_3_params());
+        check_encoding::<Blake2bHasher, Sha3_256Hasher>(security::consensus_params());
+    }
+
+    #[test]
+    fn swapped_seeds_test() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let swapped = Key::from_seed(params, [2u8; SEED_SIZE], [1u8; SEED_SIZE]).unwrap();
+        assert_ne!(key.public_key, swapped.public_key);
+
+        let signature = key.sign(MSG).unwrap();
+        let res = verify(MSG, &signature, &swapped.public_key);
+        assert_eq!(res, Err(WotsError::InvalidSignature));
+    }
+
+    // find_message returns the first counter message whose digits contain `digit`.
+    fn find_message<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        params: &Params<PRFH, MSGH>,
+        digit: u8,
+    ) -> (Vec<u8>, usize) {
+        for counter in 0u32.. {
+            let msg = counter.to_be_bytes().to_vec();
+            let digits = params.msg_hash_and_compute_checksum(&msg);
+            if let Some(pos) = digits[..params.m].iter().position(|&d| d == digit as u16) {
+                return (msg, pos);
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn boundary_digits_test() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        for digit in [0u8, (W - 1) as u8] {
+            let signer = key(params.clone(), 3);
+            let (msg, pos) = find_message(&params, digit);
+            let signature = signer.sign(&msg).unwrap();
+            verify(&msg, &signature, &signer.public_key).unwrap();
+
+            // a digit of 0 reveals the secret key point, a digit of W - 1 the ladder top
+            let point = &signature[1 + SEED_SIZE + pos * params.n..][..params.n];
+            let secret = &signer.secret_key[pos * params.n..(pos + 1) * params.n];
+            assert_eq!(point == secret, digit == 0);
+
+            let mut sig = signature.clone();
+            sig[1 + SEED_SIZE + pos * params.n] ^= 0x01;
+            let res = verify(&msg, &sig, &signer.public_key);
+            assert_eq!(res, Err(WotsError::InvalidSignature), "digit {}", digit);
+        }
+    }
+
+    #[test]
+    fn error_paths_test() {
+        type Level0 = Params<Blake2bHasher, Sha3_224Hasher>;
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let p_seed = [5u8; SEED_SIZE];
+        let points = vec![6u8; params.n * params.total];
+        let msg = MSG.to_vec();
+
+        // parameter construction
+        assert_eq!(
+            Level0::new(ParamsEncoding::Custom).err(),
+            Some(WotsError::CustomNotSupported)
+        );
+        assert_eq!(
+            Level0::new_from_values(20, 0).err(),
+            Some(WotsError::InvalidMValue)
+        );
+        assert_eq!(
+            Level0::new_from_values(20, MAX_MSG_SIZE + 1).err(),
+            Some(WotsError::InvalidMValue)
+        );
+        assert_eq!(
+            Level0::new_from_values(33, 24).err(),
+            Some(WotsError::InvalidHasher)
+        );
+        assert_eq!(
+            Params::<Sha3_224Hasher, Sha3_224Hasher>::new(ParamsEncoding::Consensus).err(),
+            Some(WotsError::InvalidHasher)
+        );
+
+        // ladder computation
+        let ladders = |p_seed: &[u8], msg: Option<Vec<u8>>, points: &[u8], mode| {
+            params.compute_ladders(p_seed, msg, points, mode).err()
+        };
+        assert_eq!(
+            ladders(&p_seed[1..], None, &points, ComputeLaddersMode::Generate),
+            Some(WotsError::InvalidSeedSize)
+        );
+        assert_eq!(
+            ladders(&p_seed, None, &points[1..], ComputeLaddersMode::Generate),
+            Some(WotsError::InvalidPointsSize)
+        );
+        assert_eq!(
+            ladders(&p_seed, None, &points, ComputeLaddersMode::Sign),
+            Some(WotsError::MustProvideMessage)
+        );
+        assert_eq!(
+            ladders(&p_seed, None, &points, ComputeLaddersMode::Verify),
+            Some(WotsError::ExpectedMessage)
+        );
+        for mode in [
+            ComputeLaddersMode::Generate,
+            ComputeLaddersMode::ComputePublicKey,
+        ] {
+            assert_eq!(
+                ladders(&p_seed, Some(msg.clone()), &points, mode),
+                Some(WotsError::NoMessageExpected)
+            );
+        }
+
+        // keys
+        let mut signer = key(params.clone(), 4);
+        assert_eq!(
+            signer.sign([0u8; MAX_MSG_SIZE + 1]).err(),
+            Some(WotsError::InvalidMessageSize)
+        );
+        let mut out = vec![0u8; params.signature_size() + 1];
+        assert_eq!(
+            signer.sign_into(&msg, &mut out).err(),
+            Some(WotsError::InvalidSignatureSize)
+        );
+        assert_eq!(
+            signer
+                .generate_with_strategy(ChainCacheStrategy::Sparse(0))
+                .err(),
+            Some(WotsError::InvalidCacheStrategy)
+        );
+        assert_eq!(
+            Key::from_seed(params.clone(), [4u8; SEED_SIZE], [0u8; SEED_SIZE]).err(),
+            Some(WotsError::DegenerateSeed)
+        );
+
+        // decoding
+        let signature = signer.sign(&msg).unwrap();
+        let mut pk = [0u8; PK_SIZE + 1];
+        assert_eq!(
+            params.decode_into(&msg, &signature[1..], &mut pk).err(),
+            Some(WotsError::InvalidPublicKeySize)
+        );
+        assert_eq!(
+            params.decode(&msg, &signature[2..]).err(),
+            Some(WotsError::InvalidSignatureSize)
+        );
+
+        // consensus signatures are refused by verify_no_consensus
+        let consensus = key(
+            security::consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+            4,
+        );
+        let signature = consensus.sign(&msg).unwrap();
+        assert_eq!(
+            verify_no_consensus(&msg, &signature, &consensus.public_key),
+            Err(WotsError::InvalidParamsEncodingType)
+        );
+        assert_eq!(
+            verify_no_consensus(&msg, [], &consensus.public_key),
+            Err(WotsError::InvalidSignatureSize)
+        );
+    }
+}
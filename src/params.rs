@@ -1,20 +1,87 @@
-use sha3::{Digest, Sha3_256};
+#[cfg(feature = "std")]
+use rand::{rngs::OsRng, seq::SliceRandom};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 use thiserror::Error;
 
-use crate::hasher::Hasher;
-use crate::keys::PK_SIZE;
-use crate::security::ParamsEncoding;
+use core::any::TypeId;
+use std::fmt;
+use std::sync::Arc;
+
+#[cfg(feature = "std")]
+use crate::backend::ThreadedBackend;
+use crate::backend::{
+    walk_chain, ChainBackend, ChainRows, LadderJob, OrderedBackend, SoftwareBackend,
+};
+use crate::codec::{MessageCodec, SigningContext};
+use crate::digits::{base_w_len, write_base_w, write_checksum};
+pub use crate::digits::{checksum, checksum_digits, checksum_len};
+use crate::hasher::{Hasher, HasherId, Sha3_256Hasher};
+use crate::keys::{PublicKey, PK_SIZE};
+use crate::ladders::{ChainCache, LadderPoints};
+use crate::randomness::PublicRandomness;
+use crate::security::{ParamsEncoding, PREDEFINED};
+use crate::signature::check_canonical;
+use crate::trace::{trace_verification, VerifyTrace};
+use crate::verify_table::VerifyTable;
 
 /// Winternits parameter
 pub const W: usize = 256;
 
-/// Secret and public seed size
+/// Largest Winternitz parameter `Params::with_w` accepts; chain steps are indexed by a u16
+pub const MAX_W: usize = 1 << 16;
+
+/// Public seed size, and the default secret seed size
 pub const SEED_SIZE: usize = 32;
 
+/// Largest secret seed size `Params::with_seed_size` accepts
+pub const MAX_SEED_SIZE: usize = 64;
+
 /// Maximum message size that ca n be signed
 pub const MAX_MSG_SIZE: usize = 254;
 
-#[derive(Error, Debug)]
+/// Longest context `Key::sign_with_context` accepts
+pub const MAX_CONTEXT_SIZE: usize = 255;
+
+// Prefix of every context-bound message hash. It's longer than any message that can be signed
+// without a context, so a context-bound digest can never collide with a plain one.
+pub(crate) const CONTEXT_DOMAIN: [u8; MAX_MSG_SIZE + 1] = context_domain(b"W-OTS+ signing context");
+
+// Prefix of message hashes under `EncodingProfile::bind_params`, followed by the params.
+const PARAMS_DOMAIN: &[u8] = b"W-OTS+ params";
+
+const fn context_domain(label: &[u8]) -> [u8; MAX_MSG_SIZE + 1] {
+    let mut domain = [0u8; MAX_MSG_SIZE + 1];
+    let mut i = 0;
+    while i < label.len() {
+        domain[i] = label[i];
+        i += 1;
+    }
+    domain
+}
+
+/// Largest hasher output supported when the `arrayvec` feature keeps hash buffers on the stack
+pub const MAX_HASH_SIZE: usize = 64;
+
+// Message digits followed by their checksum digits. A `w` of at least `W` never makes more
+// digits than bytes, so `m + 2` always holds them.
+#[cfg(feature = "arrayvec")]
+pub(crate) type Digits = arrayvec::ArrayVec<u16, { MAX_MSG_SIZE + 2 }>;
+#[cfg(not(feature = "arrayvec"))]
+pub(crate) type Digits = Vec<u16>;
+
+// Scratch space for a single hasher output.
+#[cfg(feature = "arrayvec")]
+pub(crate) type HashBuf = arrayvec::ArrayVec<u8, MAX_HASH_SIZE>;
+#[cfg(not(feature = "arrayvec"))]
+pub(crate) type HashBuf = Vec<u8>;
+
+pub(crate) fn hash_buf(size: usize) -> HashBuf {
+    let mut buf = HashBuf::new();
+    buf.extend(core::iter::repeat_n(0u8, size));
+    buf
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum WotsError {
     #[error("invalid m value: must be between 1 and 254")]
     InvalidMValue,
@@ -22,7 +89,7 @@ pub enum WotsError {
     CustomNotSupported,
     #[error("prf hash size must be less than n and msg hash size must be less than m")]
     InvalidHasher,
-    #[error("invalid seed size: expected 32")]
+    #[error("invalid seed size: must match the params, between 32 and 64 bytes")]
     InvalidSeedSize,
     #[error("invalid message size: must be smaller than 254")]
     InvalidMessageSize,
@@ -44,6 +111,303 @@ pub enum WotsError {
     NoMessageExpected,
     #[error("expected message for ComputeLaddersMode::Sign or ComputeLaddersMode::Verify")]
     ExpectedMessage,
+    #[error("invalid chain cache strategy: stride must be at least 1")]
+    InvalidCacheStrategy,
+    #[error("signature encoding byte does not match the verifying params")]
+    EncodingMismatch,
+    #[error("degenerate p_seed: must not be all zeros")]
+    DegenerateSeed,
+    #[error("invalid context size: must be at most 255 bytes")]
+    InvalidContextSize,
+    #[error("invalid signed message envelope")]
+    InvalidEnvelope,
+    #[error("unsupported signed message envelope version")]
+    UnsupportedEnvelopeVersion,
+    #[error("seed provider failed to answer a PRF query")]
+    SeedProviderFailure,
+    #[error("entropy source failed its health tests")]
+    EntropyFailure,
+    #[error("invalid params config: n and m are required for custom and must match the encoding otherwise")]
+    InvalidConfig,
+    #[error("configured hasher does not match the hasher type")]
+    HasherMismatch,
+    #[error("invalid public key bundle")]
+    InvalidBundle,
+    #[error("unsupported public key bundle version")]
+    UnsupportedBundleVersion,
+    #[error("invalid ladder index: must be less than total")]
+    InvalidLadderIndex,
+    #[error("invalid generate checkpoint, or one made for another key")]
+    InvalidCheckpoint,
+    #[error("key directory i/o failed")]
+    DirectoryIo,
+    #[error("invalid key directory record")]
+    InvalidKeyRecord,
+    #[error("no key with this fingerprint in the directory")]
+    KeyNotFound,
+    #[error("key has already been used to sign")]
+    KeyAlreadyUsed,
+    #[error("key seed is held by a seed provider and cannot be stored")]
+    SeedUnavailable,
+    #[error("key is already stored in the directory")]
+    KeyExists,
+    #[error("invalid share policy: need 1 <= k <= n <= 255")]
+    InvalidSharePolicy,
+    #[error("invalid or corrupted seed share")]
+    InvalidShare,
+    #[error("not enough seed shares to recover the seed")]
+    NotEnoughShares,
+    #[error("seed shares do not belong to the same split or are tampered with")]
+    ShareMismatch,
+    #[error("invalid sealed seed encoding")]
+    InvalidSealedSeed,
+    #[error("sealed seed failed to decrypt: wrong key or tampered data")]
+    UnsealFailure,
+    #[error("unknown params encoding byte {0}")]
+    UnknownEncoding(u8),
+    #[error("invalid public randomness encoding")]
+    InvalidRandomness,
+    #[error("public randomness derived for another p_seed, n or w")]
+    RandomnessMismatch,
+    #[error("message is not in the verification table")]
+    MessageNotInTable,
+    #[error("signature header is not the canonical encoding of its params")]
+    NonCanonicalSignature,
+    #[error("invalid chain cache encoding")]
+    InvalidChainCache,
+    #[error("chain cache integrity tag mismatch: corrupted or tampered cache")]
+    ChainCacheTampered,
+    #[error("chain levels out of range")]
+    InvalidChainRange,
+    #[error("invalid n value: must be between 1 and the prf hash size")]
+    InvalidNValue,
+    #[error("params fields are inconsistent with each other")]
+    InconsistentParams,
+    #[error("invalid courier envelope encoding")]
+    InvalidCourierEnvelope,
+    #[error(
+        "courier envelope failed to decrypt: wrong or small order recipient key, or tampered data"
+    )]
+    CourierOpenFailure,
+    #[error("key has been destroyed")]
+    KeyDestroyed,
+    #[error("invalid certificate encoding")]
+    InvalidCertificate,
+    #[error("certificate was issued by another key")]
+    IssuerMismatch,
+    #[error("verification would exceed the hash budget")]
+    BudgetExceeded,
+    #[error("invalid w value: must be a power of two between 256 and 65536")]
+    InvalidWValue,
+    #[error("invalid derivation path: every index must be hardened and below 2^31")]
+    InvalidDerivationPath,
+    #[error("invalid public key set encoding")]
+    InvalidKeySet,
+    #[error("unsupported signature header version {0}")]
+    UnsupportedSignatureVersion(u8),
+    #[error("a thread walking the ladders panicked")]
+    LadderWorkerPanicked,
+}
+
+impl WotsError {
+    /// Stable numeric code for FFI, WASM and logs. Codes start at 1, are never reused and
+    /// new variants get the next free one.
+    pub const fn code(&self) -> u32 {
+        match self {
+            WotsError::InvalidMValue => 1,
+            WotsError::CustomNotSupported => 2,
+            WotsError::InvalidHasher => 3,
+            WotsError::InvalidSeedSize => 4,
+            WotsError::InvalidMessageSize => 5,
+            WotsError::InvalidPointsSize => 6,
+            WotsError::MustProvideMessage => 7,
+            WotsError::ChainsNotSet => 8,
+            WotsError::InvalidPublicKeySize => 9,
+            WotsError::InvalidSignatureSize => 10,
+            WotsError::InvalidSignature => 11,
+            WotsError::InvalidParamsEncodingType => 12,
+            WotsError::NoMessageExpected => 13,
+            WotsError::ExpectedMessage => 14,
+            WotsError::InvalidCacheStrategy => 15,
+            WotsError::EncodingMismatch => 16,
+            WotsError::DegenerateSeed => 17,
+            WotsError::InvalidContextSize => 18,
+            WotsError::InvalidEnvelope => 19,
+            WotsError::UnsupportedEnvelopeVersion => 20,
+            WotsError::SeedProviderFailure => 21,
+            WotsError::EntropyFailure => 22,
+            WotsError::InvalidConfig => 23,
+            WotsError::HasherMismatch => 24,
+            WotsError::InvalidBundle => 25,
+            WotsError::UnsupportedBundleVersion => 26,
+            WotsError::InvalidLadderIndex => 27,
+            WotsError::InvalidCheckpoint => 28,
+            // 29 is retired: it was the auth path error of a removed module
+            WotsError::DirectoryIo => 30,
+            WotsError::InvalidKeyRecord => 31,
+            WotsError::KeyNotFound => 32,
+            WotsError::KeyAlreadyUsed => 33,
+            WotsError::SeedUnavailable => 34,
+            WotsError::KeyExists => 35,
+            WotsError::InvalidSharePolicy => 36,
+            WotsError::InvalidShare => 37,
+            WotsError::NotEnoughShares => 38,
+            WotsError::ShareMismatch => 39,
+            WotsError::InvalidSealedSeed => 40,
+            WotsError::UnsealFailure => 41,
+            WotsError::UnknownEncoding(_) => 42,
+            WotsError::InvalidRandomness => 43,
+            WotsError::RandomnessMismatch => 44,
+            WotsError::MessageNotInTable => 45,
+            WotsError::NonCanonicalSignature => 46,
+            WotsError::InvalidChainCache => 47,
+            WotsError::ChainCacheTampered => 48,
+            WotsError::InvalidChainRange => 49,
+            WotsError::InvalidNValue => 50,
+            WotsError::InconsistentParams => 51,
+            WotsError::InvalidCourierEnvelope => 52,
+            WotsError::CourierOpenFailure => 53,
+            WotsError::KeyDestroyed => 54,
+            WotsError::InvalidCertificate => 55,
+            WotsError::IssuerMismatch => 56,
+            WotsError::BudgetExceeded => 57,
+            WotsError::InvalidWValue => 58,
+            WotsError::InvalidDerivationPath => 59,
+            WotsError::InvalidKeySet => 60,
+            WotsError::UnsupportedSignatureVersion(_) => 61,
+            WotsError::LadderWorkerPanicked => 62,
+        }
+    }
+
+    /// Inverse of `code`; `None` for codes not assigned to a variant. Variants carrying data
+    /// are returned with it zeroed.
+    pub const fn from_code(code: u32) -> Option<WotsError> {
+        Some(match code {
+            1 => WotsError::InvalidMValue,
+            2 => WotsError::CustomNotSupported,
+            3 => WotsError::InvalidHasher,
+            4 => WotsError::InvalidSeedSize,
+            5 => WotsError::InvalidMessageSize,
+            6 => WotsError::InvalidPointsSize,
+            7 => WotsError::MustProvideMessage,
+            8 => WotsError::ChainsNotSet,
+            9 => WotsError::InvalidPublicKeySize,
+            10 => WotsError::InvalidSignatureSize,
+            11 => WotsError::InvalidSignature,
+            12 => WotsError::InvalidParamsEncodingType,
+            13 => WotsError::NoMessageExpected,
+            14 => WotsError::ExpectedMessage,
+            15 => WotsError::InvalidCacheStrategy,
+            16 => WotsError::EncodingMismatch,
+            17 => WotsError::DegenerateSeed,
+            18 => WotsError::InvalidContextSize,
+            19 => WotsError::InvalidEnvelope,
+            20 => WotsError::UnsupportedEnvelopeVersion,
+            21 => WotsError::SeedProviderFailure,
+            22 => WotsError::EntropyFailure,
+            23 => WotsError::InvalidConfig,
+            24 => WotsError::HasherMismatch,
+            25 => WotsError::InvalidBundle,
+            26 => WotsError::UnsupportedBundleVersion,
+            27 => WotsError::InvalidLadderIndex,
+            28 => WotsError::InvalidCheckpoint,
+            // 29 is retired: it was the auth path error of a removed module
+            30 => WotsError::DirectoryIo,
+            31 => WotsError::InvalidKeyRecord,
+            32 => WotsError::KeyNotFound,
+            33 => WotsError::KeyAlreadyUsed,
+            34 => WotsError::SeedUnavailable,
+            35 => WotsError::KeyExists,
+            36 => WotsError::InvalidSharePolicy,
+            37 => WotsError::InvalidShare,
+            38 => WotsError::NotEnoughShares,
+            39 => WotsError::ShareMismatch,
+            40 => WotsError::InvalidSealedSeed,
+            41 => WotsError::UnsealFailure,
+            42 => WotsError::UnknownEncoding(0),
+            43 => WotsError::InvalidRandomness,
+            44 => WotsError::RandomnessMismatch,
+            45 => WotsError::MessageNotInTable,
+            46 => WotsError::NonCanonicalSignature,
+            47 => WotsError::InvalidChainCache,
+            48 => WotsError::ChainCacheTampered,
+            49 => WotsError::InvalidChainRange,
+            50 => WotsError::InvalidNValue,
+            51 => WotsError::InconsistentParams,
+            52 => WotsError::InvalidCourierEnvelope,
+            53 => WotsError::CourierOpenFailure,
+            54 => WotsError::KeyDestroyed,
+            55 => WotsError::InvalidCertificate,
+            56 => WotsError::IssuerMismatch,
+            57 => WotsError::BudgetExceeded,
+            58 => WotsError::InvalidWValue,
+            59 => WotsError::InvalidDerivationPath,
+            60 => WotsError::InvalidKeySet,
+            61 => WotsError::UnsupportedSignatureVersion(0),
+            62 => WotsError::LadderWorkerPanicked,
+            _ => return None,
+        })
+    }
+
+    /// Classifies a failed verification, for gateways that penalize a peer sending malformed
+    /// data differently from one relaying a signature that does not verify. `None` for errors
+    /// that are no verdict on the input, such as a misconfigured verifier or an exhausted
+    /// hash budget.
+    pub const fn verify_failure(&self) -> Option<VerifyFailure> {
+        match self {
+            WotsError::InvalidSignature | WotsError::IssuerMismatch => Some(VerifyFailure::Invalid),
+            WotsError::InvalidMessageSize
+            | WotsError::InvalidPublicKeySize
+            | WotsError::InvalidSignatureSize
+            | WotsError::InvalidParamsEncodingType
+            | WotsError::EncodingMismatch
+            | WotsError::DegenerateSeed
+            | WotsError::InvalidContextSize
+            | WotsError::InvalidEnvelope
+            | WotsError::UnsupportedEnvelopeVersion
+            | WotsError::HasherMismatch
+            | WotsError::InvalidBundle
+            | WotsError::UnsupportedBundleVersion
+            | WotsError::UnknownEncoding(_)
+            | WotsError::NonCanonicalSignature
+            | WotsError::UnsupportedSignatureVersion(_)
+            | WotsError::InvalidCertificate => Some(VerifyFailure::Malformed),
+            _ => None,
+        }
+    }
+}
+
+// record_failure notes the kind of a failed verification in the `failure` field of the
+// current span.
+#[cfg(feature = "tracing")]
+pub(crate) fn record_failure(result: &Result<(), WotsError>) {
+    if let Some(failure) = result.as_ref().err().and_then(WotsError::verify_failure) {
+        tracing::Span::current().record("failure", tracing::field::display(failure));
+    }
+}
+
+/// Kind of a failed verification; see `WotsError::verify_failure`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum VerifyFailure {
+    /// the input cannot be verified at all: wrong sizes, an unknown, mismatched or rejected
+    /// encoding, a non-canonical header, a degenerate `p_seed`
+    Malformed,
+    /// well-formed input whose signature does not verify
+    Invalid,
+}
+
+impl fmt::Display for VerifyFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            VerifyFailure::Malformed => "malformed",
+            VerifyFailure::Invalid => "invalid",
+        })
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -54,6 +418,138 @@ pub enum ComputeLaddersMode {
     ComputePublicKey,
 }
 
+/// A message to sign or verify, optionally bound to a context.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Message<'a> {
+    context: Option<&'a [u8]>,
+    msg: &'a [u8],
+}
+
+impl<'a> Message<'a> {
+    pub(crate) fn plain(msg: &'a [u8]) -> Self {
+        Message { context: None, msg }
+    }
+
+    pub(crate) fn with_context(msg: &'a [u8], context: &'a [u8]) -> Result<Self, WotsError> {
+        if context.len() > MAX_CONTEXT_SIZE {
+            return Err(WotsError::InvalidContextSize);
+        }
+        Ok(Message {
+            context: Some(context),
+            msg,
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.msg.len()
+    }
+
+    // plain_bytes returns the message if it isn't bound to a context.
+    pub(crate) fn plain_bytes(&self) -> Option<&'a [u8]> {
+        self.context.is_none().then_some(self.msg)
+    }
+
+    // write hashes `CONTEXT_DOMAIN || len(context) || context || msg`, or just `msg` without a
+    // context, with `msg` encoded by `codec` if one is set.
+    fn write<H: Hasher>(&self, hasher: &mut H, codec: Option<&dyn MessageCodec>) {
+        if let Some(context) = self.context {
+            hasher.write(&CONTEXT_DOMAIN);
+            hasher.write(&[context.len() as u8]);
+            hasher.write(context);
+        }
+        match codec {
+            Some(codec) => codec.encode(self.msg, &mut |bytes| hasher.write(bytes)),
+            None => hasher.write(self.msg),
+        }
+    }
+}
+
+/// Incremental message hasher made by `Params::message_hasher`, for proxies that compute the
+/// ladder digits of messages they receive over the network without buffering them.
+#[derive(Clone)]
+pub struct MessageHasherCtx<'a, PRFH: Hasher + Clone, MSGH: Hasher + Clone> {
+    params: &'a Params<PRFH, MSGH>,
+    hasher: MSGH,
+}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> MessageHasherCtx<'_, PRFH, MSGH> {
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.hasher.write(chunk);
+    }
+
+    /// Message digits followed by the checksum digits, one per ladder.
+    pub fn finish_digits(self) -> Vec<u16> {
+        self.params.hash_digits(self.hasher).into_iter().collect()
+    }
+}
+
+/// Order the checksum digits are signed in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ChecksumOrder {
+    /// most significant digit first, as everywhere in this crate
+    #[default]
+    BigEndian,
+    /// least significant digit first
+    LittleEndian,
+}
+
+/// Encoding details that vary between W-OTS+ implementations, to match third-party signatures
+/// bit for bit.
+///
+/// Digit packing needs no option: at `W = 256` every base-w digit is one byte, and a
+/// left-aligned checksum (RFC 8391) yields the same digits as this crate's.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncodingProfile {
+    pub checksum_order: ChecksumOrder,
+    /// prefix the message hash input with the encoding byte, `n`, `m` and the hasher names,
+    /// so that a signature can't verify under other params producing the same digits. Opt-in:
+    /// no predefined encoding sets it, since it changes every signature and so breaks
+    /// compatibility with other implementations. Both hashers need a `Hasher::NAME`.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bind_params: bool,
+}
+
+/// Plain description of a parameter set, for CLIs, dashboards and compatibility negotiation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParamsDescription {
+    pub encoding: ParamsEncoding,
+
+    /// security parameter; size of secret key and ladder points (in bytes)
+    pub n: usize,
+
+    /// size of message to be signed (after hashing) (in bytes)
+    pub m: usize,
+
+    /// Winternitz parameter
+    pub w: usize,
+
+    /// total number of ladders
+    pub total: usize,
+
+    /// number of ladders signing the checksum, included in `total`
+    pub checksum_ladders: usize,
+
+    /// sizes in bytes
+    pub signature_size: usize,
+    pub public_key_size: usize,
+    pub secret_key_size: usize,
+    pub seed_size: usize,
+
+    /// hashers; `None` for hashers outside this crate
+    pub prf_hasher: Option<HasherId>,
+    pub msg_hasher: Option<HasherId>,
+    pub aggregation_hasher: Option<HasherId>,
+
+    pub profile: EncodingProfile,
+}
+
 #[derive(Debug, Clone)]
 pub struct Params<PRFH: Hasher + Clone, MSGH: Hasher + Clone> {
     /// security parameter; size of secret key and ladder points (in bytes)
@@ -62,85 +558,442 @@ pub struct Params<PRFH: Hasher + Clone, MSGH: Hasher + Clone> {
     /// size of message to be signed (after hashing) (in bytes)
     pub m: usize,
 
+    /// Winternitz parameter; `W` unless set with `with_w`
+    pub w: usize,
+
     /// total number of ladders
     pub total: usize,
 
     /// encoding level
     pub encoding: ParamsEncoding,
 
+    /// size of the secret seed (in bytes)
+    pub seed_size: usize,
+
+    /// interop encoding details; the default is this crate's own encoding
+    pub profile: EncodingProfile,
+
+    // encodes messages before they are hashed; see `with_codec`
+    codec: Option<Arc<dyn MessageCodec>>,
+
+    // hashes the ladder tops into the public key; see `with_aggregation_hasher`
+    aggregation: Aggregation,
+
     prf_hash: std::marker::PhantomData<PRFH>,
     msg_hash: std::marker::PhantomData<MSGH>,
 }
 
-impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> Params<PRFH, MSGH> {
-    pub fn new(encoding: ParamsEncoding) -> Result<Params<PRFH, MSGH>, WotsError> {
-        let (n, m) = match encoding {
-            ParamsEncoding::Level0 => (20, 24),
-            ParamsEncoding::Level1 => (24, 24),
-            ParamsEncoding::Level2 => (28, 24),
-            ParamsEncoding::Level3 => (32, 24),
-            ParamsEncoding::Consensus => (32, 32),
-            ParamsEncoding::Custom => {
-                return Err(WotsError::CustomNotSupported);
-            }
-        };
+/// Builds `Custom` params, checking every setting against the others and the hashers:
+///
+/// ```
+/// # use w_ots::hasher::{Blake2bHasher, Sha3_224Hasher};
+/// # use w_ots::params::{Params, ParamsBuilder};
+/// let params: Params<Blake2bHasher, Sha3_224Hasher> =
+///     ParamsBuilder::new().n(26).m(20).w(1 << 10).context(b"app").build()?;
+/// # Ok::<(), w_ots::params::WotsError>(())
+/// ```
+///
+/// `n` and `m` must be set; `w`, the seed size and the profile default to those of the
+/// predefined encodings, and there is no context unless one is set.
+#[derive(Debug, Clone, Default)]
+pub struct ParamsBuilder {
+    n: Option<usize>,
+    m: Option<usize>,
+    w: Option<usize>,
+    seed_size: Option<usize>,
+    profile: EncodingProfile,
+    context: Option<Vec<u8>>,
+}
 
-        if !(1..=MAX_MSG_SIZE).contains(&m) {
-            return Err(WotsError::InvalidMValue);
-        }
+impl ParamsBuilder {
+    pub fn new() -> Self {
+        ParamsBuilder::default()
+    }
+
+    /// Size of ladder points, at most the output size of the PRF hasher.
+    pub fn n(mut self, n: usize) -> Self {
+        self.n = Some(n);
+        self
+    }
+
+    /// Size of the message digest, at most `MAX_MSG_SIZE` and the output size of the message
+    /// hasher.
+    pub fn m(mut self, m: usize) -> Self {
+        self.m = Some(m);
+        self
+    }
+
+    /// Winternitz parameter, as `Params::with_w` takes it.
+    pub fn w(mut self, w: usize) -> Self {
+        self.w = Some(w);
+        self
+    }
+
+    /// Secret seed size, as `Params::with_seed_size` takes it.
+    pub fn seed_size(mut self, seed_size: usize) -> Self {
+        self.seed_size = Some(seed_size);
+        self
+    }
 
+    pub fn profile(mut self, profile: EncodingProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Binds every message to `context` (at most `MAX_CONTEXT_SIZE` bytes) through a
+    /// `SigningContext` codec, so the params sign and verify what `Key::sign_with_context` and
+    /// `verify_with_context` do with this context.
+    pub fn context(mut self, context: impl AsRef<[u8]>) -> Self {
+        self.context = Some(context.as_ref().to_vec());
+        self
+    }
+
+    /// Fails with the error of the first setting that doesn't fit: `InvalidMValue`,
+    /// `InvalidNValue`, `InvalidHasher` when `n` or `m` exceed the hashers, `InvalidWValue`,
+    /// `InvalidSeedSize` or `InvalidContextSize`.
+    pub fn build<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        self,
+    ) -> Result<Params<PRFH, MSGH>, WotsError> {
+        let m = self
+            .m
+            .filter(|m| (1..=MAX_MSG_SIZE).contains(m))
+            .ok_or(WotsError::InvalidMValue)?;
+        let n = self.n.filter(|&n| n > 0).ok_or(WotsError::InvalidNValue)?;
         if PRFH::size() < n || MSGH::size() < m {
             return Err(WotsError::InvalidHasher);
         }
-
-        let mut checksum_ladders: usize = 2;
-        if m == 1 {
-            checksum_ladders = 1;
+        let w = self.w.unwrap_or(W);
+        if !valid_w(w) {
+            return Err(WotsError::InvalidWValue);
+        }
+        let seed_size = self.seed_size.unwrap_or(SEED_SIZE);
+        if !(SEED_SIZE..=MAX_SEED_SIZE).contains(&seed_size) {
+            return Err(WotsError::InvalidSeedSize);
         }
+        let codec = match self.context {
+            Some(context) => Some(Arc::new(SigningContext::new(context)?) as Arc<dyn MessageCodec>),
+            None => None,
+        };
+
+        let params = Params {
+            n,
+            m,
+            w,
+            total: ladders_for(w, m),
+            encoding: ParamsEncoding::Custom,
+            seed_size,
+            profile: self.profile,
+            codec,
+            aggregation: Aggregation::of::<Sha3_256Hasher>(),
+            prf_hash: std::marker::PhantomData,
+            msg_hash: std::marker::PhantomData,
+        };
+        // also checks the hasher sizes against `MAX_HASH_SIZE` with the `arrayvec` feature
+        params.check()?;
+        Ok(params)
+    }
+}
+
+/// Params are equal when they sign and verify alike: same `n`, `m`, `w`, encoding, seed size,
+/// profile and hashers, and the same codec instance if one is set. Registries and caches can
+/// key on them directly.
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> PartialEq for Params<PRFH, MSGH> {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> Eq for Params<PRFH, MSGH> {}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> std::hash::Hash for Params<PRFH, MSGH> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.identity().hash(state)
+    }
+}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> Params<PRFH, MSGH> {
+    pub fn new(encoding: ParamsEncoding) -> Result<Params<PRFH, MSGH>, WotsError> {
+        let (n, m) = match encoding.values() {
+            Some(values) => values,
+            None => {
+                return Err(WotsError::CustomNotSupported);
+            }
+        };
 
-        Ok(Params::<PRFH, MSGH> {
+        let params = Params::<PRFH, MSGH> {
             n,
             m,
-            total: m + checksum_ladders,
+            w: W,
+            total: total_ladders(m),
+            seed_size: SEED_SIZE,
+            profile: EncodingProfile::default(),
+            codec: None,
+            aggregation: Aggregation::of::<Sha3_256Hasher>(),
             prf_hash: std::marker::PhantomData::<PRFH>,
             msg_hash: std::marker::PhantomData::<MSGH>,
             encoding,
-        })
+        };
+        params.check()?;
+        Ok(params)
     }
 
+    /// `Custom` params of `n` and `m`; see `ParamsBuilder` for the other settings.
     pub fn new_from_values(n: usize, m: usize) -> Result<Params<PRFH, MSGH>, WotsError> {
-        if !(1..=MAX_MSG_SIZE).contains(&m) {
+        ParamsBuilder::new().n(n).m(m).build()
+    }
+
+    // check fails for params whose fields don't fit the hashers or each other, e.g. after
+    // being changed by hand, so that the hashing code can slice by them without panicking.
+    pub(crate) fn check(&self) -> Result<(), WotsError> {
+        if !(1..=MAX_MSG_SIZE).contains(&self.m) {
             return Err(WotsError::InvalidMValue);
         }
 
-        if PRFH::size() < n || MSGH::size() < m {
+        if self.n == 0 {
+            return Err(WotsError::InvalidNValue);
+        }
+
+        if PRFH::size() < self.n || MSGH::size() < self.m {
             return Err(WotsError::InvalidHasher);
         }
 
-        let mut checksum_ladders: usize = 2;
-        if m == 1 {
-            checksum_ladders = 1;
+        if self.profile.bind_params && (PRFH::NAME.is_none() || MSGH::NAME.is_none()) {
+            return Err(WotsError::InvalidHasher);
         }
 
-        Ok(Params::<PRFH, MSGH> {
-            n,
-            m,
-            total: m + checksum_ladders,
-            prf_hash: std::marker::PhantomData::<PRFH>,
-            msg_hash: std::marker::PhantomData::<MSGH>,
-            encoding: ParamsEncoding::Custom,
-        })
+        #[cfg(feature = "arrayvec")]
+        if PRFH::size() > MAX_HASH_SIZE || MSGH::size() > MAX_HASH_SIZE {
+            return Err(WotsError::InvalidHasher);
+        }
+
+        if !valid_w(self.w) {
+            return Err(WotsError::InvalidWValue);
+        }
+
+        if self.total != ladders_for(self.w, self.m)
+            || !(SEED_SIZE..=MAX_SEED_SIZE).contains(&self.seed_size)
+        {
+            return Err(WotsError::InconsistentParams);
+        }
+        Ok(())
     }
 
-    pub fn msg_hash_and_compute_checksum(&self, msg: &[u8]) -> Vec<u8> {
+    /// Sets the size of the secret seeds keys are derived from, e.g. to match the entropy to a
+    /// 512-bit PRF. The public seed, and so the signature format, stays `SEED_SIZE` bytes.
+    pub fn with_seed_size(mut self, seed_size: usize) -> Result<Self, WotsError> {
+        if !(SEED_SIZE..=MAX_SEED_SIZE).contains(&seed_size) {
+            return Err(WotsError::InvalidSeedSize);
+        }
+        self.seed_size = seed_size;
+        Ok(self)
+    }
+
+    /// Sets the Winternitz parameter, a power of two from `W` to `MAX_W`, for evaluating
+    /// experimental parameter sets: every doubling of `w` packs one more bit into each digit,
+    /// so there are fewer ladders and signatures are shorter, but the ladders are twice as
+    /// long to walk. Params with a `w` other than `W` are always `Custom`.
+    pub fn with_w(mut self, w: usize) -> Result<Self, WotsError> {
+        if !valid_w(w) {
+            return Err(WotsError::InvalidWValue);
+        }
+        self.w = w;
+        self.total = ladders_for(w, self.m);
+        if w != W {
+            self.encoding = ParamsEncoding::Custom;
+        }
+        Ok(self)
+    }
+
+    /// Signs and verifies with `profile`. Params with a non-default profile are always
+    /// `Custom`: their digits differ from those of the predefined encoding.
+    pub fn with_profile(mut self, profile: EncodingProfile) -> Self {
+        self.profile = profile;
+        if profile != EncodingProfile::default() {
+            self.encoding = ParamsEncoding::Custom;
+        }
+        self
+    }
+
+    /// Encodes every message with `codec` before hashing it, when signing and verifying alike.
+    /// The codec isn't part of the encoding byte, directories or configs, so both sides have
+    /// to set it, and the params become `ParamsEncoding::Custom`. `message_hasher` chunks are
+    /// hashed as they are: feed it encoded messages.
+    pub fn with_codec(mut self, codec: Arc<dyn MessageCodec>) -> Self {
+        self.codec = Some(codec);
+        self.encoding = ParamsEncoding::Custom;
+        self
+    }
+
+    /// Hashes the ladder tops into the public key with `H` instead of SHA3-256, so deployments
+    /// standardizing on one hash family can use it throughout. `H` must output at least
+    /// `PK_SIZE` bytes. Public keys change with the hasher, so params with any other than
+    /// SHA3-256 are always `Custom`; like the codec, the hasher isn't part of the encoding
+    /// byte, directories or configs.
+    pub fn with_aggregation_hasher<H: Hasher + 'static>(mut self) -> Result<Self, WotsError> {
+        if H::size() < PK_SIZE {
+            return Err(WotsError::InvalidHasher);
+        }
+        #[cfg(feature = "arrayvec")]
+        if H::size() > MAX_HASH_SIZE {
+            return Err(WotsError::InvalidHasher);
+        }
+        self.aggregation = Aggregation::of::<H>();
+        if self.aggregation.id != Some(HasherId::Sha3_256) {
+            self.encoding = ParamsEncoding::Custom;
+        }
+        Ok(self)
+    }
+
+    pub(crate) fn aggregation(&self) -> Aggregation {
+        self.aggregation
+    }
+
+    // identity is what `eq` and `hash` compare. `PRFH` and `MSGH` are fixed by the type; the
+    // codec is compared by address, as codecs can't be compared themselves.
+    fn identity(&self) -> impl PartialEq + std::hash::Hash {
+        (
+            (self.n, self.m, self.w, self.total),
+            self.encoding,
+            self.seed_size,
+            self.profile,
+            (PRFH::ID, MSGH::ID, self.aggregation.type_id),
+            self.codec.as_ref().map(|codec| {
+                codec
+                    .identity()
+                    .ok_or(Arc::as_ptr(codec) as *const () as usize)
+            }),
+        )
+    }
+
+    pub fn describe(&self) -> ParamsDescription {
+        ParamsDescription {
+            encoding: self.encoding,
+            n: self.n,
+            m: self.m,
+            w: self.w,
+            total: self.total,
+            checksum_ladders: self.total - base_w_len(self.w, self.m),
+            signature_size: self.signature_size(),
+            public_key_size: PK_SIZE,
+            secret_key_size: self.secret_key_size(),
+            seed_size: self.seed_size,
+            prf_hasher: PRFH::ID,
+            msg_hasher: MSGH::ID,
+            aggregation_hasher: self.aggregation.id,
+            profile: self.profile,
+        }
+    }
+
+    /// The encoding byte signatures under these params canonically carry: the predefined
+    /// encoding with the same `n`, `m`, hashers and profile, `Custom` if there is none.
+    pub fn canonical_encoding(&self) -> ParamsEncoding {
+        PREDEFINED
+            .into_iter()
+            .find(|encoding| {
+                let msg_hasher = match encoding {
+                    ParamsEncoding::Consensus => HasherId::Sha3_256,
+                    _ => HasherId::Sha3_224,
+                };
+                encoding.values() == Some((self.n, self.m))
+                    && self.w == W
+                    && PRFH::ID == Some(HasherId::Blake2b)
+                    && MSGH::ID == Some(msg_hasher)
+                    && self.profile == EncodingProfile::default()
+                    && self.codec.is_none()
+                    && self.aggregation.id == Some(HasherId::Sha3_256)
+            })
+            .unwrap_or(ParamsEncoding::Custom)
+    }
+
+    pub fn msg_hash_and_compute_checksum(&self, msg: &[u8]) -> Vec<u16> {
+        self.message_digits(Message::plain(msg))
+            .into_iter()
+            .collect()
+    }
+
+    /// Starts hashing a message that arrives in chunks; the digits it finishes with are the
+    /// ones `msg_hash_and_compute_checksum` computes for the whole message.
+    pub fn message_hasher(&self) -> MessageHasherCtx<'_, PRFH, MSGH> {
+        MessageHasherCtx {
+            params: self,
+            hasher: self.start_message_hash(),
+        }
+    }
+
+    /// Same as `message_hasher`, for messages signed with `Key::sign_with_context`.
+    pub fn message_hasher_with_context(
+        &self,
+        context: &[u8],
+    ) -> Result<MessageHasherCtx<'_, PRFH, MSGH>, WotsError> {
+        let mut ctx = self.message_hasher();
+        Message::with_context(&[], context)?.write(&mut ctx.hasher, None);
+        Ok(ctx)
+    }
+
+    pub(crate) fn message_digits(&self, message: Message) -> Digits {
+        let mut hasher = self.start_message_hash();
+        message.write(&mut hasher, self.codec.as_deref());
+        self.hash_digits(hasher)
+    }
+
+    // start_message_hash returns the message hasher, primed with
+    // `PARAMS_DOMAIN || encoding || n || m || len || PRF hasher name || len || message hasher
+    // name` under `bind_params`, followed by `log2(w)` unless `w` is `W`. `check` refuses
+    // hashers without a name.
+    fn start_message_hash(&self) -> MSGH {
         let mut hasher = MSGH::new();
-        let mut msg_buf = vec![0u8; MSGH::size()];
-        let mut hashed_msg = vec![0u8; self.m];
-        hasher.write(msg.to_vec());
+        if self.profile.bind_params {
+            hasher.write(PARAMS_DOMAIN);
+            hasher.write(&[u8::from(&self.encoding), self.n as u8, self.m as u8]);
+            for name in [PRFH::NAME, MSGH::NAME] {
+                let name = name.unwrap_or_default().as_bytes();
+                hasher.write(&[name.len() as u8]);
+                hasher.write(name);
+            }
+            if self.w != W {
+                hasher.write(&[self.w.trailing_zeros() as u8]);
+            }
+        }
+        hasher
+    }
+
+    // hash_digits finishes `hasher` over a message and returns its digits, checksum included.
+    fn hash_digits(&self, hasher: MSGH) -> Digits {
+        let mut msg_buf = hash_buf(MSGH::size());
         hasher.sum(&mut msg_buf);
-        hashed_msg[0..self.m].clone_from_slice(&msg_buf[0..self.m]);
-        hashed_msg.append(&mut checksum(&hashed_msg));
-        hashed_msg
+
+        // `check` rejects an `m` larger than the digest on every signing path; clamping keeps
+        // the public digit helpers from panicking on such params too
+        let m = self.m.min(msg_buf.len());
+        let w = self.digit_w();
+        let mut digits = Digits::new();
+        write_base_w(&msg_buf[0..m], w, &mut digits);
+        let mut checksum = Digits::new();
+        write_checksum(digits.iter().copied(), w, &mut checksum);
+        if self.profile.checksum_order == ChecksumOrder::LittleEndian {
+            checksum.reverse();
+        }
+        digits.extend(checksum);
+        digits
+    }
+
+    // digit_w is `w`, or `W` if it was set to a value `check` rejects, so that the digit
+    // helpers don't panic on such params either.
+    fn digit_w(&self) -> usize {
+        match valid_w(self.w) {
+            true => self.w,
+            false => W,
+        }
+    }
+
+    /// Size of a full signature (encoding byte, `p_seed` and ladder points) in bytes.
+    pub fn signature_size(&self) -> usize {
+        1 + SEED_SIZE + self.secret_key_size()
+    }
+
+    /// Size of the secret key (all ladder starting points) in bytes.
+    pub fn secret_key_size(&self) -> usize {
+        self.n * self.total
     }
 
     pub fn compute_ladders(
@@ -149,12 +1002,55 @@ impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> Params<PRFH, MSGH> {
         maybe_msg: Option<Vec<u8>>,
         points: &[u8],
         mode: ComputeLaddersMode,
-    ) -> Result<(Vec<u8>, Vec<Vec<u8>>), WotsError> {
-        if p_seed.len() != SEED_SIZE {
-            return Err(WotsError::InvalidSeedSize);
+    ) -> Result<(Vec<u8>, Option<ChainCache>), WotsError> {
+        let points = points
+            .get(..self.n * self.total)
+            .ok_or(WotsError::InvalidPointsSize)?;
+        let points = LadderPoints::new(points, self.n, self.total)?;
+        let mut out = match mode {
+            ComputeLaddersMode::Sign => vec![0u8; self.n * self.total],
+            _ => vec![0u8; PK_SIZE],
+        };
+
+        // chains are only kept around when generating; no need to pay for them otherwise
+        let mut chains = match mode {
+            ComputeLaddersMode::Generate => Some(ChainCache::new(self.n, self.w, self.total, 1)),
+            _ => None,
+        };
+        let p_seed: &[u8; SEED_SIZE] = p_seed.try_into().map_err(|_| WotsError::InvalidSeedSize)?;
+        self.compute_ladders_into(
+            &SoftwareBackend,
+            &self.public_randomness(p_seed),
+            maybe_msg.as_deref().map(Message::plain),
+            points,
+            mode,
+            &mut out,
+            chains.as_mut().map(ChainCache::rows_mut),
+        )?;
+        Ok((out, chains))
+    }
+
+    /// Same as `compute_ladders`, but walks the ladders on `backend` and writes the result
+    /// into `out` (`n * total` bytes for `ComputeLaddersMode::Sign`, `PK_SIZE` bytes
+    /// otherwise) and, when generating, the intermediate ladder values into `maybe_chains`.
+    /// The ladders are walked with the randomizers of `randomness.p_seed()`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn compute_ladders_into<B: ChainBackend<PRFH>>(
+        &self,
+        backend: &B,
+        randomness: &PublicRandomness,
+        maybe_msg: Option<Message>,
+        points: LadderPoints,
+        mode: ComputeLaddersMode,
+        out: &mut [u8],
+        maybe_chains: Option<ChainRows>,
+    ) -> Result<(), WotsError> {
+        self.check()?;
+        if randomness.n() != self.n || randomness.w() != self.w {
+            return Err(WotsError::RandomnessMismatch);
         }
 
-        if points.len() < (self.n * self.total) as usize {
+        if points.len() != self.total || points.as_bytes().len() != self.n * self.total {
             return Err(WotsError::InvalidPointsSize);
         }
 
@@ -162,13 +1058,24 @@ impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> Params<PRFH, MSGH> {
             return Err(WotsError::MustProvideMessage);
         }
 
+        match mode {
+            ComputeLaddersMode::Sign if out.len() != self.n * self.total => {
+                return Err(WotsError::InvalidSignatureSize);
+            }
+            ComputeLaddersMode::Sign => {}
+            _ if out.len() != PK_SIZE => {
+                return Err(WotsError::InvalidPublicKeySize);
+            }
+            _ => {}
+        };
+
         let start = match maybe_msg {
             Some(msg) => {
                 if mode != ComputeLaddersMode::Sign && mode != ComputeLaddersMode::Verify {
                     return Err(WotsError::NoMessageExpected);
                 }
 
-                self.msg_hash_and_compute_checksum(&msg)
+                self.message_digits(msg)
             }
             None => {
                 if mode != ComputeLaddersMode::Generate
@@ -176,179 +1083,615 @@ impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> Params<PRFH, MSGH> {
                 {
                     return Err(WotsError::ExpectedMessage);
                 }
-                vec![0u8; self.total]
+                let mut digits = Digits::new();
+                digits.extend(core::iter::repeat_n(0u16, self.total));
+                digits
             }
         };
 
-        let random_elements = compute_random_elements::<PRFH>(self.n, p_seed);
-        let mut value = vec![0u8; self.n];
+        self.walk_ladders_from(backend, randomness, &start, points, mode, out, maybe_chains)
+    }
+
+    // walk_ladders_from walks ladder `i` of `points` from level `start[i]` to the top (from
+    // the bottom up to it when signing) and writes the result into `out` like
+    // `compute_ladders_into`, which checks the sizes.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn walk_ladders_from<B: ChainBackend<PRFH>>(
+        &self,
+        backend: &B,
+        randomness: &PublicRandomness,
+        start: &[u16],
+        points: LadderPoints,
+        mode: ComputeLaddersMode,
+        out: &mut [u8],
+        mut maybe_chains: Option<ChainRows>,
+    ) -> Result<(), WotsError> {
+        // when signing, the ladder outputs are the result; otherwise they're hashed into it
+        let mut pk_outputs = Vec::new();
+        if mode != ComputeLaddersMode::Sign {
+            pk_outputs = vec![0u8; self.n * self.total];
+        }
+        let outputs = match mode {
+            ComputeLaddersMode::Sign => &mut *out,
+            _ => &mut pk_outputs[..],
+        };
+        outputs.copy_from_slice(points.as_bytes());
 
-        let mut outputs = vec![0u8; self.n * self.total];
-        let mut chains = vec![vec![0u8; self.n * self.total]; W];
         if mode == ComputeLaddersMode::Generate {
-            chains[0].copy_from_slice(points);
+            if let Some(ref mut chains) = maybe_chains {
+                chains.rows[0].copy_from_slice(outputs);
+            }
         }
 
-        let mut t_hasher = Sha3_256::new();
+        let top = (self.w - 1) as u16;
+        let ranges: Vec<(u16, u16)> = start
+            .iter()
+            .take(self.total)
+            .map(|&digit| match mode {
+                ComputeLaddersMode::Sign => (0, digit),
+                _ => (digit, top),
+            })
+            .collect();
+        let job = LadderJob {
+            n: self.n,
+            randomness,
+            ranges: &ranges,
+        };
+        let maybe_chains = match mode {
+            ComputeLaddersMode::Generate => maybe_chains.as_mut(),
+            _ => None,
+        };
+        backend.walk_ladders(&job, outputs, maybe_chains)?;
 
-        let mut begin;
-        let mut end;
+        // if signing, then the outputs (length = n * total) are already in place
+        if mode != ComputeLaddersMode::Sign {
+            self.aggregate_public_key(randomness.p_seed(), &pk_outputs, out);
+        }
+        Ok(())
+    }
 
-        for i in 0..self.total {
-            let from = (i * self.n) as usize;
-            let to = ((i + 1) * self.n) as usize;
-            value.clone_from_slice(&points[from..to]);
+    // aggregate_public_key hashes the ladder tops (n * total bytes) into the public key: the
+    // tops of odd parity into a tweak, then `p_seed || tweak || tops`.
+    fn aggregate_public_key(&self, p_seed: &[u8], outputs: &[u8], out: &mut [u8]) {
+        let odd: Vec<&[u8]> = outputs.chunks(self.n).filter(|top| parity(top)).collect();
+        let mut tweak = hash_buf(self.aggregation.size);
+        (self.aggregation.hash)(&odd, &mut tweak);
+        (self.aggregation.hash)(&[p_seed, &tweak, outputs], out);
+    }
 
-            match mode {
-                ComputeLaddersMode::Sign => {
-                    begin = 0;
-                    end = start[i as usize];
-                }
-                _ => {
-                    begin = start[i as usize];
-                    end = (W - 1) as u8;
-                }
-            };
-
-            match mode {
-                ComputeLaddersMode::Generate => {
-                    value = self.compute_chain(
-                        p_seed,
-                        &value,
-                        &random_elements,
-                        Some(&mut chains),
-                        i,
-                        (begin, end),
-                    );
-                }
-                _ => {
-                    value =
-                        self.compute_chain(p_seed, &value, &random_elements, None, i, (begin, end));
-                }
-            };
+    // compute_chain iterates c(value, randomness) over `range`, updating `value` in place.
+    pub(crate) fn compute_chain(
+        &self,
+        randomness: &PublicRandomness,
+        value: &mut [u8],
+        maybe_chains: Option<&mut ChainRows>,
+        idx: usize,
+        range: (u16, u16),
+    ) {
+        walk_chain::<PRFH>(self.n, randomness, value, maybe_chains, idx, range);
+    }
 
-            outputs[from..to].copy_from_slice(&value);
+    /// The randomizers of `p_seed` under these params, for the `*_with_randomness` methods.
+    pub fn public_randomness(&self, p_seed: &[u8; SEED_SIZE]) -> PublicRandomness {
+        PublicRandomness::with_w::<PRFH>(self.n, self.w, p_seed)
+    }
 
-            if mode != ComputeLaddersMode::Sign && parity(&value) {
-                Digest::update(&mut t_hasher, &value);
-            }
+    pub fn get_encoding(&self) -> &ParamsEncoding {
+        &self.encoding
+    }
+
+    /// Whether these params are at least as strong as `level`: both `n` and `m` are at least
+    /// as large as its values. Applies to custom params too; `false` for `level = Custom`.
+    pub fn is_at_least(&self, level: ParamsEncoding) -> bool {
+        match level.values() {
+            Some((n, m)) => self.n >= n && self.m >= m,
+            None => false,
         }
+    }
 
-        if mode != ComputeLaddersMode::Sign {
-            let tweak = t_hasher.finalize();
-            let mut t_hasher = Sha3_256::new();
-            Digest::update(&mut t_hasher, &p_seed);
-            Digest::update(&mut t_hasher, &tweak);
-            Digest::update(&mut t_hasher, &outputs);
+    pub fn verify(
+        &self,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+    ) -> Result<(), WotsError> {
+        self.verify_message(
+            Message::plain(msg.as_ref()),
+            signature.as_ref(),
+            public_key.as_ref(),
+        )
+    }
 
-            return Ok((t_hasher.finalize().to_vec(), chains));
+    /// Builds a `VerifyTable` checking signatures of `public_key` over `msgs` only.
+    pub fn precompute_verify_table(
+        &self,
+        msgs: &[&[u8]],
+        public_key: &[u8],
+    ) -> Result<VerifyTable<PRFH, MSGH>, WotsError> {
+        self.check()?;
+        let public_key = public_key
+            .try_into()
+            .map_err(|_| WotsError::InvalidPublicKeySize)?;
+        if msgs.iter().any(|msg| msg.len() > MAX_MSG_SIZE) {
+            return Err(WotsError::InvalidMessageSize);
         }
+        Ok(VerifyTable::new(self, msgs, public_key))
+    }
 
-        // if signing, then return outputs (length = n * total)
-        Ok((outputs, chains))
+    /// Strict `verify` of a full `signature` (encoding byte included), for systems that hash
+    /// signatures: besides verifying, it must be the single byte string these params accept
+    /// for `(msg, public_key)`, so its header has to be `canonical_encoding()`.
+    pub fn verify_strict(
+        &self,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+    ) -> Result<(), WotsError> {
+        let msg = msg.as_ref();
+        let signature = signature.as_ref();
+        let public_key = public_key.as_ref();
+        check_canonical(signature, self)?;
+        self.verify(msg, &signature[1..], public_key)
     }
 
-    // compute_chain returns the result of c(input, random_elements) iterated total times.
-    fn compute_chain(
+    /// Verifies a signature made by `Key::sign_with_context` with the same `context`.
+    pub fn verify_with_context(
         &self,
-        p_seed: &[u8],
-        input: &[u8],
-        random_elements: &[Vec<u8>],
-        mut maybe_chains: Option<&mut [Vec<u8>]>,
-        idx: usize,
-        range: (u8, u8),
-    ) -> Vec<u8> {
-        let mut curr_value = vec![0u8; self.n];
-        curr_value.clone_from_slice(input);
-
-        for j in range.0..range.1 {
-            let preimage: Vec<u8> = curr_value
-                .iter()
-                .zip(random_elements[j as usize].iter())
-                .map(|(&x1, &x2)| x1 ^ x2)
-                .collect();
-
-            let mut hasher = PRFH::new();
-            hasher.write(p_seed.to_vec());
-            hasher.write(vec![(j + 1) as u8]);
-            hasher.write(preimage);
-            let mut buf = vec![0u8; PRFH::size()];
-            hasher.sum(&mut buf);
-            curr_value.clone_from_slice(&buf[0..self.n]);
+        msg: impl AsRef<[u8]>,
+        context: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+    ) -> Result<(), WotsError> {
+        self.verify_message(
+            Message::with_context(msg.as_ref(), context.as_ref())?,
+            signature.as_ref(),
+            public_key.as_ref(),
+        )
+    }
 
-            if let Some(ref mut chains) = maybe_chains {
-                let from = idx * self.n;
-                let to = (idx + 1) * self.n;
-                chains[j as usize + 1][from..to].copy_from_slice(&curr_value);
-            }
+    /// Same as `verify`, but walks the ladders on `backend`.
+    pub fn verify_with<B: ChainBackend<PRFH>>(
+        &self,
+        backend: &B,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+    ) -> Result<(), WotsError> {
+        self.verify_message_with(
+            backend,
+            Message::plain(msg.as_ref()),
+            signature.as_ref(),
+            public_key.as_ref(),
+            None,
+        )
+    }
+
+    /// Same as `verify`, but walks the ladders of this one signature on `threads` threads, to
+    /// cut its latency on paths such as block import. Verifying many signatures is better
+    /// spread over threads one signature each.
+    #[cfg(feature = "std")]
+    pub fn verify_parallel(
+        &self,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+        threads: usize,
+    ) -> Result<(), WotsError> {
+        self.verify_with(&ThreadedBackend::new(threads), msg, signature, public_key)
+    }
+
+    /// Same as `verify`, with the randomizers of the signature's `p_seed` precomputed. Fails
+    /// with `RandomnessMismatch` when `randomness` belongs to another `p_seed`.
+    pub fn verify_with_randomness(
+        &self,
+        randomness: &PublicRandomness,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+    ) -> Result<(), WotsError> {
+        self.verify_message_with(
+            &SoftwareBackend,
+            Message::plain(msg.as_ref()),
+            signature.as_ref(),
+            public_key.as_ref(),
+            Some(randomness),
+        )
+    }
+
+    /// Hashes walking the ladders to verify a signature on `msg` takes: the `w - 1`
+    /// randomizers and one per step left on each ladder. The digits depend on the message
+    /// alone, so this is known before any signature is read.
+    pub fn verify_cost(&self, msg: impl AsRef<[u8]>) -> usize {
+        self.message_cost(Message::plain(msg.as_ref()))
+    }
+
+    pub(crate) fn message_cost(&self, message: Message) -> usize {
+        let w = self.digit_w();
+        let steps: usize = self
+            .message_digits(message)
+            .iter()
+            .map(|&d| w - 1 - d as usize)
+            .sum();
+        steps + w - 1
+    }
+
+    /// Same as `verify`, but fails with `WotsError::BudgetExceeded`, before walking any
+    /// ladder, if `verify_cost` is more than `max_hashes`.
+    pub fn verify_with_budget(
+        &self,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+        max_hashes: usize,
+    ) -> Result<(), WotsError> {
+        let message = Message::plain(msg.as_ref());
+        self.check()?;
+        if self.message_cost(message) > max_hashes {
+            return Err(WotsError::BudgetExceeded);
         }
+        self.verify_message(message, signature.as_ref(), public_key.as_ref())
+    }
 
-        let mut result = vec![0u8; self.n as usize];
-        result.clone_from_slice(&curr_value);
-        result
+    pub(crate) fn verify_message(
+        &self,
+        message: Message,
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<(), WotsError> {
+        self.verify_message_with(&SoftwareBackend, message, signature, public_key, None)
     }
 
-    pub fn get_encoding(&self) -> &ParamsEncoding {
-        &self.encoding
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "wots.verify",
+            level = "debug",
+            skip_all,
+            fields(
+                encoding = ?self.encoding,
+                msg_len = message.len(),
+                failure = tracing::field::Empty,
+            ),
+            err,
+        )
+    )]
+    fn verify_message_with<B: ChainBackend<PRFH>>(
+        &self,
+        backend: &B,
+        message: Message,
+        signature: &[u8],
+        public_key: &[u8],
+        randomness: Option<&PublicRandomness>,
+    ) -> Result<(), WotsError> {
+        let result = if public_key.len() != PK_SIZE {
+            Err(WotsError::InvalidPublicKeySize)
+        } else {
+            let mut pk = [0u8; PK_SIZE];
+            self.decode_with(backend, message, signature, &mut pk, randomness)
+                .and_then(|()| {
+                    // compare in constant time, so a forger learns nothing from how many bytes
+                    // matched
+                    if bool::from(public_key.ct_eq(&pk)) {
+                        Ok(())
+                    } else {
+                        Err(WotsError::InvalidSignature)
+                    }
+                })
+        };
+        #[cfg(feature = "tracing")]
+        record_failure(&result);
+        result
     }
 
-    pub fn verify(&self, msg: &[u8], signature: &[u8], public_key: &[u8]) -> Result<(), WotsError> {
+    /// Like `verify`, for deployments exposed to fault injection: the public key is decoded a
+    /// second time and each result is compared to `public_key` independently, so a single
+    /// glitched computation or comparison cannot make a forgery pass.
+    ///
+    /// The second pass walks the ladders in reverse, or in a random order when `shuffle` is set
+    /// (requires `std`; without it the order stays reversed).
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "wots.verify_hardened",
+            level = "debug",
+            skip_all,
+            fields(encoding = ?self.encoding, msg_len = msg.as_ref().len(), shuffle = shuffle),
+            err,
+        )
+    )]
+    pub fn verify_hardened(
+        &self,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+        shuffle: bool,
+    ) -> Result<(), WotsError> {
+        let msg = msg.as_ref();
+        let signature = signature.as_ref();
+        let public_key = public_key.as_ref();
         if public_key.len() != PK_SIZE {
             return Err(WotsError::InvalidPublicKeySize);
         }
 
-        let pk = self.decode(msg, signature)?;
-        if public_key != pk {
+        let mut order: Vec<usize> = (0..self.total).rev().collect();
+        if shuffle {
+            shuffle_order(&mut order);
+        }
+
+        let mut first = [0u8; PK_SIZE];
+        let mut second = [0u8; PK_SIZE];
+        self.decode_into(msg, signature, &mut first)?;
+        self.decode_with(
+            &OrderedBackend { order: &order },
+            Message::plain(msg),
+            signature,
+            &mut second,
+            None,
+        )?;
+
+        if !bool::from(core::hint::black_box(public_key.ct_eq(&first))) {
+            return Err(WotsError::InvalidSignature);
+        }
+        if !bool::from(core::hint::black_box(public_key.ct_eq(&second))) {
+            return Err(WotsError::InvalidSignature);
+        }
+        if !bool::from(core::hint::black_box(first.ct_eq(&second))) {
             return Err(WotsError::InvalidSignature);
         }
 
         Ok(())
     }
 
-    pub fn decode(&self, msg: &[u8], signature: &[u8]) -> Result<Vec<u8>, WotsError> {
+    /// Verifies `signature` like `verify`, recording every hash call and XOR on the way, e.g.
+    /// to produce SNARK witnesses. An invalid signature yields a trace with `valid` unset.
+    pub fn verify_traced(
+        &self,
+        msg: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<VerifyTrace, WotsError> {
+        trace_verification(self, msg, signature, public_key)
+    }
+
+    /// Recovers the public key once and returns the index of the first of `candidates` it
+    /// equals, or `None` if it matches none or the signature can't be decoded. Every
+    /// candidate is compared in constant time, so timing doesn't reveal which one matched.
+    pub fn decode_and_match(
+        &self,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        candidates: &[PublicKey],
+    ) -> Option<usize> {
+        let mut pk = [0u8; PK_SIZE];
+        self.decode_into(msg.as_ref(), signature.as_ref(), &mut pk)
+            .ok()?;
+
+        let mut index = 0u64;
+        let mut matched = Choice::from(0);
+        for (i, candidate) in candidates.iter().enumerate() {
+            let equal = candidate.as_bytes().ct_eq(&pk);
+            index.conditional_assign(&(i as u64), equal & !matched);
+            matched |= equal;
+        }
+        bool::from(matched).then_some(index as usize)
+    }
+
+    /// Recomputes the public key `signature` (without encoding byte) decodes to for `msg`.
+    /// Verification is decoding followed by a comparison with the expected key; see
+    /// `security::recover_public_key` for checking the encoding byte as well.
+    pub fn decode(
+        &self,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+    ) -> Result<Vec<u8>, WotsError> {
+        let mut pk = vec![0u8; PK_SIZE];
+        self.decode_into(msg.as_ref(), signature.as_ref(), &mut pk)?;
+        Ok(pk)
+    }
+
+    /// Same as `decode`, but writes the recovered public key into `out`, which must be
+    /// `PK_SIZE` bytes.
+    pub fn decode_into(
+        &self,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        out: &mut [u8],
+    ) -> Result<(), WotsError> {
+        self.decode_with(
+            &SoftwareBackend,
+            Message::plain(msg.as_ref()),
+            signature.as_ref(),
+            out,
+            None,
+        )
+    }
+
+    fn decode_with<B: ChainBackend<PRFH>>(
+        &self,
+        backend: &B,
+        message: Message,
+        signature: &[u8],
+        out: &mut [u8],
+        randomness: Option<&PublicRandomness>,
+    ) -> Result<(), WotsError> {
+        self.check()?;
         if signature.len() != (self.total * self.n) + SEED_SIZE {
             return Err(WotsError::InvalidSignatureSize);
         }
 
-        let (pk, _) = self.compute_ladders(
-            &signature[0..SEED_SIZE],
-            Some(msg.to_vec()),
-            &signature[SEED_SIZE..],
+        let p_seed: &[u8; SEED_SIZE] = signature[0..SEED_SIZE]
+            .try_into()
+            .map_err(|_| WotsError::InvalidSignatureSize)?;
+        let derived;
+        let randomness = match randomness {
+            Some(randomness) => {
+                randomness.matches(self.n, self.w, p_seed)?;
+                randomness
+            }
+            None => {
+                derived = self.public_randomness(p_seed);
+                &derived
+            }
+        };
+        self.compute_ladders_into(
+            backend,
+            randomness,
+            Some(message),
+            LadderPoints::new(&signature[SEED_SIZE..], self.n, self.total)?,
             ComputeLaddersMode::Verify,
-        )?;
-        Ok(pk)
+            out,
+            None,
+        )
     }
 }
 
-pub fn checksum(msg: &[u8]) -> Vec<u8> {
-    let mut sum = ((W - 1) as u16) * (msg.len() as u16);
-    for n in msg.iter() {
-        sum -= *n as u16;
+#[cfg(feature = "std")]
+fn shuffle_order(order: &mut [usize]) {
+    order.shuffle(&mut OsRng);
+}
+
+#[cfg(not(feature = "std"))]
+fn shuffle_order(_order: &mut [usize]) {}
+
+/// Number of checksum ladders needed for `m` message digits.
+pub const fn checksum_ladders(m: usize) -> usize {
+    checksum_len(W, m)
+}
+
+/// Total number of ladders for `m` message digits.
+pub const fn total_ladders(m: usize) -> usize {
+    m + checksum_ladders(m)
+}
+
+// ladders_for is the total number of ladders for an `m` byte digest under `w`; `total_ladders`
+// for `w = W`.
+const fn ladders_for(w: usize, m: usize) -> usize {
+    let digits = base_w_len(w, m);
+    digits + checksum_len(w, digits)
+}
+
+// valid_w accepts the values of `Params::with_w`.
+const fn valid_w(w: usize) -> bool {
+    w.is_power_of_two() && W <= w && w <= MAX_W
+}
+
+/// Size of a full signature (encoding byte, `p_seed` and ladder points) in bytes.
+pub const fn signature_size(n: usize, m: usize) -> usize {
+    1 + SEED_SIZE + n * total_ladders(m)
+}
+
+/// Size of the secret key (all ladder starting points) in bytes.
+pub const fn secret_key_size(n: usize, m: usize) -> usize {
+    n * total_ladders(m)
+}
+
+// StepIndex is chain step `j + 1` as hashed into the randomizers and ladder steps: one byte
+// while `w` is at most `W`, so those keep their values, and two big-endian bytes above.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StepIndex {
+    bytes: [u8; 2],
+    len: usize,
+}
+
+impl StepIndex {
+    pub(crate) fn new(w: usize, step: usize) -> Self {
+        let bytes = (step as u16).to_be_bytes();
+        match w <= W {
+            true => StepIndex {
+                bytes: [bytes[1], 0],
+                len: 1,
+            },
+            false => StepIndex { bytes, len: 2 },
+        }
     }
-    if msg.len() == 1 {
-        return vec![sum as u8];
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
     }
-    let upper = ((sum & 0xff00) >> 8) as u8;
-    let lower = sum as u8;
-    vec![upper, lower]
 }
 
-fn compute_random_elements<H: Hasher>(n: usize, p_seed: &[u8]) -> Vec<Vec<u8>> {
-    let mut random_elements = vec![vec![0u8; n]; W - 1];
-    let mut buf = vec![0u8; H::size()];
+/// One step of the W-OTS+ chaining function, as every ladder walk under `w = W` in this crate
+/// does it: maps `value`, at level `j`, to level `j + 1`, `H(p_seed || j + 1 || value ^ r_j)[..n]`
+/// with the randomizer `r_j = H(p_seed || j + 1)[..n]` and `n = value.len()`.
+pub fn chain_step<H: Hasher>(
+    p_seed: &[u8; SEED_SIZE],
+    value: &mut [u8],
+    j: u8,
+) -> Result<(), WotsError> {
+    if j as usize >= W - 1 {
+        return Err(WotsError::InvalidChainRange);
+    }
+    chain_walk::<H>(p_seed, value, (j, j + 1))
+}
+
+/// Walks `value` from level `range.0` up to level `range.1` (the top is `W - 1`) with
+/// `chain_step`.
+pub fn chain_walk<H: Hasher>(
+    p_seed: &[u8; SEED_SIZE],
+    value: &mut [u8],
+    range: (u8, u8),
+) -> Result<(), WotsError> {
+    if value.is_empty() || value.len() > H::size() {
+        return Err(WotsError::InvalidPointsSize);
+    }
+    if range.0 > range.1 {
+        return Err(WotsError::InvalidChainRange);
+    }
+
+    let n = value.len();
+    let mut mask = hash_buf(H::size());
+    let mut buf = hash_buf(H::size());
+    for j in range.0..range.1 {
+        let mut hasher = H::new();
+        hasher.write(p_seed);
+        hasher.write(&[j + 1]);
+        hasher.sum(&mut mask);
+        mask.iter_mut()
+            .zip(value.iter())
+            .for_each(|(m, &v)| *m ^= v);
 
-    for i in 0..W - 1 {
         let mut hasher = H::new();
-        hasher.write(p_seed.to_vec());
-        hasher.write(vec![(i + 1) as u8]);
+        hasher.write(p_seed);
+        hasher.write(&[j + 1]);
+        hasher.write(&mask[..n]);
         hasher.sum(&mut buf);
-        random_elements[i as usize].clone_from_slice(&buf[0..n])
+        value.copy_from_slice(&buf[..n]);
+    }
+    Ok(())
+}
+
+// Aggregation is the hasher of `Params::with_aggregation_hasher`, kept as a function pointer
+// so it doesn't add a type parameter to `Params`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Aggregation {
+    pub(crate) id: Option<HasherId>,
+    // tells hashers outside this crate apart, which all have no id
+    pub(crate) type_id: TypeId,
+    pub(crate) size: usize,
+    // hashes the concatenation of the parts into `out`, truncated to `out.len()` bytes
+    pub(crate) hash: fn(&[&[u8]], &mut [u8]),
+}
+
+impl Aggregation {
+    fn of<H: Hasher + 'static>() -> Self {
+        Aggregation {
+            id: H::ID,
+            type_id: TypeId::of::<H>(),
+            size: H::size(),
+            hash: hash_parts::<H>,
+        }
     }
+}
 
-    random_elements
+fn hash_parts<H: Hasher>(parts: &[&[u8]], out: &mut [u8]) {
+    let mut hasher = H::new();
+    for part in parts {
+        hasher.write(part);
+    }
+    hasher.sum(out);
 }
 
-fn parity(value: &[u8]) -> bool {
+pub(crate) fn parity(value: &[u8]) -> bool {
     let mut count = 0;
     for n in value.iter() {
         let mut v: u8 = *n;
@@ -363,11 +1706,22 @@ fn parity(value: &[u8]) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use crate::hasher::{Blake2bHasher, Hasher, Sha3_256Hasher};
-    use crate::params::{ComputeLaddersMode, Params, MAX_MSG_SIZE, SEED_SIZE, W};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use crate::codec::{DomainPrefix, LengthPrefix, MessageCodec, Scale};
+    use crate::hasher::{Blake2bHasher, Hasher, HasherId, Sha3_224Hasher, Sha3_256Hasher};
+    use crate::keys::{ChainCacheStrategy, GenerateCheckpoint, Key, PK_SIZE};
+    use crate::params::{
+        chain_step, chain_walk, ladders_for, ChecksumOrder, ComputeLaddersMode, EncodingProfile,
+        Message, Params, ParamsBuilder, VerifyFailure, WotsError, MAX_CONTEXT_SIZE, MAX_MSG_SIZE,
+        MAX_SEED_SIZE, MAX_W, SEED_SIZE, W,
+    };
+    use crate::randomness::PublicRandomness;
     use crate::security;
     use crate::security::ParamsEncoding;
-    use rand::{rngs::OsRng, RngCore};
+    use crate::signature::Signature;
+    use proptest::prelude::*;
 
     #[test]
     fn new_params() {
@@ -399,18 +1753,34 @@ mod tests {
         let params = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
         let total = 16; //arbitrary
         let input = vec![99u8; 32];
-        let p_seed = vec![88u8; SEED_SIZE];
+        let p_seed = [88u8; SEED_SIZE];
 
-        let mut random_elements = vec![vec![0u8; 32]; total];
-        for i in 0..total {
-            let mut x = vec![0u8; 32];
-            OsRng.fill_bytes(&mut x);
-            random_elements[i] = x;
-        }
+        let randomness = params.public_randomness(&p_seed);
 
-        let res =
-            params.compute_chain(&p_seed, &input, &random_elements, None, 0, (0, total as u8));
+        let mut res = input.clone();
+        params.compute_chain(&randomness, &mut res, None, 0, (0, total as u16));
         assert_eq!(res.len(), input.len());
+        assert_ne!(res, input);
+
+        // the public primitives walk the exact same chain, in any number of pieces
+        let mut walked = input.clone();
+        chain_walk::<Blake2bHasher>(&p_seed, &mut walked, (0, 10)).unwrap();
+        for j in 10..total as u8 {
+            chain_step::<Blake2bHasher>(&p_seed, &mut walked, j).unwrap();
+        }
+        assert_eq!(walked, res);
+        assert_eq!(
+            chain_walk::<Blake2bHasher>(&p_seed, &mut walked, (3, 2)),
+            Err(WotsError::InvalidChainRange)
+        );
+        assert_eq!(
+            chain_step::<Blake2bHasher>(&p_seed, &mut walked, (W - 1) as u8),
+            Err(WotsError::InvalidChainRange)
+        );
+        assert_eq!(
+            chain_step::<Blake2bHasher>(&p_seed, &mut [0u8; 33], 0),
+            Err(WotsError::InvalidPointsSize)
+        );
     }
 
     #[test]
@@ -424,7 +1794,7 @@ mod tests {
             .compute_ladders(&p_seed, None, &points, ComputeLaddersMode::Generate)
             .unwrap();
         assert_eq!(res.0.len(), Blake2bHasher::size());
-        assert_eq!(res.1.len(), W);
+        assert_eq!(res.1.unwrap().levels(), W);
     }
 
     #[test]
@@ -467,4 +1837,642 @@ mod tests {
             .unwrap();
         assert_eq!(res.0.len(), params.n * params.total);
     }
+
+    #[test]
+    fn decode_into() {
+        let params = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
+        let key = Key::new(params.clone()).unwrap();
+        let msg = vec![77u8; MAX_MSG_SIZE];
+        let sig = key.sign(&msg).unwrap();
+
+        // should fail with a wrongly sized buffer
+        let mut out = vec![0u8; PK_SIZE + 1];
+        assert!(params.decode_into(&msg, &sig[1..], &mut out).is_err());
+
+        let mut out = [0u8; PK_SIZE];
+        params.decode_into(&msg, &sig[1..], &mut out).unwrap();
+        assert_eq!(out.to_vec(), key.public_key);
+        assert_eq!(out.to_vec(), params.decode(&msg, &sig[1..]).unwrap());
+    }
+
+    #[test]
+    fn verify_rejects_any_differing_byte() {
+        let params = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
+        let key = Key::from_seed(params.clone(), [3u8; SEED_SIZE], [4u8; SEED_SIZE]).unwrap();
+        let msg = vec![7u8; 10];
+        let signature = key.sign(&msg).unwrap();
+        params
+            .verify(&msg, &signature[1..], &key.public_key)
+            .unwrap();
+
+        for i in [0, PK_SIZE / 2, PK_SIZE - 1] {
+            let mut public_key = key.public_key.clone();
+            public_key[i] ^= 1;
+            assert_eq!(
+                params.verify(&msg, &signature[1..], &public_key),
+                Err(crate::params::WotsError::InvalidSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn describe() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let description = params.describe();
+        assert_eq!(description.encoding, ParamsEncoding::Level0);
+        assert_eq!((description.n, description.m, description.w), (20, 24, W));
+        assert_eq!(description.total, 26);
+        assert_eq!(description.checksum_ladders, 2);
+        assert_eq!(description.signature_size, 1 + SEED_SIZE + 20 * 26);
+        assert_eq!(description.secret_key_size, 20 * 26);
+        assert_eq!(description.public_key_size, PK_SIZE);
+        assert_eq!(description.prf_hasher, Some(HasherId::Blake2b));
+        assert_eq!(description.msg_hasher.unwrap().name(), "sha3-224");
+    }
+
+    #[test]
+    fn aggregation_hasher() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let seeds = ([1u8; SEED_SIZE], [2u8; SEED_SIZE]);
+        let key = Key::from_seed(params.clone(), seeds.0, seeds.1).unwrap();
+
+        let sha3 = params
+            .clone()
+            .with_aggregation_hasher::<Sha3_256Hasher>()
+            .unwrap();
+        assert_eq!(sha3.canonical_encoding(), ParamsEncoding::Level0);
+        let same = Key::from_seed(sha3, seeds.0, seeds.1).unwrap();
+        assert_eq!(same.public_key, key.public_key);
+
+        let blake = params
+            .clone()
+            .with_aggregation_hasher::<Blake2bHasher>()
+            .unwrap();
+        assert_eq!(blake.canonical_encoding(), ParamsEncoding::Custom);
+        assert_eq!(blake.describe().aggregation_hasher, Some(HasherId::Blake2b));
+        assert_eq!(
+            params.describe().aggregation_hasher,
+            Some(HasherId::Sha3_256)
+        );
+        let other = Key::from_seed(blake.clone(), seeds.0, seeds.1).unwrap();
+        assert_ne!(other.public_key, key.public_key);
+
+        let signature = other.sign(b"msg").unwrap();
+        // the predefined byte would claim a public key no standard verifier derives
+        assert_eq!(*blake.get_encoding(), ParamsEncoding::Custom);
+        assert_eq!(signature[0], u8::from(&ParamsEncoding::Custom));
+        blake
+            .verify(b"msg", &signature[1..], &other.public_key)
+            .unwrap();
+        assert!(
+            blake
+                .verify_traced(b"msg", &signature[1..], &other.public_key)
+                .unwrap()
+                .valid
+        );
+        assert_eq!(
+            security::verify(b"msg", &signature, &other.public_key),
+            Err(WotsError::InvalidParamsEncodingType)
+        );
+
+        assert_eq!(
+            params
+                .with_aggregation_hasher::<Sha3_224Hasher>()
+                .unwrap_err(),
+            WotsError::InvalidHasher
+        );
+    }
+
+    #[test]
+    fn params_as_map_keys() {
+        type Level = Params<Blake2bHasher, Sha3_224Hasher>;
+        let level0 = Level::new(ParamsEncoding::Level0).unwrap();
+        let mut registry = HashMap::new();
+        registry.insert(level0.clone(), "level0");
+        registry.insert(Level::new(ParamsEncoding::Level1).unwrap(), "level1");
+        assert_eq!(registry.get(&security::level_0_params()), Some(&"level0"));
+        let mut custom = Level::new_from_values(20, 24).unwrap();
+        assert_ne!(custom, level0);
+        custom.encoding = ParamsEncoding::Level0;
+        assert_eq!(custom, level0);
+
+        let codec: Arc<dyn MessageCodec> = Arc::new(LengthPrefix);
+        let coded = level0.clone().with_codec(codec.clone());
+        assert_eq!(coded, level0.clone().with_codec(codec));
+        assert_eq!(coded, level0.clone().with_codec(Arc::new(LengthPrefix)));
+        assert_eq!(coded.encoding, ParamsEncoding::Custom);
+        assert_ne!(coded, level0.clone().with_codec(Arc::new(Scale)));
+        assert_ne!(
+            level0
+                .clone()
+                .with_codec(Arc::new(DomainPrefix(b"a".to_vec()))),
+            level0
+                .clone()
+                .with_codec(Arc::new(DomainPrefix(b"b".to_vec())))
+        );
+
+        // codecs without an identity only equal themselves
+        #[derive(Debug)]
+        struct Opaque;
+        impl MessageCodec for Opaque {
+            fn encode(&self, msg: &[u8], write: &mut dyn FnMut(&[u8])) {
+                write(msg);
+            }
+        }
+        let opaque: Arc<dyn MessageCodec> = Arc::new(Opaque);
+        let opaque_coded = level0.clone().with_codec(opaque.clone());
+        assert_eq!(opaque_coded, level0.clone().with_codec(opaque));
+        assert_ne!(opaque_coded, level0.clone().with_codec(Arc::new(Opaque)));
+        for other in [
+            level0.clone().with_w(1 << 10).unwrap(),
+            level0.clone().with_seed_size(64).unwrap(),
+            level0.clone().with_profile(EncodingProfile {
+                bind_params: true,
+                ..EncodingProfile::default()
+            }),
+            level0
+                .clone()
+                .with_aggregation_hasher::<Blake2bHasher>()
+                .unwrap(),
+            coded,
+        ] {
+            assert_ne!(other, level0);
+            assert!(!registry.contains_key(&other));
+        }
+    }
+
+    #[test]
+    fn params_builder() {
+        type Level = Params<Blake2bHasher, Sha3_224Hasher>;
+        let params: Level = ParamsBuilder::new().n(26).m(20).w(1 << 10).build().unwrap();
+        assert_eq!((params.n, params.m, params.w), (26, 20, 1 << 10));
+        assert_eq!(params.total, ladders_for(1 << 10, 20));
+        assert_eq!(params.encoding, ParamsEncoding::Custom);
+        assert_eq!(
+            ParamsBuilder::new().n(20).m(24).build(),
+            Level::new_from_values(20, 24)
+        );
+
+        for (builder, err) in [
+            (ParamsBuilder::new().n(26), WotsError::InvalidMValue),
+            (ParamsBuilder::new().n(26).m(0), WotsError::InvalidMValue),
+            (ParamsBuilder::new().m(20), WotsError::InvalidNValue),
+            (ParamsBuilder::new().n(0).m(20), WotsError::InvalidNValue),
+            (ParamsBuilder::new().n(33).m(20), WotsError::InvalidHasher),
+            (ParamsBuilder::new().n(26).m(29), WotsError::InvalidHasher),
+            (
+                ParamsBuilder::new().n(26).m(20).w(16),
+                WotsError::InvalidWValue,
+            ),
+            (
+                ParamsBuilder::new().n(26).m(20).w(MAX_W * 2),
+                WotsError::InvalidWValue,
+            ),
+            (
+                ParamsBuilder::new()
+                    .n(26)
+                    .m(20)
+                    .seed_size(MAX_SEED_SIZE + 1),
+                WotsError::InvalidSeedSize,
+            ),
+            (
+                ParamsBuilder::new()
+                    .n(26)
+                    .m(20)
+                    .context([0u8; MAX_CONTEXT_SIZE + 1]),
+                WotsError::InvalidContextSize,
+            ),
+        ] {
+            assert_eq!(
+                builder.build::<Blake2bHasher, Sha3_224Hasher>().err(),
+                Some(err)
+            );
+        }
+
+        // context-bound params verify what plain params sign with the same context
+        let plain = Level::new_from_values(26, 20).unwrap();
+        let key = Key::from_seed(plain.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let signed = key.sign_with_context(b"msg", b"app").unwrap();
+        // params verify signatures without the header byte
+        let signature = &signed[1..];
+        let bound: Level = ParamsBuilder::new()
+            .n(26)
+            .m(20)
+            .context(b"app")
+            .build()
+            .unwrap();
+        bound.verify(b"msg", signature, &key.public_key).unwrap();
+        assert!(plain.verify(b"msg", signature, &key.public_key).is_err());
+        let other: Level = ParamsBuilder::new()
+            .n(26)
+            .m(20)
+            .context(b"other")
+            .build()
+            .unwrap();
+        assert!(other.verify(b"msg", signature, &key.public_key).is_err());
+    }
+
+    #[test]
+    fn bind_params_profile() {
+        let bound = EncodingProfile {
+            bind_params: true,
+            ..EncodingProfile::default()
+        };
+        let level2 = security::level_2_params::<Blake2bHasher, Sha3_224Hasher>();
+        let bound2 = level2.clone().with_profile(bound);
+        let msg = b"XX NETWORK";
+        assert_ne!(
+            level2.msg_hash_and_compute_checksum(msg),
+            bound2.msg_hash_and_compute_checksum(msg)
+        );
+        // the same digest truncated to other params no longer yields a prefix of the digits
+        let bound3 =
+            security::level_3_params::<Blake2bHasher, Sha3_224Hasher>().with_profile(bound);
+        assert_ne!(
+            bound2.msg_hash_and_compute_checksum(msg)[..bound2.m],
+            bound3.msg_hash_and_compute_checksum(msg)[..bound3.m]
+        );
+        let mut ctx = bound2.message_hasher();
+        ctx.update(msg);
+        assert_eq!(
+            ctx.finish_digits(),
+            bound2.msg_hash_and_compute_checksum(msg)
+        );
+
+        let key = Key::from_seed(bound2.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let signature = key.sign(msg).unwrap();
+        // the header doesn't claim digits the profile changed
+        assert_eq!(signature[0], u8::from(&ParamsEncoding::Custom));
+        assert_eq!(
+            level2.clone().with_profile(EncodingProfile::default()),
+            level2
+        );
+        bound2
+            .verify(msg, &signature[1..], &key.public_key)
+            .unwrap();
+        assert_eq!(
+            level2.verify(msg, &signature[1..], &key.public_key),
+            Err(WotsError::InvalidSignature)
+        );
+
+        // hashers outside this crate are bound by their own name, and need one
+        #[derive(Clone)]
+        struct Named<const NAMED: bool>(Sha3_224Hasher);
+        impl<const NAMED: bool> Hasher for Named<NAMED> {
+            const SIZE: usize = Sha3_224Hasher::SIZE;
+            const NAME: Option<&'static str> = if NAMED { Some("test-sha3-224") } else { None };
+            fn new() -> Self {
+                Named(Sha3_224Hasher::new())
+            }
+            fn write(&mut self, data: &[u8]) {
+                self.0.write(data)
+            }
+            fn sum(self, out: &mut [u8]) {
+                self.0.sum(out)
+            }
+        }
+        let named = security::level_2_params::<Blake2bHasher, Named<true>>().with_profile(bound);
+        named.check().unwrap();
+        assert_ne!(
+            named.msg_hash_and_compute_checksum(msg),
+            bound2.msg_hash_and_compute_checksum(msg)
+        );
+        let unnamed = security::level_2_params::<Blake2bHasher, Named<false>>();
+        unnamed.check().unwrap();
+        let unnamed = unnamed.with_profile(bound);
+        assert_eq!(unnamed.check(), Err(WotsError::InvalidHasher));
+        assert_eq!(
+            Key::from_seed(unnamed, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).err(),
+            Some(WotsError::InvalidHasher)
+        );
+    }
+
+    #[test]
+    fn configurable_w() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        for w in [0, 2, 16, 257, 1 << 17] {
+            assert_eq!(
+                params.clone().with_w(w).err(),
+                Some(WotsError::InvalidWValue)
+            );
+        }
+
+        let msg = b"XX NETWORK";
+        let digest = params.msg_hash_and_compute_checksum(msg);
+        let wide = params.clone().with_w(MAX_W).unwrap();
+        // 16 bit digits are pairs of the base-256 ones
+        let digits = wide.msg_hash_and_compute_checksum(msg);
+        assert_eq!((wide.total, digits.len()), (14, 14));
+        for (i, &digit) in digits[..12].iter().enumerate() {
+            assert_eq!(digit, digest[2 * i] << 8 | digest[2 * i + 1]);
+        }
+        // 9 bit digits don't divide the digest; the last one is padded with zero bits
+        let odd = params.clone().with_w(512).unwrap();
+        let digits = odd.msg_hash_and_compute_checksum(msg);
+        assert_eq!(odd.total, 22 + 2);
+        assert_eq!(digits[0], digest[0] << 1 | digest[1] >> 7);
+        assert_eq!(digits[21], (digest[23] & 0x7) << 6);
+
+        for w in [512, 1 << 12] {
+            let experimental = params.clone().with_w(w).unwrap();
+            assert_eq!(experimental.canonical_encoding(), ParamsEncoding::Custom);
+            assert_eq!(experimental.describe().w, w);
+            assert!(experimental.signature_size() < params.signature_size());
+
+            let mut key =
+                Key::from_seed(experimental.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+            let signature = key.sign(msg).unwrap();
+            assert_eq!(signature.len(), experimental.signature_size());
+            assert_eq!(*experimental.get_encoding(), ParamsEncoding::Custom);
+            assert_eq!(signature[0], u8::from(&ParamsEncoding::Custom));
+            experimental
+                .verify(msg, &signature[1..], &key.public_key)
+                .unwrap();
+            assert_eq!(
+                experimental.verify(b"XX NETWORL", &signature[1..], &key.public_key),
+                Err(WotsError::InvalidSignature)
+            );
+            assert_eq!(
+                experimental.public_randomness(&[2u8; SEED_SIZE]).len(),
+                w - 1
+            );
+            let randomness = params.public_randomness(&[2u8; SEED_SIZE]);
+            assert_eq!(
+                experimental.verify_with_randomness(
+                    &randomness,
+                    msg,
+                    &signature[1..],
+                    &key.public_key
+                ),
+                Err(WotsError::RandomnessMismatch)
+            );
+
+            key.generate_with_strategy(ChainCacheStrategy::Sparse(64))
+                .unwrap();
+            assert_eq!(key.sign(msg).unwrap(), signature);
+        }
+    }
+
+    #[test]
+    fn little_endian_checksum() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let little = params.clone().with_profile(EncodingProfile {
+            checksum_order: ChecksumOrder::LittleEndian,
+            ..EncodingProfile::default()
+        });
+        let msg = b"XX NETWORK";
+        let big_digits = params.msg_hash_and_compute_checksum(msg);
+        let little_digits = little.msg_hash_and_compute_checksum(msg);
+        assert_eq!(big_digits[..params.m], little_digits[..params.m]);
+        assert_eq!(big_digits[params.m], little_digits[params.m + 1]);
+        assert_eq!(big_digits[params.m + 1], little_digits[params.m]);
+
+        let key = Key::from_seed(little.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let signature = key.sign(msg).unwrap();
+        little
+            .verify(msg, &signature[1..], &key.public_key)
+            .unwrap();
+        assert_eq!(
+            params.verify(msg, &signature[1..], &key.public_key),
+            Err(WotsError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn decode_and_match() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let keys: Vec<_> = (1..4u8)
+            .map(|i| Key::from_seed(params.clone(), [i; SEED_SIZE], [i; SEED_SIZE]).unwrap())
+            .collect();
+        let candidates: Vec<_> = keys
+            .iter()
+            .map(|key| key.verifying_key().unwrap())
+            .collect();
+        let signature = keys[1].sign(b"msg").unwrap();
+
+        assert_eq!(
+            params.decode_and_match(b"msg", &signature[1..], &candidates),
+            Some(1)
+        );
+        assert_eq!(
+            params.decode_and_match(b"msg", &signature[1..], &candidates[2..]),
+            None
+        );
+        assert_eq!(
+            params.decode_and_match(b"msg", &signature[2..], &candidates),
+            None
+        );
+    }
+
+    #[test]
+    fn error_codes() {
+        // retired codes are never handed out again
+        const RETIRED: [u32; 1] = [29];
+        for code in 1..63 {
+            match WotsError::from_code(code) {
+                Some(err) => assert_eq!(err.code(), code),
+                None => assert!(RETIRED.contains(&code), "code {} unassigned", code),
+            }
+        }
+        assert_eq!(WotsError::from_code(63), None);
+        assert_eq!(WotsError::from_code(0), None);
+        // codes are part of the API: pin a few so renumbering shows up here
+        assert_eq!(WotsError::InvalidSignature.code(), 11);
+        assert_eq!(WotsError::EntropyFailure.code(), 22);
+    }
+
+    #[test]
+    fn verify_failure() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let signature = key.sign(b"msg").unwrap();
+        let failure = |msg: &[u8], signature: &[u8], public_key: &[u8]| {
+            params
+                .verify(msg, signature, public_key)
+                .unwrap_err()
+                .verify_failure()
+        };
+        assert_eq!(
+            failure(b"msh", &signature[1..], &key.public_key),
+            Some(VerifyFailure::Invalid)
+        );
+        assert_eq!(
+            failure(b"msg", &signature[2..], &key.public_key),
+            Some(VerifyFailure::Malformed)
+        );
+        assert_eq!(
+            failure(b"msg", &signature[1..], &key.public_key[1..]),
+            Some(VerifyFailure::Malformed)
+        );
+        assert_eq!(WotsError::BudgetExceeded.verify_failure(), None);
+        assert_eq!(VerifyFailure::Malformed.to_string(), "malformed");
+    }
+
+    #[test]
+    fn streaming_digits() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let msg: Vec<u8> = (0..100u8).collect();
+        let mut ctx = params.message_hasher();
+        for chunk in msg.chunks(7) {
+            ctx.update(chunk);
+        }
+        let digits = ctx.finish_digits();
+        assert_eq!(digits.len(), params.total);
+        assert_eq!(digits, params.msg_hash_and_compute_checksum(&msg));
+
+        let mut ctx = params.message_hasher_with_context(b"gossip").unwrap();
+        ctx.update(&msg);
+        let expected = params.message_digits(Message::with_context(&msg, b"gossip").unwrap());
+        assert_eq!(
+            ctx.finish_digits(),
+            expected.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn strength_order() {
+        assert!(ParamsEncoding::Level0 < ParamsEncoding::Level2);
+        assert!(ParamsEncoding::Consensus > ParamsEncoding::Level3);
+        assert_eq!(
+            ParamsEncoding::Custom.partial_cmp(&ParamsEncoding::Level0),
+            None
+        );
+
+        let level2 = security::level_2_params::<Blake2bHasher, Sha3_224Hasher>();
+        assert!(level2.is_at_least(ParamsEncoding::Level1));
+        assert!(level2.is_at_least(ParamsEncoding::Level2));
+        assert!(!level2.is_at_least(ParamsEncoding::Level3));
+        assert!(!level2.is_at_least(ParamsEncoding::Custom));
+        let custom = Params::<Blake2bHasher, Sha3_256Hasher>::new_from_values(30, 32).unwrap();
+        assert!(custom.is_at_least(ParamsEncoding::Level2));
+        assert!(!custom.is_at_least(ParamsEncoding::Consensus));
+    }
+
+    #[test]
+    fn verify_parallel() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let signature = key.sign(b"msg").unwrap();
+        for threads in [0, 1, 3, 100] {
+            params
+                .verify_parallel(b"msg", &signature[1..], &key.public_key, threads)
+                .unwrap();
+            assert_eq!(
+                params.verify_parallel(b"msh", &signature[1..], &key.public_key, threads),
+                Err(WotsError::InvalidSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn verify_hardened() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [3u8; SEED_SIZE], [4u8; SEED_SIZE]).unwrap();
+        let msg = vec![7u8; 10];
+        let signature = key.sign(&msg).unwrap();
+
+        for shuffle in [false, true] {
+            params
+                .verify_hardened(&msg, &signature[1..], &key.public_key, shuffle)
+                .unwrap();
+
+            let mut mutated = signature.clone();
+            mutated[signature.len() - 1] ^= 1;
+            assert_eq!(
+                params.verify_hardened(&msg, &mutated[1..], &key.public_key, shuffle),
+                Err(crate::params::WotsError::InvalidSignature)
+            );
+        }
+    }
+
+    #[test]
+    fn tampered_params() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let sig = key.sign(b"msg").unwrap();
+        let pk = key.verifying_key().unwrap();
+
+        // the fields are public, so they can be changed after construction
+        let cases = [
+            (
+                Params {
+                    n: 0,
+                    ..params.clone()
+                },
+                WotsError::InvalidNValue,
+            ),
+            (
+                Params {
+                    n: 64,
+                    ..params.clone()
+                },
+                WotsError::InvalidHasher,
+            ),
+            (
+                Params {
+                    m: 64,
+                    ..params.clone()
+                },
+                WotsError::InvalidHasher,
+            ),
+            (
+                Params {
+                    total: params.total + 1,
+                    ..params.clone()
+                },
+                WotsError::InconsistentParams,
+            ),
+            (
+                Params {
+                    w: 0,
+                    ..params.clone()
+                },
+                WotsError::InvalidWValue,
+            ),
+            (
+                Params {
+                    w: 1 << 12,
+                    ..params.clone()
+                },
+                WotsError::InconsistentParams,
+            ),
+        ];
+        for (tampered, err) in cases {
+            assert_eq!(tampered.verify(b"msg", &sig[1..], pk), Err(err.clone()));
+            assert_eq!(tampered.decode(b"msg", &sig[1..]), Err(err.clone()));
+            assert_eq!(
+                Key::from_seed(tampered.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).err(),
+                Some(err)
+            );
+            tampered.msg_hash_and_compute_checksum(b"msg");
+            tampered.verify_cost(b"msg");
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        // parsers of untrusted bytes return errors rather than panic
+        #[test]
+        fn untrusted_bytes_never_panic(
+            encoding in 0u8..8,
+            mut bytes in proptest::collection::vec(any::<u8>(), 0..1200),
+            msg in proptest::collection::vec(any::<u8>(), 0..=MAX_MSG_SIZE + 1),
+        ) {
+            if let Some(first) = bytes.first_mut() {
+                *first = encoding;
+            }
+            let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+            let _ = security::verify(&msg, &bytes, &bytes);
+            let _ = security::inspect(&bytes, Some(&msg));
+            let _ = params.verify(&msg, &bytes, [0u8; PK_SIZE]);
+            let _ = params.decode(&msg, &bytes);
+            let _ = Signature::try_from(bytes.as_slice());
+            let _ = PublicRandomness::from_bytes(&bytes);
+            let _ = GenerateCheckpoint::from_bytes(&bytes);
+            let mut key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+            let _ = key.import_chain_cache(&bytes);
+            let _ = key.sign(&msg);
+        }
+    }
 }
@@ -0,0 +1,92 @@
+//! Kani harnesses, run with `cargo kani --features verify-proofs`. They cover the arithmetic
+//! every signature relies on for all valid `(n, m)`, and the checksum for all digit values
+//! of bounded length.
+
+use crate::digits::{checksum_digits, checksum_len};
+use crate::ladders::LadderPoints;
+use crate::params::{
+    checksum_ladders, secret_key_size, signature_size, total_ladders, MAX_MSG_SIZE, MAX_W,
+    SEED_SIZE, W,
+};
+
+// largest n of any hasher in this crate
+const MAX_N: usize = 64;
+
+// longest digit string the checksum is checked for; the sum is linear in the length, so
+// bigger m only scales the values bounded by `checksum_len_holds_the_largest_sum`
+const CHECKSUM_LEN: usize = 4;
+
+#[kani::proof]
+#[kani::unwind(10)]
+fn checksum_len_holds_the_largest_sum() {
+    let w: usize = kani::any();
+    let m: usize = kani::any();
+    kani::assume((2..=W).contains(&w));
+    kani::assume((1..=MAX_MSG_SIZE).contains(&m));
+
+    let digits = checksum_len(w, m) as u32;
+    assert!(digits >= 1);
+    assert!((m as u64) * (w as u64 - 1) < (w as u64).pow(digits));
+}
+
+#[kani::proof]
+#[kani::unwind(6)]
+fn checksum_never_underflows_and_stays_below_w() {
+    let w: usize = kani::any();
+    kani::assume((2..=W).contains(&w));
+    let len: usize = kani::any();
+    kani::assume(len <= CHECKSUM_LEN);
+    let digits: [u8; CHECKSUM_LEN] = kani::any();
+    for &digit in digits.iter().take(len) {
+        kani::assume((digit as usize) < w);
+    }
+
+    let checksum = checksum_digits(&digits[..len], w);
+    assert_eq!(checksum.len(), checksum_len(w, len));
+    let mut value = 0u64;
+    for &digit in checksum.iter() {
+        assert!((digit as usize) < w);
+        value = value * w as u64 + digit as u64;
+    }
+    let expected: u64 = digits[..len]
+        .iter()
+        .map(|&digit| (w - 1 - digit as usize) as u64)
+        .sum();
+    assert_eq!(value, expected);
+}
+
+#[kani::proof]
+#[kani::unwind(4)]
+fn signature_offsets_in_bounds() {
+    let n: usize = kani::any();
+    let m: usize = kani::any();
+    let i: usize = kani::any();
+    kani::assume((1..=MAX_N).contains(&n));
+    kani::assume((1..=MAX_MSG_SIZE).contains(&m));
+    let total = total_ladders(m);
+    kani::assume(i < total);
+
+    // the w - 1 randomizers are indexed by the chain step, which is a u16 digit
+    assert!(total == m + checksum_ladders(m));
+    assert!(MAX_W - 1 == u16::MAX as usize);
+
+    // ladder i of a signature, behind the encoding byte and p_seed
+    let end = 1 + SEED_SIZE + (i + 1) * n;
+    assert!(end <= signature_size(n, m));
+    assert!((i + 1) * n <= secret_key_size(n, m));
+    assert_eq!(signature_size(n, m), 1 + SEED_SIZE + secret_key_size(n, m));
+}
+
+#[kani::proof]
+#[kani::unwind(3)]
+fn ladder_points_in_bounds() {
+    let n: usize = kani::any();
+    let total: usize = kani::any();
+    let i: usize = kani::any();
+    kani::assume((1..=2).contains(&n) && (1..=2).contains(&total) && i < total);
+    let bytes = [0u8; 4];
+
+    let points = LadderPoints::new(&bytes[..n * total], n, total).unwrap();
+    assert_eq!(points.len(), total);
+    assert_eq!(points.ladder(i).len(), n);
+}
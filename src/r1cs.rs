@@ -0,0 +1,303 @@
+//! R1CS constraints mirroring the ladder walk of `Params::compute_ladders`, for proving
+//! knowledge of a valid W-OTS+ signature inside a SNARK.
+//!
+//! Blake2b and Sha3 are far too expensive in-circuit, so this instantiates the scheme over a
+//! prime field with a pluggable circuit-friendly `CircuitHasher`: ladder values are single
+//! field elements, the XOR with `random_elements[j]` becomes a field addition, and the public
+//! key is `H(p_seed, tops..)`, without the parity tweak. The native functions here compute the
+//! same values outside the circuit.
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::boolean::Boolean;
+use ark_r1cs_std::eq::EqGadget;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_r1cs_std::fields::FieldVar;
+use ark_r1cs_std::select::CondSelectGadget;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use crate::digits::{checksum_digits, checksum_len};
+
+/// Hash over field elements with a matching constraint gadget. `hash_gadget` must constrain
+/// its output to equal `hash` of the witnessed inputs.
+pub trait CircuitHasher<F: PrimeField> {
+    fn hash(inputs: &[F]) -> F;
+    fn hash_gadget(inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError>;
+}
+
+/// Shape of the field instantiation: winternitz parameter `w` and `m` message digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitParams {
+    pub w: usize,
+    pub m: usize,
+}
+
+impl CircuitParams {
+    /// Number of ladders, including the checksum ladders.
+    pub fn total(&self) -> usize {
+        self.m + checksum_len(self.w, self.m)
+    }
+
+    /// Message digits followed by their base-`w` checksum digits.
+    pub fn digits(&self, msg_digits: &[u8]) -> Vec<u8> {
+        let mut digits = msg_digits.to_vec();
+        digits.extend(checksum_digits(msg_digits, self.w));
+        digits
+    }
+}
+
+/// `random_elements[j] = H(p_seed, j + 1)`
+pub fn random_elements<F: PrimeField, H: CircuitHasher<F>>(w: usize, p_seed: F) -> Vec<F> {
+    (1..w as u64)
+        .map(|j| H::hash(&[p_seed, F::from(j)]))
+        .collect()
+}
+
+/// Walks `value` from level `begin` to `end`, one `H(p_seed, j + 1, value + r_j)` per step.
+pub fn walk<F: PrimeField, H: CircuitHasher<F>>(
+    p_seed: F,
+    random_elements: &[F],
+    mut value: F,
+    begin: usize,
+    end: usize,
+) -> F {
+    for (j, r) in random_elements.iter().enumerate().take(end).skip(begin) {
+        value = H::hash(&[p_seed, F::from(j as u64 + 1), value + r]);
+    }
+    value
+}
+
+/// Signature points over `secret` ladder starts for `digits` (see `CircuitParams::digits`).
+pub fn sign<F: PrimeField, H: CircuitHasher<F>>(
+    params: &CircuitParams,
+    p_seed: F,
+    secret: &[F],
+    digits: &[u8],
+) -> Vec<F> {
+    let random_elements = random_elements::<F, H>(params.w, p_seed);
+    secret
+        .iter()
+        .zip(digits)
+        .map(|(&sk, &digit)| walk::<F, H>(p_seed, &random_elements, sk, 0, digit as usize))
+        .collect()
+}
+
+/// Public key for `secret` ladder starts, or, given signature points and their digits, the
+/// public key the signature decodes to.
+pub fn public_key<F: PrimeField, H: CircuitHasher<F>>(
+    params: &CircuitParams,
+    p_seed: F,
+    points: &[F],
+    digits: &[u8],
+) -> F {
+    let random_elements = random_elements::<F, H>(params.w, p_seed);
+    let mut inputs = vec![p_seed];
+    for (&point, &digit) in points.iter().zip(digits) {
+        inputs.push(walk::<F, H>(
+            p_seed,
+            &random_elements,
+            point,
+            digit as usize,
+            params.w - 1,
+        ));
+    }
+    H::hash(&inputs)
+}
+
+/// Allocated inputs of a verification: `msg_digits` and `public_key` are usually public
+/// inputs, `points` and `checksum_digits` witnesses.
+pub struct VerifyVars<F: PrimeField> {
+    pub p_seed: FpVar<F>,
+    pub msg_digits: Vec<FpVar<F>>,
+    pub checksum_digits: Vec<FpVar<F>>,
+    pub points: Vec<FpVar<F>>,
+    pub public_key: FpVar<F>,
+}
+
+impl<F: PrimeField> VerifyVars<F> {
+    /// Allocates the digits and public key as public inputs and the rest as witnesses.
+    pub fn new(
+        cs: ConstraintSystemRef<F>,
+        params: &CircuitParams,
+        p_seed: F,
+        points: &[F],
+        digits: &[u8],
+        public_key: F,
+    ) -> Result<Self, SynthesisError> {
+        let input = |v: F| FpVar::new_input(cs.clone(), || Ok(v));
+        let witness = |v: F| FpVar::new_witness(cs.clone(), || Ok(v));
+        Ok(VerifyVars {
+            p_seed: witness(p_seed)?,
+            msg_digits: digits[..params.m]
+                .iter()
+                .map(|&d| input(F::from(d as u64)))
+                .collect::<Result<_, _>>()?,
+            checksum_digits: digits[params.m..]
+                .iter()
+                .map(|&d| witness(F::from(d as u64)))
+                .collect::<Result<_, _>>()?,
+            points: points
+                .iter()
+                .map(|&p| witness(p))
+                .collect::<Result<_, _>>()?,
+            public_key: input(public_key)?,
+        })
+    }
+}
+
+/// Constrains `vars` to be a valid signature: every digit is below `w`, the checksum digits
+/// match the message digits, and walking each point to the top of its ladder yields
+/// `public_key`.
+pub fn verify_gadget<F: PrimeField, H: CircuitHasher<F>>(
+    params: &CircuitParams,
+    vars: &VerifyVars<F>,
+) -> Result<(), SynthesisError> {
+    let w = params.w;
+    if vars.msg_digits.len() != params.m
+        || vars.checksum_digits.len() != params.total() - params.m
+        || vars.points.len() != params.total()
+    {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+
+    // checksum = sum(w - 1 - digit), written big-endian in base w
+    let mut checksum = FpVar::zero();
+    for digit in &vars.msg_digits {
+        checksum += FpVar::constant(F::from((w - 1) as u64)) - digit;
+    }
+    let mut encoded = FpVar::zero();
+    for digit in &vars.checksum_digits {
+        encoded = encoded * FpVar::constant(F::from(w as u64)) + digit;
+    }
+    checksum.enforce_equal(&encoded)?;
+
+    let random_elements: Vec<FpVar<F>> = (1..w as u64)
+        .map(|j| H::hash_gadget(&[vars.p_seed.clone(), FpVar::constant(F::from(j))]))
+        .collect::<Result<_, _>>()?;
+
+    let mut inputs = vec![vars.p_seed.clone()];
+    let digits = vars.msg_digits.iter().chain(&vars.checksum_digits);
+    for (point, digit) in vars.points.iter().zip(digits) {
+        inputs.push(ladder_top::<F, H>(
+            w,
+            &vars.p_seed,
+            &random_elements,
+            point,
+            digit,
+        )?);
+    }
+    H::hash_gadget(&inputs)?.enforce_equal(&vars.public_key)
+}
+
+// ladder_top walks `point` from level `digit` to `w - 1`. As the digit is a witness, every
+// step is computed and only applied once the walk has reached `digit`; matching exactly one
+// level also range checks the digit.
+fn ladder_top<F: PrimeField, H: CircuitHasher<F>>(
+    w: usize,
+    p_seed: &FpVar<F>,
+    random_elements: &[FpVar<F>],
+    point: &FpVar<F>,
+    digit: &FpVar<F>,
+) -> Result<FpVar<F>, SynthesisError> {
+    let mut value = point.clone();
+    let mut walking = Boolean::FALSE;
+    let mut matches = FpVar::zero();
+    for (level, r) in random_elements.iter().enumerate() {
+        let at_level = digit.is_eq(&FpVar::constant(F::from(level as u64)))?;
+        matches += FpVar::from(at_level.clone());
+        walking = walking.or(&at_level)?;
+
+        let step = FpVar::constant(F::from(level as u64 + 1));
+        let next = H::hash_gadget(&[p_seed.clone(), step, &value + r])?;
+        value = FpVar::conditionally_select(&walking, &next, &value)?;
+    }
+    // the top level takes no steps
+    let at_top = digit.is_eq(&FpVar::constant(F::from((w - 1) as u64)))?;
+    matches += FpVar::from(at_top);
+    matches.enforce_equal(&FpVar::one())?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_ff::Field;
+    use ark_r1cs_std::fields::fp::FpVar;
+    use ark_r1cs_std::fields::FieldVar;
+    use ark_relations::r1cs::{ConstraintSystem, SynthesisError};
+
+    use crate::r1cs::{self, CircuitHasher, CircuitParams, VerifyVars};
+
+    // Toy MiMC-style sponge; fine for testing the constraints, not a secure hash.
+    struct ToyHasher;
+
+    const ROUNDS: u64 = 8;
+
+    impl CircuitHasher<Fr> for ToyHasher {
+        fn hash(inputs: &[Fr]) -> Fr {
+            let mut state = Fr::from(0u64);
+            for input in inputs {
+                state += input;
+                for c in 0..ROUNDS {
+                    state = (state + Fr::from(c * 7 + 3)).pow([5u64]);
+                }
+            }
+            state
+        }
+
+        fn hash_gadget(inputs: &[FpVar<Fr>]) -> Result<FpVar<Fr>, SynthesisError> {
+            let mut state = FpVar::zero();
+            for input in inputs {
+                state += input;
+                for c in 0..ROUNDS {
+                    let x = state + FpVar::constant(Fr::from(c * 7 + 3));
+                    let x2 = x.square()?;
+                    state = x2.square()? * &x;
+                }
+            }
+            Ok(state)
+        }
+    }
+
+    fn check(params: &CircuitParams, p_seed: Fr, points: &[Fr], digits: &[u8], pk: Fr) -> bool {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let vars = VerifyVars::new(cs.clone(), params, p_seed, points, digits, pk).unwrap();
+        r1cs::verify_gadget::<Fr, ToyHasher>(params, &vars).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn verify_gadget_matches_native() {
+        let params = CircuitParams { w: 16, m: 4 };
+        let p_seed = Fr::from(1234u64);
+        let secret: Vec<Fr> = (0..params.total() as u64)
+            .map(|i| Fr::from(i + 99))
+            .collect();
+        let top = vec![0u8; params.total()];
+        let pk = r1cs::public_key::<Fr, ToyHasher>(&params, p_seed, &secret, &top);
+
+        let digits = params.digits(&[0, 15, 7, 3]);
+        assert_eq!(digits.len(), params.total());
+        let points = r1cs::sign::<Fr, ToyHasher>(&params, p_seed, &secret, &digits);
+        assert_eq!(
+            r1cs::public_key::<Fr, ToyHasher>(&params, p_seed, &points, &digits),
+            pk
+        );
+        assert!(check(&params, p_seed, &points, &digits, pk));
+
+        // a tampered point, a wrong public key or a forged digit must not satisfy the circuit
+        let mut tampered = points.clone();
+        tampered[1] += Fr::from(1u64);
+        assert!(!check(&params, p_seed, &tampered, &digits, pk));
+        assert!(!check(
+            &params,
+            p_seed,
+            &points,
+            &digits,
+            pk + Fr::from(1u64)
+        ));
+        let mut forged = digits.clone();
+        forged[0] += 1;
+        assert!(!check(&params, p_seed, &points, &forged, pk));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::hasher::Hasher;
+use crate::params::{StepIndex, WotsError, SEED_SIZE, W};
+use crate::wire::RANDOMNESS_HEADER_SIZE;
+pub use crate::wire::RANDOMNESS_VERSION;
+
+/// The `w - 1` randomizers of a `p_seed`: element `j` is `H(p_seed || j + 1)[..n]`, xor'd
+/// into a ladder value before step `j`. Every generate, sign and verify under the same
+/// `p_seed` derives the same set, so callers handling many of them can compute it once and
+/// pass it to the `*_with_randomness` methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicRandomness {
+    n: usize,
+    w: usize,
+    p_seed: [u8; SEED_SIZE],
+    // `w - 1` elements of `n` bytes each
+    elements: Vec<u8>,
+}
+
+impl PublicRandomness {
+    /// Derives the randomizers of `p_seed` for values of `n` bytes with the PRF hasher `H`.
+    pub fn new<H: Hasher>(n: usize, p_seed: &[u8; SEED_SIZE]) -> Self {
+        Self::with_w::<H>(n, W, p_seed)
+    }
+
+    /// Same as `new`, for params with another Winternitz parameter (see `Params::with_w`).
+    /// Above `W`, the step `j + 1` is hashed as two big-endian bytes.
+    pub fn with_w<H: Hasher>(n: usize, w: usize, p_seed: &[u8; SEED_SIZE]) -> Self {
+        let mut elements = vec![0u8; n * (w - 1)];
+        let mut buf = vec![0u8; H::size()];
+
+        for (i, element) in elements.chunks_mut(n).enumerate() {
+            let mut hasher = H::new();
+            hasher.write(p_seed);
+            hasher.write(StepIndex::new(w, i + 1).as_bytes());
+            hasher.sum(&mut buf);
+            element.copy_from_slice(&buf[0..n]);
+        }
+
+        PublicRandomness {
+            n,
+            w,
+            p_seed: *p_seed,
+            elements,
+        }
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Winternitz parameter the randomizers are derived for.
+    pub fn w(&self) -> usize {
+        self.w
+    }
+
+    pub fn p_seed(&self) -> &[u8; SEED_SIZE] {
+        &self.p_seed
+    }
+
+    /// Number of elements, always `w - 1`.
+    pub fn len(&self) -> usize {
+        self.w - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// The randomizer xor'd in before step `j`. Panics for `j >= w - 1`.
+    pub fn element(&self, j: usize) -> &[u8] {
+        &self.elements[j * self.n..(j + 1) * self.n]
+    }
+
+    /// `version || n || log2(w) || p_seed || elements`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(RANDOMNESS_HEADER_SIZE + self.elements.len());
+        bytes.push(RANDOMNESS_VERSION);
+        bytes.push(self.n as u8);
+        bytes.push(self.w.trailing_zeros() as u8);
+        bytes.extend_from_slice(&self.p_seed);
+        bytes.extend_from_slice(&self.elements);
+        bytes
+    }
+
+    /// Parses the output of `to_bytes`. The elements are taken as given, not derived again:
+    /// a tampered set makes verification meaningless, so only load them from storage as
+    /// trusted as the code itself.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WotsError> {
+        // version 1 has no log2(w) byte
+        let (w, header) = match bytes {
+            [1, ..] => (W, RANDOMNESS_HEADER_SIZE - 1),
+            [RANDOMNESS_VERSION, _, log_w, ..] if (8..=16).contains(log_w) => {
+                (1 << log_w, RANDOMNESS_HEADER_SIZE)
+            }
+            _ => return Err(WotsError::InvalidRandomness),
+        };
+        if bytes.len() < header {
+            return Err(WotsError::InvalidRandomness);
+        }
+        let n = bytes[1] as usize;
+        let elements = &bytes[header..];
+        if n == 0 || elements.len() != n * (w - 1) {
+            return Err(WotsError::InvalidRandomness);
+        }
+        let mut p_seed = [0u8; SEED_SIZE];
+        p_seed.copy_from_slice(&bytes[header - SEED_SIZE..header]);
+        Ok(PublicRandomness {
+            n,
+            w,
+            p_seed,
+            elements: elements.to_vec(),
+        })
+    }
+
+    // matches fails with `RandomnessMismatch` unless these are the randomizers of `p_seed`
+    // for values of `n` bytes under `w`.
+    pub(crate) fn matches(&self, n: usize, w: usize, p_seed: &[u8]) -> Result<(), WotsError> {
+        if self.n != n || self.w != w || self.p_seed[..] != *p_seed {
+            return Err(WotsError::RandomnessMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hasher::{Blake2bHasher, Sha3_256Hasher};
+    use crate::keys::{ChainCacheStrategy, Key};
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::randomness::PublicRandomness;
+    use crate::security;
+
+    #[test]
+    fn public_randomness_reuse() {
+        let params = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
+        let mut key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let randomness = key.public_randomness();
+        assert_eq!(randomness.len(), 255);
+        assert_eq!(randomness.element(0).len(), 32);
+        let stored = PublicRandomness::from_bytes(&randomness.to_bytes()).unwrap();
+        assert_eq!(stored, randomness);
+        assert_eq!(
+            PublicRandomness::from_bytes(&randomness.to_bytes()[1..]),
+            Err(WotsError::InvalidRandomness)
+        );
+        // version 1, from before w was configurable, is read as w = W
+        let mut v1 = randomness.to_bytes();
+        v1.remove(2);
+        v1[0] = 1;
+        assert_eq!(PublicRandomness::from_bytes(&v1).unwrap(), randomness);
+        let wide = params
+            .clone()
+            .with_w(512)
+            .unwrap()
+            .public_randomness(&[2u8; SEED_SIZE]);
+        assert_eq!(wide.len(), 511);
+        assert_eq!(
+            PublicRandomness::from_bytes(&wide.to_bytes()).unwrap(),
+            wide
+        );
+
+        let sig = key.sign_with_randomness(b"msg", &stored).unwrap();
+        assert_eq!(sig, key.sign(b"msg").unwrap());
+        key.generate_with_randomness(ChainCacheStrategy::Sparse(16), &stored)
+            .unwrap();
+        assert_eq!(key.sign_with_randomness(b"msg", &stored).unwrap(), sig);
+        let pk = key.verifying_key().unwrap();
+        params
+            .verify_with_randomness(&stored, b"msg", &sig[1..], pk.as_bytes())
+            .unwrap();
+
+        let other = params.public_randomness(&[3u8; SEED_SIZE]);
+        assert_eq!(
+            key.sign_with_randomness(b"msg", &other),
+            Err(WotsError::RandomnessMismatch)
+        );
+        assert_eq!(
+            params.verify_with_randomness(&other, b"msg", &sig[1..], pk.as_bytes()),
+            Err(WotsError::RandomnessMismatch)
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use chacha20poly1305::aead::{AeadInPlace, KeyInit};
+use chacha20poly1305::{Tag, XChaCha20Poly1305, XNonce};
+use rand::rngs::OsRng;
+
+use crate::entropy::EntropySource;
+use crate::params::{WotsError, MAX_SEED_SIZE, SEED_SIZE};
+use crate::secret::{SecretBytes, SecretSeed};
+use crate::security::ParamsEncoding;
+pub use crate::wire::SEALED_SEED_VERSION;
+
+/// Size of the XChaCha20-Poly1305 nonce
+pub const NONCE_SIZE: usize = 24;
+
+/// Size of the Poly1305 tag
+pub const TAG_SIZE: usize = 16;
+
+const AAD_DOMAIN: &[u8] = b"W-OTS+ sealed seed";
+
+/// A seed encrypted at rest with XChaCha20-Poly1305 under a caller held 32 byte key.
+///
+/// The version and params encoding are authenticated as associated data, so a sealed seed
+/// only unseals for the params it was sealed for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealedSeed {
+    pub encoding: ParamsEncoding,
+    pub nonce: [u8; NONCE_SIZE],
+    /// encrypted seed followed by the tag
+    pub ciphertext: Vec<u8>,
+}
+
+impl SealedSeed {
+    /// Decrypts the seed, failing with `WotsError::EncodingMismatch` if it was sealed for
+    /// another encoding and with `WotsError::UnsealFailure` for a wrong key or tampered data.
+    pub fn unseal(
+        &self,
+        key: &[u8; 32],
+        encoding: &ParamsEncoding,
+    ) -> Result<SecretSeed, WotsError> {
+        if &self.encoding != encoding {
+            return Err(WotsError::EncodingMismatch);
+        }
+        let seed_size = self
+            .ciphertext
+            .len()
+            .checked_sub(TAG_SIZE)
+            .filter(|size| (SEED_SIZE..=MAX_SEED_SIZE).contains(size))
+            .ok_or(WotsError::InvalidSealedSeed)?;
+        let (ciphertext, tag) = self.ciphertext.split_at(seed_size);
+
+        let mut seed = SecretBytes::from_slice(ciphertext);
+        XChaCha20Poly1305::new(key.into())
+            .decrypt_in_place_detached(
+                XNonce::from_slice(&self.nonce),
+                &aad(encoding),
+                &mut seed,
+                Tag::from_slice(tag),
+            )
+            .map_err(|_| WotsError::UnsealFailure)?;
+        SecretSeed::from_slice(&seed)
+    }
+
+    /// Encoded as version || encoding byte || nonce || ciphertext.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 + NONCE_SIZE + self.ciphertext.len());
+        bytes.push(SEALED_SEED_VERSION);
+        bytes.push(u8::from(&self.encoding));
+        bytes.extend_from_slice(&self.nonce);
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WotsError> {
+        if bytes.len() < 2 + NONCE_SIZE || bytes[0] != SEALED_SEED_VERSION {
+            return Err(WotsError::InvalidSealedSeed);
+        }
+        let (nonce, ciphertext) = bytes[2..].split_at(NONCE_SIZE);
+        Ok(SealedSeed {
+            encoding: ParamsEncoding::try_from(bytes[1])?,
+            nonce: nonce.try_into().expect("split at NONCE_SIZE"),
+            ciphertext: ciphertext.to_vec(),
+        })
+    }
+}
+
+impl SecretSeed {
+    /// Encrypts the seed under `key` for keys of `encoding`, with a random nonce.
+    pub fn seal(&self, key: &[u8; 32], encoding: &ParamsEncoding) -> Result<SealedSeed, WotsError> {
+        let mut nonce = [0u8; NONCE_SIZE];
+        EntropySource::new(OsRng).fill(&mut nonce)?;
+
+        let mut ciphertext = SecretBytes::from_slice(self);
+        let tag = XChaCha20Poly1305::new(key.into())
+            .encrypt_in_place_detached(XNonce::from_slice(&nonce), &aad(encoding), &mut ciphertext)
+            .expect("seeds are far below the XChaCha20 length limit");
+        let mut sealed = ciphertext.to_vec();
+        sealed.extend_from_slice(&tag);
+        Ok(SealedSeed {
+            encoding: *encoding,
+            nonce,
+            ciphertext: sealed,
+        })
+    }
+}
+
+fn aad(encoding: &ParamsEncoding) -> Vec<u8> {
+    let mut aad = AAD_DOMAIN.to_vec();
+    aad.extend_from_slice(&[SEALED_SEED_VERSION, u8::from(encoding)]);
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::params::WotsError;
+    use crate::seal::SealedSeed;
+    use crate::secret::SecretSeed;
+    use crate::security::ParamsEncoding;
+
+    #[test]
+    fn seal_and_unseal() {
+        let key = [7u8; 32];
+        let seed = SecretSeed::from_slice(&[3u8; 48]).unwrap();
+        let sealed = seed.seal(&key, &ParamsEncoding::Level2).unwrap();
+        let decoded = SealedSeed::from_bytes(&sealed.to_bytes()).unwrap();
+        assert_eq!(decoded, sealed);
+        let unsealed = decoded.unseal(&key, &ParamsEncoding::Level2).unwrap();
+        assert_eq!(&unsealed[..], &seed[..]);
+
+        assert_eq!(
+            sealed.unseal(&[8u8; 32], &ParamsEncoding::Level2).err(),
+            Some(WotsError::UnsealFailure)
+        );
+        assert_eq!(
+            sealed.unseal(&key, &ParamsEncoding::Level3).err(),
+            Some(WotsError::EncodingMismatch)
+        );
+        // rebinding to other params breaks the tag
+        let mut rebound = sealed.clone();
+        rebound.encoding = ParamsEncoding::Level3;
+        assert_eq!(
+            rebound.unseal(&key, &ParamsEncoding::Level3).err(),
+            Some(WotsError::UnsealFailure)
+        );
+    }
+}
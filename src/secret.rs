@@ -0,0 +1,240 @@
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::hasher::Hasher;
+use crate::params::{WotsError, MAX_SEED_SIZE, SEED_SIZE};
+
+/// Seed a key's secret ladder starts are derived from.
+///
+/// Zeroed on drop. With the `memlock` feature it is also kept out of swap and core dumps.
+#[derive(Clone)]
+pub struct SecretSeed(SecretBytes);
+
+impl SecretSeed {
+    pub fn new(seed: [u8; SEED_SIZE]) -> Self {
+        SecretSeed(SecretBytes::from_slice(&seed))
+    }
+
+    /// Seed of `SEED_SIZE` to `MAX_SEED_SIZE` bytes, for params set up with
+    /// `Params::with_seed_size`.
+    pub fn from_slice(seed: &[u8]) -> Result<Self, WotsError> {
+        if !(SEED_SIZE..=MAX_SEED_SIZE).contains(&seed.len()) {
+            return Err(WotsError::InvalidSeedSize);
+        }
+        Ok(SecretSeed(SecretBytes::from_slice(seed)))
+    }
+}
+
+impl From<[u8; SEED_SIZE]> for SecretSeed {
+    fn from(seed: [u8; SEED_SIZE]) -> Self {
+        SecretSeed::new(seed)
+    }
+}
+
+impl Deref for SecretSeed {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0.bytes
+    }
+}
+
+impl AsRef<[u8]> for SecretSeed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0.bytes
+    }
+}
+
+impl fmt::Debug for SecretSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretSeed(..)")
+    }
+}
+
+/// Answers the PRF queries a key's secret ladder starts are derived from, without necessarily
+/// revealing the seed; HSM, TPM or secure element backends implement this to keep custody of it.
+pub trait SeedProvider<H: Hasher> {
+    /// Writes `H(seed || index)` into `out` (`H::SIZE` bytes).
+    fn prf(&self, index: u8, out: &mut [u8]) -> Result<(), WotsError>;
+}
+
+/// Software provider holding the seed in memory.
+impl<H: Hasher> SeedProvider<H> for SecretSeed {
+    fn prf(&self, index: u8, out: &mut [u8]) -> Result<(), WotsError> {
+        let mut hasher = H::new();
+        hasher.write(self);
+        hasher.write(&[index]);
+        hasher.sum(out);
+        Ok(())
+    }
+}
+
+/// Secret ladder starting points of a key (`n * total` bytes), or of a single ladder (`n` bytes)
+/// from `keys::derive_sk_chunk`.
+///
+/// Zeroed on drop. With the `memlock` feature it is also kept out of swap and core dumps.
+#[derive(Clone)]
+pub struct SecretKey(SecretBytes);
+
+impl SecretKey {
+    pub(crate) fn zeroed(len: usize) -> Self {
+        SecretKey(SecretBytes::zeroed(len))
+    }
+
+    pub(crate) fn as_mut_bytes(&mut self) -> &mut [u8] {
+        &mut self.0.bytes
+    }
+}
+
+impl Deref for SecretKey {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0.bytes
+    }
+}
+
+impl AsRef<[u8]> for SecretKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0.bytes
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey(..)")
+    }
+}
+
+// SecretBytes is a fixed size heap buffer, so secrets are never left behind by a reallocation.
+// It's locked for its whole lifetime with `memlock` and zeroed before being freed.
+pub(crate) struct SecretBytes {
+    bytes: Box<[u8]>,
+}
+
+impl SecretBytes {
+    pub(crate) fn zeroed(len: usize) -> Self {
+        let mut bytes = vec![0u8; len].into_boxed_slice();
+        lock(&mut bytes);
+        SecretBytes { bytes }
+    }
+
+    pub(crate) fn from_slice(data: &[u8]) -> Self {
+        let mut secret = SecretBytes::zeroed(data.len());
+        secret.bytes.copy_from_slice(data);
+        secret
+    }
+}
+
+impl Deref for SecretBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl DerefMut for SecretBytes {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+}
+
+impl Clone for SecretBytes {
+    fn clone(&self) -> Self {
+        SecretBytes::from_slice(&self.bytes)
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        unlock_and_zero(&mut self.bytes);
+    }
+}
+
+// lock is best effort: a low RLIMIT_MEMLOCK shouldn't stop keys from being created.
+#[cfg(feature = "memlock")]
+fn lock(bytes: &mut [u8]) {
+    if !bytes.is_empty() {
+        // SAFETY: the pointer and length describe a live allocation owned by the caller.
+        unsafe {
+            memsec::mlock(bytes.as_mut_ptr(), bytes.len());
+        }
+    }
+}
+
+#[cfg(not(feature = "memlock"))]
+fn lock(_bytes: &mut [u8]) {}
+
+#[cfg(feature = "memlock")]
+fn unlock_and_zero(bytes: &mut [u8]) {
+    if !bytes.is_empty() {
+        // SAFETY: as in `lock`; munlock zeroes the memory before unlocking it.
+        unsafe {
+            memsec::munlock(bytes.as_mut_ptr(), bytes.len());
+        }
+    }
+}
+
+#[cfg(not(feature = "memlock"))]
+fn unlock_and_zero(bytes: &mut [u8]) {
+    for b in bytes.iter_mut() {
+        // SAFETY: `b` is a valid, aligned reference; volatile keeps the write from being elided.
+        unsafe { core::ptr::write_volatile(b, 0) };
+    }
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::secret::{SecretKey, SecretSeed, SeedProvider};
+    use crate::security;
+
+    #[test]
+    fn secret_seed() {
+        let seed = SecretSeed::new([7u8; SEED_SIZE]);
+        assert_eq!(&seed[..], &[7u8; SEED_SIZE]);
+        assert_eq!(&seed.clone()[..], &seed[..]);
+        assert_eq!(format!("{:?}", seed), "SecretSeed(..)");
+
+        let mut sk = SecretKey::zeroed(64);
+        sk.as_mut_bytes()[63] = 1;
+        assert_eq!(sk.len(), 64);
+        assert_eq!(sk.clone()[63], 1);
+        assert!(!format!("{:?}", sk).contains('1'));
+    }
+
+    // Stands in for a device that never hands out its seed.
+    struct Device([u8; SEED_SIZE], bool);
+
+    impl SeedProvider<Blake2bHasher> for Device {
+        fn prf(&self, index: u8, out: &mut [u8]) -> Result<(), WotsError> {
+            if self.1 && index > 0 {
+                return Err(WotsError::SeedProviderFailure);
+            }
+            SeedProvider::<Blake2bHasher>::prf(&SecretSeed::new(self.0), index, out)
+        }
+    }
+
+    #[test]
+    fn seed_provider() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let hw_key =
+            Key::from_seed_provider(params, &Device([1u8; SEED_SIZE], false), [2u8; SEED_SIZE])
+                .unwrap();
+        assert!(hw_key.seed.is_none());
+        assert_eq!(hw_key.public_key, key.public_key);
+        assert_eq!(hw_key.sign(b"msg").unwrap(), key.sign(b"msg").unwrap());
+
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let failing = Device([1u8; SEED_SIZE], true);
+        assert_eq!(
+            Key::from_seed_provider(params, &failing, [2u8; SEED_SIZE]).err(),
+            Some(WotsError::SeedProviderFailure)
+        );
+    }
+}
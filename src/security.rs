@@ -1,9 +1,23 @@
 use std::convert::From;
+use std::fmt;
 
 use crate::hasher::{Blake2bHasher, Hasher, Sha3_224Hasher, Sha3_256Hasher};
-use crate::params::{Params, WotsError};
+use crate::key_set::PublicKeySet;
+use crate::keys::{PublicKey, PK_SIZE};
+use crate::params::{secret_key_size, signature_size, Message, Params, WotsError, SEED_SIZE};
+use crate::signature::{
+    check_p_seed, normalize_header, validate_bytes, SignatureParts, VersionPolicy,
+};
+use crate::wire::{SIGNATURE_POINTS_OFFSET, SIGNATURE_P_SEED_OFFSET};
 
-#[derive(Debug, Clone)]
+/// Predefined encodings are ordered by strength, `Level0` lowest and `Consensus` highest;
+/// `Custom` is only comparable to itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum ParamsEncoding {
     Level0,
     Level1,
@@ -13,15 +27,129 @@ pub enum ParamsEncoding {
     Custom,
 }
 
-impl From<u8> for ParamsEncoding {
-    fn from(item: u8) -> Self {
+impl ParamsEncoding {
+    /// `(n, m)` of the predefined encodings; `None` for `Custom`.
+    pub const fn values(&self) -> Option<(usize, usize)> {
+        match self {
+            ParamsEncoding::Level0 => Some((20, 24)),
+            ParamsEncoding::Level1 => Some((24, 24)),
+            ParamsEncoding::Level2 => Some((28, 24)),
+            ParamsEncoding::Level3 => Some((32, 24)),
+            ParamsEncoding::Consensus => Some((32, 32)),
+            ParamsEncoding::Custom => None,
+        }
+    }
+
+    /// Size of a full signature in bytes; `None` for `Custom`.
+    pub const fn signature_size(&self) -> Option<usize> {
+        match self.values() {
+            Some((n, m)) => Some(signature_size(n, m)),
+            None => None,
+        }
+    }
+
+    /// Size of the secret key in bytes; `None` for `Custom`.
+    pub const fn secret_key_size(&self) -> Option<usize> {
+        match self.values() {
+            Some((n, m)) => Some(secret_key_size(n, m)),
+            None => None,
+        }
+    }
+
+    /// Size of the public key in bytes; the same for every encoding.
+    pub const fn public_key_size(&self) -> usize {
+        PK_SIZE
+    }
+
+    /// Classical security estimate in bits: the size of the ladder values or of the message
+    /// digest, whichever is smaller, as forging needs only one of them to give way. `None` for
+    /// `Custom`.
+    pub const fn security_bits(&self) -> Option<usize> {
+        match self.values() {
+            Some((n, m)) if n < m => Some(8 * n),
+            Some((_, m)) => Some(8 * m),
+            None => None,
+        }
+    }
+}
+
+// Ordered like `Params::is_at_least`: both `n` and `m` must be at least as large.
+impl PartialOrd for ParamsEncoding {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        if self == other {
+            return Some(core::cmp::Ordering::Equal);
+        }
+        match (self.values()?, other.values()?) {
+            (a, b) if a.0 <= b.0 && a.1 <= b.1 => Some(core::cmp::Ordering::Less),
+            (a, b) if a.0 >= b.0 && a.1 >= b.1 => Some(core::cmp::Ordering::Greater),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) const PREDEFINED: [ParamsEncoding; 5] = [
+    ParamsEncoding::Level0,
+    ParamsEncoding::Level1,
+    ParamsEncoding::Level2,
+    ParamsEncoding::Level3,
+    ParamsEncoding::Consensus,
+];
+
+/// Picks the strongest predefined encoding whose signatures fit in `max_sig_len` bytes and
+/// that offers at least `min_bits` of security (see `ParamsEncoding::security_bits`), the
+/// smallest one among equally strong encodings. `None` if no encoding fits.
+pub fn smallest_encoding_for(max_sig_len: usize, min_bits: usize) -> Option<ParamsEncoding> {
+    PREDEFINED
+        .iter()
+        .filter_map(|encoding| {
+            let bits = encoding.security_bits()?;
+            let size = encoding.signature_size()?;
+            (size <= max_sig_len && bits >= min_bits).then_some((bits, size, encoding))
+        })
+        .min_by_key(|&(bits, size, _)| (core::cmp::Reverse(bits), size))
+        .map(|(_, _, encoding)| *encoding)
+}
+
+// fits reports whether `encoding` can be instantiated with the given hashers.
+const fn fits<PRFH: Hasher, MSGH: Hasher>(encoding: ParamsEncoding) -> bool {
+    match encoding.values() {
+        Some((n, m)) => n <= PRFH::SIZE && m <= MSGH::SIZE,
+        None => false,
+    }
+}
+
+// the hashers `verify` dispatches each encoding to must be large enough for it, and the
+// public key is a SHA3-256 digest
+const _: () = assert!(fits::<Blake2bHasher, Sha3_224Hasher>(
+    ParamsEncoding::Level0
+));
+const _: () = assert!(fits::<Blake2bHasher, Sha3_224Hasher>(
+    ParamsEncoding::Level1
+));
+const _: () = assert!(fits::<Blake2bHasher, Sha3_224Hasher>(
+    ParamsEncoding::Level2
+));
+const _: () = assert!(fits::<Blake2bHasher, Sha3_224Hasher>(
+    ParamsEncoding::Level3
+));
+const _: () = assert!(fits::<Blake2bHasher, Sha3_256Hasher>(
+    ParamsEncoding::Consensus
+));
+const _: () = assert!(PK_SIZE == Sha3_256Hasher::SIZE);
+
+/// Fails with `WotsError::UnknownEncoding` for bytes not assigned to an encoding.
+impl TryFrom<u8> for ParamsEncoding {
+    type Error = WotsError;
+
+    fn try_from(item: u8) -> Result<Self, Self::Error> {
         match item {
-            0 => ParamsEncoding::Level0,
-            1 => ParamsEncoding::Level1,
-            2 => ParamsEncoding::Level2,
-            3 => ParamsEncoding::Level3,
-            4 => ParamsEncoding::Consensus,
-            _ => ParamsEncoding::Custom,
+            0 => Ok(ParamsEncoding::Level0),
+            1 => Ok(ParamsEncoding::Level1),
+            2 => Ok(ParamsEncoding::Level2),
+            3 => Ok(ParamsEncoding::Level3),
+            4 => Ok(ParamsEncoding::Consensus),
+            5 => Ok(ParamsEncoding::Custom),
+            _ => Err(WotsError::UnknownEncoding(item)),
         }
     }
 }
@@ -39,11 +167,25 @@ impl From<&ParamsEncoding> for u8 {
     }
 }
 
+/// The lowercase name the CLI takes, e.g. `level3`.
+impl fmt::Display for ParamsEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ParamsEncoding::Level0 => "level0",
+            ParamsEncoding::Level1 => "level1",
+            ParamsEncoding::Level2 => "level2",
+            ParamsEncoding::Level3 => "level3",
+            ParamsEncoding::Consensus => "consensus",
+            ParamsEncoding::Custom => "custom",
+        })
+    }
+}
+
 impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> From<&ParamsEncoding> for Params<PRFH, MSGH> {
     fn from(item: &ParamsEncoding) -> Self {
         match item {
             ParamsEncoding::Custom => consensus_params(), // TODO
-            _ => Params::new(item.clone()).expect("instantiating level0 params should not fail"),
+            _ => Params::new(*item).expect("instantiating level0 params should not fail"),
         }
     }
 }
@@ -68,81 +210,536 @@ pub fn consensus_params<PRFH: Hasher + Clone, MSGH: Hasher + Clone>() -> Params<
     Params::new(ParamsEncoding::Consensus).expect("instantiating consensus params should not fail")
 }
 
-pub fn verify(msg: &[u8], signature: &[u8], public_key: &[u8]) -> Result<(), WotsError> {
-    if signature.is_empty() {
+pub fn verify(
+    msg: impl AsRef<[u8]>,
+    signature: impl AsRef<[u8]>,
+    public_key: impl AsRef<[u8]>,
+) -> Result<(), WotsError> {
+    verify_message(
+        Message::plain(msg.as_ref()),
+        signature.as_ref(),
+        public_key.as_ref(),
+        true,
+        None,
+    )
+}
+
+pub fn verify_no_consensus(
+    msg: impl AsRef<[u8]>,
+    signature: impl AsRef<[u8]>,
+    public_key: impl AsRef<[u8]>,
+) -> Result<(), WotsError> {
+    verify_message(
+        Message::plain(msg.as_ref()),
+        signature.as_ref(),
+        public_key.as_ref(),
+        false,
+        None,
+    )
+}
+
+/// Verifies a signature made by `Key::sign_detached_seed`, with the `p_seed` of the key
+/// that made it, e.g. as registered with its public key.
+pub fn verify_with_seed(
+    msg: impl AsRef<[u8]>,
+    p_seed: &[u8; SEED_SIZE],
+    signature: impl AsRef<[u8]>,
+    public_key: impl AsRef<[u8]>,
+) -> Result<(), WotsError> {
+    let (&encoding, points) = signature
+        .as_ref()
+        .split_first()
+        .ok_or(WotsError::InvalidSignatureSize)?;
+    let parts = SignatureParts {
+        encoding: ParamsEncoding::try_from(encoding)?,
+        p_seed: *p_seed,
+        points: points.to_vec(),
+    };
+    verify(msg.as_ref(), parts.assemble(), public_key.as_ref())
+}
+
+/// Verifies a signature made by `Key::sign_with_context` with the same `context`.
+pub fn verify_with_context(
+    msg: impl AsRef<[u8]>,
+    context: impl AsRef<[u8]>,
+    signature: impl AsRef<[u8]>,
+    public_key: impl AsRef<[u8]>,
+) -> Result<(), WotsError> {
+    verify_message(
+        Message::with_context(msg.as_ref(), context.as_ref())?,
+        signature.as_ref(),
+        public_key.as_ref(),
+        true,
+        None,
+    )
+}
+
+/// Same as `verify`, but fails with `WotsError::BudgetExceeded`, before walking any ladder,
+/// if the params of the signature's encoding would take more than `max_hashes` to verify it.
+/// For gateways verifying signatures from untrusted peers; see `Params::verify_cost`.
+pub fn verify_with_budget(
+    msg: impl AsRef<[u8]>,
+    signature: impl AsRef<[u8]>,
+    public_key: impl AsRef<[u8]>,
+    max_hashes: usize,
+) -> Result<(), WotsError> {
+    verify_message(
+        Message::plain(msg.as_ref()),
+        signature.as_ref(),
+        public_key.as_ref(),
+        true,
+        Some(max_hashes),
+    )
+}
+
+/// Verifies a full `signature` with `params`, whatever its encoding byte says, for
+/// deployments that pin their parameter set and hashers and treat the header as advisory.
+/// Where `verify` trusts the byte to pick the params, here a forged header can't make the
+/// signature verify under weaker params or other hashers; it is not even checked against
+/// `params`.
+pub fn verify_detached_params<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    params: &Params<PRFH, MSGH>,
+    msg: impl AsRef<[u8]>,
+    signature: impl AsRef<[u8]>,
+    public_key: impl AsRef<[u8]>,
+) -> Result<(), WotsError> {
+    let (signature, public_key) = (signature.as_ref(), public_key.as_ref());
+    if public_key.len() != PK_SIZE {
+        return Err(WotsError::InvalidPublicKeySize);
+    }
+    if signature.len() != params.signature_size() {
         return Err(WotsError::InvalidSignatureSize);
     }
-    match ParamsEncoding::from(signature[0]) {
-        ParamsEncoding::Level0 => level_0_params::<Blake2bHasher, Sha3_224Hasher>().verify(
-            msg,
-            &signature[1..],
-            public_key,
+    check_p_seed(&signature[SIGNATURE_P_SEED_OFFSET..SIGNATURE_POINTS_OFFSET])?;
+    params.verify_message(
+        Message::plain(msg.as_ref()),
+        &signature[SIGNATURE_P_SEED_OFFSET..],
+        public_key,
+    )
+}
+
+/// Recomputes the public key `signature` (including its encoding byte) decodes to for `msg`,
+/// dispatching on the encoding like `verify`. A signature is valid exactly when the recovered
+/// key is the signer's, so protocols that identify signers by public key can verify by
+/// looking the recovered key up in their registry instead of being told which key to check.
+/// Any signature of the right size decodes to some key: never accept one whose key is not
+/// registered.
+pub fn recover_public_key(
+    msg: impl AsRef<[u8]>,
+    signature: impl AsRef<[u8]>,
+) -> Result<PublicKey, WotsError> {
+    let signature = signature.as_ref();
+    let encoding = match signature.first() {
+        Some(&byte) => ParamsEncoding::try_from(byte)?,
+        None => return Err(WotsError::InvalidSignatureSize),
+    };
+    match encoding {
+        ParamsEncoding::Consensus => recover_with(
+            &consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+            msg.as_ref(),
+            signature,
         ),
-        ParamsEncoding::Level1 => level_1_params::<Blake2bHasher, Sha3_224Hasher>().verify(
-            msg,
-            &signature[1..],
-            public_key,
+        ParamsEncoding::Custom => Err(WotsError::InvalidParamsEncodingType),
+        encoding => recover_with(
+            &Params::<Blake2bHasher, Sha3_224Hasher>::from(&encoding),
+            msg.as_ref(),
+            signature,
         ),
-        ParamsEncoding::Level2 => level_2_params::<Blake2bHasher, Sha3_224Hasher>().verify(
-            msg,
-            &signature[1..],
-            public_key,
+    }
+}
+
+/// Verifies `signature` by recovering its public key (see `recover_public_key`) and looking
+/// it up in `set`, returning the key that signed. Fails with `WotsError::InvalidSignature`
+/// when the recovered key is not in the set. For consensus layers tracking the current
+/// one-time keys of their validators, where the signer isn't known ahead of verification.
+pub fn verify_against_set(
+    msg: impl AsRef<[u8]>,
+    signature: impl AsRef<[u8]>,
+    set: &PublicKeySet,
+) -> Result<PublicKey, WotsError> {
+    let public_key = recover_public_key(msg, signature)?;
+    if !set.contains(&public_key) {
+        return Err(WotsError::InvalidSignature);
+    }
+    Ok(public_key)
+}
+
+fn recover_with<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    params: &Params<PRFH, MSGH>,
+    msg: &[u8],
+    signature: &[u8],
+) -> Result<PublicKey, WotsError> {
+    validate_bytes(signature, params)?;
+    let mut pk = [0u8; PK_SIZE];
+    params.decode_into(msg, &signature[1..], &mut pk)?;
+    Ok(PublicKey::from(pk))
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "wots.dispatch",
+        level = "debug",
+        skip_all,
+        fields(
+            encoding = ?signature.first(),
+            allow_consensus = allow_consensus,
+            failure = tracing::field::Empty,
         ),
-        ParamsEncoding::Level3 => level_3_params::<Blake2bHasher, Sha3_224Hasher>().verify(
-            msg,
-            &signature[1..],
+        err,
+    )
+)]
+fn verify_message(
+    message: Message,
+    signature: &[u8],
+    public_key: &[u8],
+    allow_consensus: bool,
+    budget: Option<usize>,
+) -> Result<(), WotsError> {
+    let result = dispatch(message, signature, public_key, allow_consensus, budget);
+    #[cfg(feature = "tracing")]
+    crate::params::record_failure(&result);
+    result
+}
+
+fn dispatch(
+    message: Message,
+    signature: &[u8],
+    public_key: &[u8],
+    allow_consensus: bool,
+    budget: Option<usize>,
+) -> Result<(), WotsError> {
+    normalize_header(signature, VersionPolicy::Reject)?;
+    match ParamsEncoding::try_from(signature[0])? {
+        ParamsEncoding::Consensus if allow_consensus => verify_with(
+            &consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+            message,
+            signature,
             public_key,
+            budget,
         ),
-        ParamsEncoding::Consensus => consensus_params::<Blake2bHasher, Sha3_256Hasher>().verify(
-            msg,
-            &signature[1..],
+        ParamsEncoding::Consensus | ParamsEncoding::Custom => {
+            Err(WotsError::InvalidParamsEncodingType)
+        }
+        encoding => verify_with(
+            &Params::<Blake2bHasher, Sha3_224Hasher>::from(&encoding),
+            message,
+            signature,
             public_key,
+            budget,
         ),
-        _ => Err(WotsError::InvalidParamsEncodingType),
     }
 }
 
-/// Disallows verification of signatures signed using consensus parameters.
-pub fn verify_no_consensus(
+/// Encodings a `Verifier` accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyPolicy {
+    pub allowed: Vec<ParamsEncoding>,
+}
+
+impl VerifyPolicy {
+    /// Every predefined encoding, as `verify` accepts.
+    pub fn all() -> Self {
+        VerifyPolicy {
+            allowed: PREDEFINED.to_vec(),
+        }
+    }
+
+    /// Every predefined encoding but `Consensus`, as `verify_no_consensus` accepts.
+    pub fn no_consensus() -> Self {
+        VerifyPolicy {
+            allowed: PREDEFINED[..4].to_vec(),
+        }
+    }
+}
+
+impl Default for VerifyPolicy {
+    fn default() -> Self {
+        VerifyPolicy::all()
+    }
+}
+
+/// Verifier for hot loops: the params of every encoding allowed by its policy are built once,
+/// instead of on every call as `verify` does.
+#[derive(Debug, Clone)]
+pub struct Verifier {
+    // indexed by encoding byte
+    levels: [Option<Params<Blake2bHasher, Sha3_224Hasher>>; 4],
+    consensus: Option<Params<Blake2bHasher, Sha3_256Hasher>>,
+    versions: VersionPolicy,
+}
+
+impl Verifier {
+    /// `Custom` in the policy is ignored, as it can't be dispatched to by encoding byte.
+    pub fn new(policy: VerifyPolicy) -> Self {
+        let mut verifier = Verifier {
+            levels: [None, None, None, None],
+            consensus: None,
+            versions: VersionPolicy::Reject,
+        };
+        for encoding in policy.allowed {
+            match encoding {
+                ParamsEncoding::Custom => {}
+                ParamsEncoding::Consensus => verifier.consensus = Some(consensus_params()),
+                encoding => {
+                    let i = u8::from(&encoding) as usize;
+                    verifier.levels[i] = Some(Params::from(&encoding));
+                }
+            }
+        }
+        verifier
+    }
+
+    /// Sets how signatures of a newer header version are treated; see
+    /// `signature::normalize_header`. Unknown versions are rejected by default.
+    pub fn with_version_policy(mut self, versions: VersionPolicy) -> Self {
+        self.versions = versions;
+        self
+    }
+
+    /// Same as `security::verify`, restricted to the policy's encodings.
+    pub fn verify(
+        &self,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+    ) -> Result<(), WotsError> {
+        self.verify_message(
+            Message::plain(msg.as_ref()),
+            signature.as_ref(),
+            public_key.as_ref(),
+        )
+    }
+
+    /// Same as `security::verify_with_context`, restricted to the policy's encodings.
+    pub fn verify_with_context(
+        &self,
+        msg: impl AsRef<[u8]>,
+        context: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+        public_key: impl AsRef<[u8]>,
+    ) -> Result<(), WotsError> {
+        self.verify_message(
+            Message::with_context(msg.as_ref(), context.as_ref())?,
+            signature.as_ref(),
+            public_key.as_ref(),
+        )
+    }
+
+    fn verify_message(
+        &self,
+        message: Message,
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<(), WotsError> {
+        let signature = &*normalize_header(signature, self.versions)?;
+        let byte = signature[0];
+        if let (ParamsEncoding::Consensus, Some(params)) =
+            (ParamsEncoding::try_from(byte)?, &self.consensus)
+        {
+            return verify_with(params, message, signature, public_key, None);
+        }
+        match self.levels.get(byte as usize) {
+            Some(Some(params)) => verify_with(params, message, signature, public_key, None),
+            _ => Err(WotsError::InvalidParamsEncodingType),
+        }
+    }
+}
+
+// verify_full verifies a full signature, encoding byte included, with `params`.
+pub(crate) fn verify_full<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    params: &Params<PRFH, MSGH>,
     msg: &[u8],
     signature: &[u8],
     public_key: &[u8],
 ) -> Result<(), WotsError> {
+    verify_with(params, Message::plain(msg), signature, public_key, None)
+}
+
+// verify_with validates the structure of `signature` (including its encoding byte) before
+// verifying it with `params`, within `budget` hashes if one is set.
+fn verify_with<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    params: &Params<PRFH, MSGH>,
+    message: Message,
+    signature: &[u8],
+    public_key: &[u8],
+    budget: Option<usize>,
+) -> Result<(), WotsError> {
+    if public_key.len() != PK_SIZE {
+        return Err(WotsError::InvalidPublicKeySize);
+    }
+    validate_bytes(signature, params)?;
+    if budget.is_some_and(|max| params.message_cost(message) > max) {
+        return Err(WotsError::BudgetExceeded);
+    }
+    params.verify_message(message, &signature[1..], public_key)
+}
+
+/// Fields of a decoded signature, for debugging interop failures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureInfo {
+    pub encoding: ParamsEncoding,
+
+    /// security parameter; size of each ladder point (in bytes)
+    pub n: usize,
+
+    /// message digest size (in bytes)
+    pub m: usize,
+
+    /// number of ladders, including the checksum ladders
+    pub total: usize,
+
+    pub p_seed: Vec<u8>,
+
+    /// ladder points, one per ladder
+    pub points: Vec<Vec<u8>>,
+
+    /// digit signed by each ladder; only known when inspecting against a message
+    pub digits: Option<Vec<u16>>,
+
+    /// public key the signature decodes to; only known when inspecting against a message
+    pub public_key: Option<Vec<u8>>,
+}
+
+/// Decodes the header, `p_seed` and ladder points of `signature` (including its encoding
+/// byte). Given the signed message, also reports the digit of every ladder and the public key
+/// the signature decodes to.
+pub fn inspect(signature: &[u8], maybe_msg: Option<&[u8]>) -> Result<SignatureInfo, WotsError> {
     if signature.is_empty() {
         return Err(WotsError::InvalidSignatureSize);
     }
-    match ParamsEncoding::from(signature[0]) {
-        ParamsEncoding::Level0 => level_0_params::<Blake2bHasher, Sha3_224Hasher>().verify(
-            msg,
-            &signature[1..],
-            public_key,
-        ),
-        ParamsEncoding::Level1 => level_1_params::<Blake2bHasher, Sha3_224Hasher>().verify(
-            msg,
-            &signature[1..],
-            public_key,
-        ),
-        ParamsEncoding::Level2 => level_2_params::<Blake2bHasher, Sha3_224Hasher>().verify(
-            msg,
-            &signature[1..],
-            public_key,
+    match ParamsEncoding::try_from(signature[0])? {
+        ParamsEncoding::Consensus => inspect_with(
+            &consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+            signature,
+            maybe_msg,
         ),
-        ParamsEncoding::Level3 => level_3_params::<Blake2bHasher, Sha3_224Hasher>().verify(
-            msg,
-            &signature[1..],
-            public_key,
+        ParamsEncoding::Custom => Err(WotsError::InvalidParamsEncodingType),
+        encoding => inspect_with(
+            &Params::<Blake2bHasher, Sha3_224Hasher>::from(&encoding),
+            signature,
+            maybe_msg,
         ),
-        _ => Err(WotsError::InvalidParamsEncodingType),
     }
 }
 
+fn inspect_with<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    params: &Params<PRFH, MSGH>,
+    signature: &[u8],
+    maybe_msg: Option<&[u8]>,
+) -> Result<SignatureInfo, WotsError> {
+    if signature.len() != params.signature_size() {
+        return Err(WotsError::InvalidSignatureSize);
+    }
+    let (digits, public_key) = match maybe_msg {
+        Some(msg) => (
+            Some(params.msg_hash_and_compute_checksum(msg)),
+            Some(params.decode(msg, &signature[1..])?),
+        ),
+        None => (None, None),
+    };
+
+    Ok(SignatureInfo {
+        encoding: params.encoding,
+        n: params.n,
+        m: params.m,
+        total: params.total,
+        p_seed: signature[1..1 + SEED_SIZE].to_vec(),
+        points: signature[1 + SEED_SIZE..]
+            .chunks(params.n)
+            .map(|point| point.to_vec())
+            .collect(),
+        digits,
+        public_key,
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::hasher::{Blake2bHasher, Sha3_224Hasher, Sha3_256Hasher};
-    use crate::keys::Key;
-    use crate::params::{MAX_MSG_SIZE, SEED_SIZE};
+    use crate::hasher::{Blake2bHasher, Hasher, Sha3_224Hasher, Sha3_256Hasher};
+    use crate::keys::{Key, PK_SIZE};
+    use crate::params::{Params, WotsError, MAX_MSG_SIZE, SEED_SIZE, W};
     use crate::security;
-    use crate::security::{verify, verify_no_consensus};
+    use crate::security::{verify, verify_no_consensus, ParamsEncoding, Verifier, VerifyPolicy};
+    use proptest::prelude::*;
+
+    #[test]
+    fn verifier() {
+        let level2 = Key::from_seed(
+            security::level_2_params::<Blake2bHasher, Sha3_224Hasher>(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let consensus = Key::from_seed(
+            security::consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let level2_sig = level2.sign(b"msg").unwrap();
+        let consensus_sig = consensus.sign(b"msg").unwrap();
+
+        let all = Verifier::new(VerifyPolicy::default());
+        all.verify(b"msg", &level2_sig, &level2.public_key).unwrap();
+        all.verify(b"msg", &consensus_sig, &consensus.public_key)
+            .unwrap();
+        assert_eq!(
+            all.verify(b"msh", &level2_sig, &level2.public_key),
+            Err(WotsError::InvalidSignature)
+        );
+
+        let no_consensus = Verifier::new(VerifyPolicy::no_consensus());
+        no_consensus
+            .verify(b"msg", &level2_sig, &level2.public_key)
+            .unwrap();
+        assert_eq!(
+            no_consensus.verify(b"msg", &consensus_sig, &consensus.public_key),
+            Err(WotsError::InvalidParamsEncodingType)
+        );
+
+        let only_level0 = Verifier::new(VerifyPolicy {
+            allowed: vec![ParamsEncoding::Level0, ParamsEncoding::Custom],
+        });
+        assert_eq!(
+            only_level0.verify(b"msg", &level2_sig, &level2.public_key),
+            Err(WotsError::InvalidParamsEncodingType)
+        );
+        let mut custom_sig = level2_sig.clone();
+        custom_sig[0] = 5;
+        assert_eq!(
+            only_level0.verify(b"msg", &custom_sig, &level2.public_key),
+            Err(WotsError::InvalidParamsEncodingType)
+        );
+    }
+
+    #[test]
+    fn smallest_encoding_for() {
+        let pick = security::smallest_encoding_for;
+        let size = |encoding: ParamsEncoding| encoding.signature_size().unwrap();
+
+        assert_eq!(pick(usize::MAX, 0), Some(ParamsEncoding::Consensus));
+        // Level3 fits as well, but its 192 bit digest makes it weaker than Consensus
+        assert_eq!(
+            pick(size(ParamsEncoding::Consensus), 200),
+            Some(ParamsEncoding::Consensus)
+        );
+        assert_eq!(pick(size(ParamsEncoding::Consensus) - 1, 200), None);
+        assert_eq!(ParamsEncoding::Level3.security_bits(), Some(192));
+        // Level1 to Level3 are all bound by the digest: the smallest one wins
+        assert_eq!(
+            pick(size(ParamsEncoding::Consensus) - 1, 0),
+            Some(ParamsEncoding::Level1)
+        );
+        assert_eq!(
+            pick(size(ParamsEncoding::Level0), 0),
+            Some(ParamsEncoding::Level0)
+        );
+        assert_eq!(pick(size(ParamsEncoding::Level0) - 1, 0), None);
+        assert_eq!(pick(size(ParamsEncoding::Level1), 224), None);
+        assert_eq!(pick(usize::MAX, 257), None);
+        assert_eq!(ParamsEncoding::Custom.security_bits(), None);
+    }
 
     #[test]
     fn params_test() {
@@ -167,6 +764,32 @@ mod tests {
         assert_eq!(params.m, 32);
     }
 
+    #[test]
+    fn encoding_sizes_test() {
+        const LEVEL0_SIG_SIZE: usize = match ParamsEncoding::Level0.signature_size() {
+            Some(size) => size,
+            None => 0,
+        };
+        let _buf = [0u8; LEVEL0_SIG_SIZE];
+
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        assert_eq!(LEVEL0_SIG_SIZE, params.signature_size());
+        let params = security::level_3_params::<Blake2bHasher, Sha3_224Hasher>();
+        assert_eq!(
+            ParamsEncoding::Level3.signature_size(),
+            Some(params.signature_size())
+        );
+        let params = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
+        assert_eq!(
+            ParamsEncoding::Consensus.secret_key_size(),
+            Some(params.secret_key_size())
+        );
+        assert_eq!(ParamsEncoding::Consensus.public_key_size(), PK_SIZE);
+
+        assert_eq!(ParamsEncoding::Custom.signature_size(), None);
+        assert_eq!(ParamsEncoding::Custom.secret_key_size(), None);
+    }
+
     #[test]
     fn verify_consensus_params_should_fail() {
         let params = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
@@ -197,6 +820,41 @@ mod tests {
         verify(&msg, &res, &key.public_key).unwrap();
     }
 
+    #[test]
+    fn verify_with_budget() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let msg = b"gateway";
+        let signature = key.sign(msg).unwrap();
+        let cost = params.verify_cost(msg);
+        assert!(cost < params.total * (W - 1) + W);
+
+        security::verify_with_budget(msg, &signature, &key.public_key, cost).unwrap();
+        params
+            .verify_with_budget(msg, &signature[1..], &key.public_key, cost)
+            .unwrap();
+        assert_eq!(
+            security::verify_with_budget(msg, &signature, &key.public_key, cost - 1),
+            Err(WotsError::BudgetExceeded)
+        );
+        assert_eq!(
+            params.verify_with_budget(msg, &signature[1..], &key.public_key, 0),
+            Err(WotsError::BudgetExceeded)
+        );
+        // a consensus header implies more work than a level 0 budget allows
+        let consensus = Key::<Blake2bHasher, Sha3_256Hasher>::from_seed(
+            security::consensus_params(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let signature = consensus.sign(msg).unwrap();
+        assert_eq!(
+            security::verify_with_budget(msg, &signature, &consensus.public_key, cost),
+            Err(WotsError::BudgetExceeded)
+        );
+    }
+
     #[test]
     fn verify_test_generate() {
         let params = security::consensus_params();
@@ -221,4 +879,165 @@ mod tests {
         let res = verify_no_consensus(&msg, &sig, &key.public_key);
         assert!(res.is_err());
     }
+
+    // check_round_trip signs `msg` with a key derived from the seeds, then checks the
+    // signature verifies, that mutating any single byte of it fails, and that decoding is
+    // deterministic.
+    fn check_round_trip<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        params: Params<PRFH, MSGH>,
+        seed: [u8; SEED_SIZE],
+        p_seed: [u8; SEED_SIZE],
+        msg: &[u8],
+        idx: usize,
+        flip: u8,
+    ) -> Result<(), TestCaseError> {
+        let key = Key::from_seed(params.clone(), seed, p_seed).unwrap();
+        let signature = key.sign(msg).unwrap();
+        prop_assert!(verify(msg, &signature, &key.public_key).is_ok());
+
+        let decoded = params.decode(msg, &signature[1..]).unwrap();
+        prop_assert_eq!(&decoded, &key.public_key);
+        prop_assert_eq!(params.decode(msg, &signature[1..]).unwrap(), decoded);
+
+        let mut mutated = signature.clone();
+        mutated[idx % signature.len()] ^= flip;
+        prop_assert!(verify(msg, &mutated, &key.public_key).is_err());
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn sign_verify_round_trip(
+            encoding in 0u8..5,
+            seed in any::<[u8; SEED_SIZE]>(),
+            p_seed in any::<[u8; SEED_SIZE]>(),
+            msg in proptest::collection::vec(any::<u8>(), 0..=MAX_MSG_SIZE),
+            idx in any::<usize>(),
+            flip in 1u8..,
+        ) {
+            match ParamsEncoding::try_from(encoding).unwrap() {
+                ParamsEncoding::Consensus => check_round_trip(
+                    security::consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+                    seed, p_seed, &msg, idx, flip,
+                )?,
+                encoding => check_round_trip(
+                    Params::<Blake2bHasher, Sha3_224Hasher>::new(encoding).unwrap(),
+                    seed, p_seed, &msg, idx, flip,
+                )?,
+            }
+        }
+    }
+
+    #[test]
+    fn verify_detached_params() {
+        let params = security::level_1_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let signature = key.sign(b"msg").unwrap();
+        security::verify_detached_params(&params, b"msg", &signature, &key.public_key).unwrap();
+
+        // the header is advisory: a wrong one neither helps nor hurts
+        let mut relabeled = signature.clone();
+        relabeled[0] = u8::from(&ParamsEncoding::Level3);
+        assert_eq!(
+            verify(b"msg", &relabeled, &key.public_key),
+            Err(WotsError::InvalidSignatureSize)
+        );
+        security::verify_detached_params(&params, b"msg", &relabeled, &key.public_key).unwrap();
+
+        let other = Params::<Blake2bHasher, Sha3_256Hasher>::new(ParamsEncoding::Level1).unwrap();
+        assert_eq!(
+            security::verify_detached_params(&other, b"msg", &signature, &key.public_key),
+            Err(WotsError::InvalidSignature)
+        );
+        assert_eq!(
+            security::verify_detached_params(
+                &security::level_0_params::<Blake2bHasher, Sha3_224Hasher>(),
+                b"msg",
+                &signature,
+                &key.public_key
+            ),
+            Err(WotsError::InvalidSignatureSize)
+        );
+        assert_eq!(
+            security::verify_detached_params(&params, b"msg", &signature, &key.public_key[1..]),
+            Err(WotsError::InvalidPublicKeySize)
+        );
+        assert_eq!(
+            security::verify_detached_params(&params, b"msh", &signature, &key.public_key),
+            Err(WotsError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn inspect_test() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let msg = b"XX NETWORK";
+        let signature = key.sign(msg).unwrap();
+
+        let info = security::inspect(&signature, None).unwrap();
+        assert_eq!(info.encoding, ParamsEncoding::Level0);
+        assert_eq!((info.n, info.m, info.total), (20, 24, 26));
+        assert_eq!(info.p_seed, key.p_seed);
+        assert_eq!(info.points.len(), info.total);
+        assert_eq!(info.points[0], signature[1 + SEED_SIZE..1 + SEED_SIZE + 20]);
+        assert_eq!(info.digits, None);
+
+        let info = security::inspect(&signature, Some(msg)).unwrap();
+        assert_eq!(info.digits, Some(params.msg_hash_and_compute_checksum(msg)));
+        assert_eq!(info.public_key, Some(key.public_key.clone()));
+
+        assert!(security::inspect(&signature[1..], None).is_err());
+        assert!(security::inspect(&[], None).is_err());
+    }
+
+    #[test]
+    fn verify_with_context_test() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let msg = b"XX NETWORK";
+        let signature = key.sign_with_context(msg, b"consensus").unwrap();
+
+        security::verify_with_context(msg, b"consensus", &signature, &key.public_key).unwrap();
+        for context in [&b"gossip"[..], &b""[..]] {
+            assert!(
+                security::verify_with_context(msg, context, &signature, &key.public_key).is_err()
+            );
+        }
+        assert!(verify(msg, &signature, &key.public_key).is_err());
+
+        // an empty context still differs from no context
+        let plain = Key::from_seed(key.params().clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE])
+            .unwrap()
+            .sign(msg)
+            .unwrap();
+        assert!(security::verify_with_context(msg, b"", &plain, &key.public_key).is_err());
+
+        assert_eq!(
+            key.sign_with_context(msg, [0u8; 256]).err(),
+            Some(WotsError::InvalidContextSize)
+        );
+    }
+
+    #[test]
+    fn verify_with_seed_test() {
+        let params = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
+        let key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let msg = b"XX NETWORK";
+        let signature = key.sign_detached_seed(msg).unwrap();
+        assert_eq!(signature.len(), key.signature_size() - SEED_SIZE);
+
+        security::verify_with_seed(msg, &key.p_seed, &signature, &key.public_key).unwrap();
+        assert_eq!(
+            security::verify_with_seed(msg, &[3u8; SEED_SIZE], &signature, &key.public_key),
+            Err(WotsError::InvalidSignature)
+        );
+        assert_eq!(
+            security::verify_with_seed(msg, &key.p_seed, [], &key.public_key),
+            Err(WotsError::InvalidSignatureSize)
+        );
+        assert!(verify(msg, &signature, &key.public_key).is_err());
+    }
 }
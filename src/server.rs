@@ -0,0 +1,585 @@
+// handlers return `tonic::Status` as tonic services do, large as it is
+#![allow(clippy::result_large_err)]
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use tonic::body::BoxBody;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{http, Arc, Body, BoxFuture, Context, Poll, Service, StdError};
+use tonic::server::{Grpc, NamedService};
+use tonic::{Request, Response, Status};
+
+use crate::params::VerifyFailure;
+use crate::security::{self, Verifier, VerifyPolicy};
+
+/// Default limit on the items of a `BatchVerify` request
+pub const DEFAULT_MAX_BATCH: usize = 128;
+
+/// Request of `Verify`, and item of `BatchVerify`.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct VerifyRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub message: Vec<u8>,
+    /// full signature, encoding byte included
+    #[prost(bytes = "vec", tag = "2")]
+    pub signature: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub public_key: Vec<u8>,
+    /// the context of `Key::sign_with_context`, if the signature was made with one
+    #[prost(bytes = "vec", optional, tag = "4")]
+    pub context: Option<Vec<u8>>,
+}
+
+/// Outcome of verifying one signature.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct VerifyResponse {
+    #[prost(bool, tag = "1")]
+    pub valid: bool,
+    /// `WotsError::code` of the failure; 0 when valid
+    #[prost(uint32, tag = "2")]
+    pub error_code: u32,
+    #[prost(string, tag = "3")]
+    pub error: String,
+    /// `WotsError::verify_failure` of the failure; unspecified when valid or neither kind
+    #[prost(enumeration = "Failure", tag = "4")]
+    pub failure: i32,
+}
+
+/// `params::VerifyFailure` on the wire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Failure {
+    Unspecified = 0,
+    Malformed = 1,
+    Invalid = 2,
+}
+
+impl From<Option<VerifyFailure>> for Failure {
+    fn from(failure: Option<VerifyFailure>) -> Self {
+        match failure {
+            None => Failure::Unspecified,
+            Some(VerifyFailure::Malformed) => Failure::Malformed,
+            Some(VerifyFailure::Invalid) => Failure::Invalid,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchVerifyRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub items: Vec<VerifyRequest>,
+}
+
+/// One result per item, in the order of the request.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchVerifyResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub results: Vec<VerifyResponse>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct InspectRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    pub signature: Vec<u8>,
+    #[prost(bytes = "vec", optional, tag = "2")]
+    pub message: Option<Vec<u8>>,
+}
+
+/// `security::SignatureInfo` on the wire.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct InspectResponse {
+    /// encoding byte
+    #[prost(uint32, tag = "1")]
+    pub encoding: u32,
+    #[prost(uint32, tag = "2")]
+    pub n: u32,
+    #[prost(uint32, tag = "3")]
+    pub m: u32,
+    #[prost(uint32, tag = "4")]
+    pub total: u32,
+    #[prost(bytes = "vec", tag = "5")]
+    pub p_seed: Vec<u8>,
+    #[prost(bytes = "vec", repeated, tag = "6")]
+    pub points: Vec<Vec<u8>>,
+    /// digit of every ladder; empty unless inspected against a message
+    #[prost(uint32, repeated, tag = "7")]
+    pub digits: Vec<u32>,
+    /// only set when inspected against a message
+    #[prost(bytes = "vec", optional, tag = "8")]
+    pub public_key: Option<Vec<u8>>,
+}
+
+/// The `wots.v1.Verification` service of `proto/wots.proto`, for running verification as a
+/// sidecar. Signatures are verified with a `Verifier`, so only the encodings of its policy
+/// are accepted; invalid signatures are reported in the response rather than as a gRPC error.
+/// Requests are handled on tokio's blocking pool, so walking the ladders never stalls the
+/// runtime's workers; `max_batch` bounds how long one request holds a blocking thread.
+#[derive(Debug, Clone)]
+pub struct VerificationService {
+    verifier: Arc<Verifier>,
+    max_batch: usize,
+}
+
+impl VerificationService {
+    pub fn new(policy: VerifyPolicy) -> Self {
+        VerificationService {
+            verifier: Arc::new(Verifier::new(policy)),
+            max_batch: DEFAULT_MAX_BATCH,
+        }
+    }
+
+    /// Sets the most items a `BatchVerify` request may have; larger batches are rejected with
+    /// `INVALID_ARGUMENT` before any of them is verified.
+    pub fn with_max_batch(mut self, max_batch: usize) -> Self {
+        self.max_batch = max_batch;
+        self
+    }
+
+    pub fn verify(&self, request: &VerifyRequest) -> VerifyResponse {
+        let result = match &request.context {
+            Some(context) => self.verifier.verify_with_context(
+                &request.message,
+                context,
+                &request.signature,
+                &request.public_key,
+            ),
+            None => self
+                .verifier
+                .verify(&request.message, &request.signature, &request.public_key),
+        };
+        match result {
+            Ok(()) => VerifyResponse {
+                valid: true,
+                error_code: 0,
+                error: String::new(),
+                failure: Failure::Unspecified.into(),
+            },
+            Err(err) => VerifyResponse {
+                valid: false,
+                error_code: err.code(),
+                error: err.to_string(),
+                failure: Failure::from(err.verify_failure()).into(),
+            },
+        }
+    }
+
+    pub fn batch_verify(
+        &self,
+        request: &BatchVerifyRequest,
+    ) -> Result<BatchVerifyResponse, Status> {
+        if request.items.len() > self.max_batch {
+            return Err(Status::invalid_argument(format!(
+                "batch of {} signatures exceeds the limit of {}",
+                request.items.len(),
+                self.max_batch
+            )));
+        }
+        Ok(BatchVerifyResponse {
+            results: request.items.iter().map(|item| self.verify(item)).collect(),
+        })
+    }
+
+    /// Same as `security::inspect`; not restricted to the policy's encodings.
+    pub fn inspect_signature(&self, request: &InspectRequest) -> Result<InspectResponse, Status> {
+        let info = security::inspect(&request.signature, request.message.as_deref())
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        Ok(InspectResponse {
+            encoding: u8::from(&info.encoding).into(),
+            n: info.n as u32,
+            m: info.m as u32,
+            total: info.total as u32,
+            p_seed: info.p_seed,
+            points: info.points,
+            digits: info
+                .digits
+                .unwrap_or_default()
+                .into_iter()
+                .map(u32::from)
+                .collect(),
+            public_key: info.public_key,
+        })
+    }
+}
+
+/// Serves `service` on `addr` until the server fails. Use `tonic::transport::Server` directly
+/// to add TLS, other services or graceful shutdown.
+pub async fn serve(
+    addr: SocketAddr,
+    service: VerificationService,
+) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(service)
+        .serve(addr)
+        .await
+}
+
+impl NamedService for VerificationService {
+    const NAME: &'static str = "wots.v1.Verification";
+}
+
+impl<B> Service<http::Request<B>> for VerificationService
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let service = self.clone();
+        match req.uri().path() {
+            "/wots.v1.Verification/Verify" => unary(req, service, |s, r| Ok(s.verify(&r))),
+            "/wots.v1.Verification/BatchVerify" => unary(req, service, |s, r| s.batch_verify(&r)),
+            "/wots.v1.Verification/InspectSignature" => {
+                unary(req, service, |s, r| s.inspect_signature(&r))
+            }
+            _ => Box::pin(async { Ok(Status::unimplemented("").into_http()) }),
+        }
+    }
+}
+
+// Unary adapts a handler of `VerificationService` to the unary services `Grpc` calls.
+struct Unary<F>(VerificationService, F);
+
+impl<M1, M2, F> Service<Request<M1>> for Unary<F>
+where
+    M1: Send + 'static,
+    M2: Send + 'static,
+    F: Fn(&VerificationService, M1) -> Result<M2, Status> + Clone + Send + 'static,
+{
+    type Response = Response<M2>;
+    type Error = Status;
+    type Future = BoxFuture<Response<M2>, Status>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    // the handler runs on the blocking pool: a batch walks up to `max_batch` sets of ladders
+    fn call(&mut self, request: Request<M1>) -> Self::Future {
+        let (service, handler) = (self.0.clone(), self.1.clone());
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || handler(&service, request.into_inner()))
+                .await
+                .map_err(|err| Status::internal(err.to_string()))?
+                .map(Response::new)
+        })
+    }
+}
+
+// unary decodes the request, runs `handler` on it and encodes its response.
+fn unary<B, M1, M2, F>(
+    req: http::Request<B>,
+    service: VerificationService,
+    handler: F,
+) -> BoxFuture<http::Response<BoxBody>, Infallible>
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+    M1: prost::Message + Default + Send + 'static,
+    M2: prost::Message + Send + 'static,
+    F: Fn(&VerificationService, M1) -> Result<M2, Status> + Clone + Send + 'static,
+{
+    Box::pin(async move {
+        let mut grpc = Grpc::new(ProstCodec::<M2, M1>::default());
+        Ok(grpc.unary(Unary(service, handler), req).await)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use tonic::codegen::Service;
+    use tonic::server::NamedService;
+    use tonic::{Code, Request};
+
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher, Sha3_256Hasher};
+    use crate::keys::Key;
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::security::{self, VerifyPolicy};
+    use crate::server::{
+        BatchVerifyRequest, Failure, InspectRequest, Unary, VerificationService, VerifyRequest,
+        DEFAULT_MAX_BATCH,
+    };
+
+    #[test]
+    fn verification_service() {
+        let key = Key::from_seed(
+            security::level_0_params::<Blake2bHasher, Sha3_224Hasher>(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let context_key = Key::from_seed(
+            security::level_0_params::<Blake2bHasher, Sha3_224Hasher>(),
+            [3u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let consensus = Key::from_seed(
+            security::consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let request = VerifyRequest {
+            message: b"msg".to_vec(),
+            signature: key.sign(b"msg").unwrap(),
+            public_key: key.public_key.to_vec(),
+            context: None,
+        };
+        let in_context = VerifyRequest {
+            message: b"msg".to_vec(),
+            signature: context_key.sign_with_context(b"msg", b"ctx").unwrap(),
+            public_key: context_key.public_key.to_vec(),
+            context: Some(b"ctx".to_vec()),
+        };
+        let tampered = VerifyRequest {
+            message: b"msh".to_vec(),
+            ..request.clone()
+        };
+        let consensus_request = VerifyRequest {
+            message: b"msg".to_vec(),
+            signature: consensus.sign(b"msg").unwrap(),
+            public_key: consensus.public_key.to_vec(),
+            context: None,
+        };
+
+        let service = VerificationService::new(VerifyPolicy::no_consensus()).with_max_batch(4);
+        assert!(service.verify(&request).valid);
+        assert!(service.verify(&in_context).valid);
+        let invalid = service.verify(&tampered);
+        assert!(!invalid.valid);
+        assert_eq!(invalid.error_code, WotsError::InvalidSignature.code());
+        assert_eq!(invalid.failure(), Failure::Invalid);
+        let rejected = service.verify(&consensus_request);
+        assert_eq!(
+            rejected.error_code,
+            WotsError::InvalidParamsEncodingType.code()
+        );
+        assert_eq!(rejected.failure(), Failure::Malformed);
+
+        let batch = BatchVerifyRequest {
+            items: vec![request.clone(), tampered, in_context, consensus_request],
+        };
+        let results = service.batch_verify(&batch).unwrap().results;
+        let valid: Vec<_> = results.iter().map(|result| result.valid).collect();
+        assert_eq!(valid, [true, false, true, false]);
+        let too_many = BatchVerifyRequest {
+            items: vec![request.clone(); 5],
+        };
+        assert_eq!(
+            service.batch_verify(&too_many).unwrap_err().code(),
+            Code::InvalidArgument
+        );
+
+        let info = service
+            .inspect_signature(&InspectRequest {
+                signature: request.signature.clone(),
+                message: Some(b"msg".to_vec()),
+            })
+            .unwrap();
+        assert_eq!(info.encoding, 0);
+        assert_eq!(info.points.len(), info.total as usize);
+        assert_eq!(info.public_key, Some(key.public_key.to_vec()));
+        let malformed = InspectRequest {
+            signature: request.signature[..10].to_vec(),
+            message: None,
+        };
+        assert_eq!(
+            service.inspect_signature(&malformed).unwrap_err().code(),
+            Code::InvalidArgument
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn handlers_run_off_the_runtime() {
+        let key = Key::from_seed(
+            security::level_0_params::<Blake2bHasher, Sha3_224Hasher>(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let request = VerifyRequest {
+            message: b"msg".to_vec(),
+            signature: key.sign(b"msg").unwrap(),
+            public_key: key.public_key.to_vec(),
+            context: None,
+        };
+        let service = VerificationService::new(VerifyPolicy::default());
+        assert_eq!(service.max_batch, DEFAULT_MAX_BATCH);
+
+        // a single-threaded runtime still answers while the handler runs on the blocking pool
+        let mut verify = Unary(service.clone(), |s: &VerificationService, r| {
+            Ok(s.verify(&r))
+        });
+        let response = verify.call(Request::new(request.clone())).await.unwrap();
+        assert!(response.into_inner().valid);
+
+        let mut batch = Unary(service, |s: &VerificationService, r| s.batch_verify(&r));
+        let too_many = BatchVerifyRequest {
+            items: vec![request; DEFAULT_MAX_BATCH + 1],
+        };
+        let status = batch.call(Request::new(too_many)).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+
+    // Decl is a message, enum or service of the proto, as "message VerifyRequest", with its
+    // fields, values or rpcs normalized to "optional bytes context = 4", "FAILURE_INVALID = 2"
+    // and "rpc Verify(VerifyRequest) returns (VerifyResponse)".
+    type Decls = BTreeMap<String, Vec<String>>;
+
+    // proto_decls returns the package and the declarations of a proto file.
+    fn proto_decls(proto: &str) -> (String, Decls) {
+        let (mut package, mut decls, mut open) = (String::new(), Decls::new(), None);
+        for line in proto.lines() {
+            let line = line
+                .split("//")
+                .next()
+                .unwrap()
+                .trim()
+                .trim_end_matches(';');
+            if let Some(name) = line.strip_prefix("package ") {
+                package = name.to_string();
+            } else if let Some(decl) = line.strip_suffix(" {") {
+                open = Some(decl.to_string());
+                decls.insert(decl.to_string(), Vec::new());
+            } else if line == "}" {
+                open = None;
+            } else if let (Some(decl), false) = (&open, line.is_empty()) {
+                decls.get_mut(decl).unwrap().push(line.to_string());
+            }
+        }
+        (package, decls)
+    }
+
+    // upper_snake turns `InspectSignature` into `INSPECT_SIGNATURE`.
+    fn upper_snake(name: &str) -> String {
+        let mut out = String::new();
+        for (i, c) in name.chars().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_uppercase());
+        }
+        out
+    }
+
+    // rust_decls returns the messages and enums defined by the `#[prost]` attributes of
+    // `source`, in the form of `proto_decls`.
+    fn rust_decls(source: &str) -> Decls {
+        let (mut decls, mut open, mut attr) = (Decls::new(), None::<String>, None::<String>);
+        for line in source.lines().map(str::trim) {
+            if let Some(name) = line
+                .strip_prefix("pub struct ")
+                .and_then(|l| l.strip_suffix(" {"))
+            {
+                open = Some(format!("message {name}"));
+            } else if let Some(name) = line
+                .strip_prefix("pub enum ")
+                .and_then(|l| l.strip_suffix(" {"))
+            {
+                open = Some(format!("enum {name}"));
+            } else if line == "}" {
+                open = None;
+            } else if let Some(prost) = line
+                .strip_prefix("#[prost(")
+                .and_then(|l| l.strip_suffix(")]"))
+            {
+                attr = Some(prost.to_string());
+            } else if let Some(decl) = &open {
+                let field = if let Some(enum_name) = decl.strip_prefix("enum ") {
+                    let (variant, value) = line.trim_end_matches(',').split_once(" = ").unwrap();
+                    format!(
+                        "{}_{} = {value}",
+                        upper_snake(enum_name),
+                        upper_snake(variant)
+                    )
+                } else if let Some(prost) = attr.take() {
+                    let (name, ty) = line.strip_prefix("pub ").unwrap().split_once(": ").unwrap();
+                    let parts: Vec<&str> = prost.split(", ").collect();
+                    let kind = match parts[0].split_once(" = ") {
+                        Some(("bytes", _)) => "bytes".to_string(),
+                        Some((_, name)) => name.trim_matches('"').to_string(),
+                        None if parts[0] == "message" => ty
+                            .trim_start_matches("Vec<")
+                            .trim_end_matches(">,")
+                            .to_string(),
+                        None => parts[0].to_string(),
+                    };
+                    let label = ["optional ", "repeated "]
+                        .into_iter()
+                        .find(|label| parts.contains(&label.trim()))
+                        .unwrap_or("");
+                    let tag = parts
+                        .last()
+                        .unwrap()
+                        .trim_start_matches("tag = ")
+                        .trim_matches('"');
+                    format!("{label}{kind} {name} = {tag}")
+                } else {
+                    continue;
+                };
+                decls.entry(decl.clone()).or_default().push(field);
+            }
+        }
+        decls
+    }
+
+    #[test]
+    fn matches_proto() {
+        let (package, mut proto) = proto_decls(include_str!("../proto/wots.proto"));
+        let source = include_str!("server.rs")
+            .split("#[cfg(test)]")
+            .next()
+            .unwrap();
+
+        let service = format!(
+            "service {}",
+            VerificationService::NAME.rsplit('.').next().unwrap()
+        );
+        let rpcs = proto
+            .remove(&service)
+            .expect("service missing from the proto");
+        assert_eq!(
+            VerificationService::NAME,
+            format!("{package}.{}", &service[8..])
+        );
+        assert_eq!(
+            source
+                .matches(&format!("\"/{}/", VerificationService::NAME))
+                .count(),
+            rpcs.len()
+        );
+        for rpc in rpcs {
+            let (name, types) = rpc.strip_prefix("rpc ").unwrap().split_once('(').unwrap();
+            let (request, response) = types
+                .trim_end_matches(')')
+                .split_once(") returns (")
+                .unwrap();
+            let route = format!("\"/{}/{name}\" =>", VerificationService::NAME);
+            assert!(source.contains(&route), "{route} isn't routed");
+            let handler = format!("pub fn {}(", upper_snake(name).to_lowercase());
+            let signature = source.split(&handler).nth(1).expect(&handler);
+            // the handler takes the request type by reference and returns the response type
+            let signature = signature.split(" {").next().unwrap();
+            let types: Vec<&str> = signature
+                .split(|c: char| !c.is_alphanumeric() && c != '&')
+                .collect();
+            assert!(
+                types.contains(&&*format!("&{request}")),
+                "{rpc}: {signature}"
+            );
+            assert!(types.contains(&response), "{rpc}: {signature}");
+        }
+
+        assert_eq!(rust_decls(source), proto);
+    }
+}
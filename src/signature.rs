@@ -0,0 +1,545 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::hasher::Hasher;
+use crate::keys::{short_hex, PublicKey};
+use crate::params::{Params, WotsError, SEED_SIZE};
+use crate::security::{self, ParamsEncoding};
+use crate::wire::{SIGNATURE_HEADER_VERSION, SIGNATURE_POINTS_OFFSET, SIGNATURE_P_SEED_OFFSET};
+
+/// Header versions this crate reads and writes, oldest first
+pub const SUPPORTED_HEADER_VERSIONS: &[u8] = &[SIGNATURE_HEADER_VERSION];
+
+// version nibble values; 0 is the current format, the others are reserved for extensions
+const MAX_HEADER_VERSION: u8 = 0x0f;
+
+/// A W-OTS+ signature: encoding byte || `p_seed` || ladder points.
+///
+/// Constructing one checks the structure of the bytes, so a `Signature` always has a known
+/// encoding, the size that encoding demands and a non-degenerate `p_seed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    // parsed from the header byte on construction
+    encoding: ParamsEncoding,
+    bytes: Vec<u8>,
+}
+
+impl Signature {
+    pub fn encoding(&self) -> ParamsEncoding {
+        self.encoding
+    }
+
+    pub fn p_seed(&self) -> &[u8] {
+        &self.bytes[SIGNATURE_P_SEED_OFFSET..SIGNATURE_POINTS_OFFSET]
+    }
+
+    /// Ladder points, `n` bytes per ladder.
+    pub fn points(&self) -> &[u8] {
+        &self.bytes[SIGNATURE_POINTS_OFFSET..]
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Checks the signature can be verified with `params`, without doing any hashing.
+    pub fn validate<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        &self,
+        params: &Params<PRFH, MSGH>,
+    ) -> Result<(), WotsError> {
+        validate_bytes(&self.bytes, params)
+    }
+
+    /// Recomputes the public key this signature decodes to for `msg`; see
+    /// `security::recover_public_key`.
+    pub fn recover_public_key(&self, msg: impl AsRef<[u8]>) -> Result<PublicKey, WotsError> {
+        security::recover_public_key(msg, &self.bytes)
+    }
+
+    /// Strict form of `validate`: also rejects a header that isn't the canonical encoding of
+    /// `params` (see `Params::canonical_encoding`), so that a `(msg, key)` pair has a single
+    /// accepted byte representation. Size and `p_seed` are already checked on construction,
+    /// and the points admit no alternative form short of a hash collision.
+    pub fn malleability_check<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+        &self,
+        params: &Params<PRFH, MSGH>,
+    ) -> Result<(), WotsError> {
+        check_canonical(&self.bytes, params)
+    }
+}
+
+impl TryFrom<&[u8]> for Signature {
+    type Error = WotsError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Signature::try_from(bytes.to_vec())
+    }
+}
+
+impl TryFrom<Vec<u8>> for Signature {
+    type Error = WotsError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        let encoding = match bytes.first() {
+            Some(&byte) => ParamsEncoding::try_from(byte)?,
+            None => return Err(WotsError::InvalidSignatureSize),
+        };
+        match encoding.signature_size() {
+            None => return Err(WotsError::InvalidParamsEncodingType),
+            Some(size) if size != bytes.len() => return Err(WotsError::InvalidSignatureSize),
+            Some(_) => {}
+        }
+        check_p_seed(&bytes[SIGNATURE_P_SEED_OFFSET..SIGNATURE_POINTS_OFFSET])?;
+        Ok(Signature { encoding, bytes })
+    }
+}
+
+impl AsRef<[u8]> for Signature {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl From<Signature> for Vec<u8> {
+    fn from(signature: Signature) -> Self {
+        signature.bytes
+    }
+}
+
+/// One-line summary, e.g. `sig level3 1085B p_seed=ab12…ef90`.
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sig {} {}B p_seed=", self.encoding(), self.bytes.len())?;
+        short_hex(f, self.p_seed())
+    }
+}
+
+/// A signature split into its fields, for protocols that transport `p_seed`, which is the
+/// same for every signature of a key, separately.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureParts {
+    pub encoding: ParamsEncoding,
+    pub p_seed: [u8; SEED_SIZE],
+    /// ladder points, `n` bytes per ladder
+    pub points: Vec<u8>,
+}
+
+impl SignatureParts {
+    /// Splits a full signature, checking its structure like `Signature::try_from`.
+    pub fn split(signature: &[u8]) -> Result<Self, WotsError> {
+        Ok(SignatureParts::from(&Signature::try_from(signature)?))
+    }
+
+    /// Joins the parts into a full signature. The result is not checked; verifying it
+    /// rejects points of the wrong size.
+    pub fn assemble(&self) -> Vec<u8> {
+        let mut signature = Vec::with_capacity(SIGNATURE_POINTS_OFFSET + self.points.len());
+        signature.push(u8::from(&self.encoding));
+        signature.extend_from_slice(&self.p_seed);
+        signature.extend_from_slice(&self.points);
+        signature
+    }
+}
+
+impl From<&Signature> for SignatureParts {
+    fn from(signature: &Signature) -> Self {
+        let mut p_seed = [0u8; SEED_SIZE];
+        p_seed.copy_from_slice(signature.p_seed());
+        SignatureParts {
+            encoding: signature.encoding(),
+            p_seed,
+            points: signature.points().to_vec(),
+        }
+    }
+}
+
+/// The first byte of a signature: header version in the high nibble, encoding in the low one.
+/// Every signature of version 0, the current format, has a header byte equal to its encoding
+/// byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureHeader {
+    pub version: u8,
+    pub encoding: ParamsEncoding,
+}
+
+impl SignatureHeader {
+    /// Fails with `WotsError::UnknownEncoding` if the low nibble is no encoding. Any version
+    /// is accepted; `normalize_header` decides what to do with unknown ones.
+    pub fn from_byte(byte: u8) -> Result<Self, WotsError> {
+        let encoding =
+            ParamsEncoding::try_from(byte & 0x0f).map_err(|_| WotsError::UnknownEncoding(byte))?;
+        Ok(SignatureHeader {
+            version: byte >> 4,
+            encoding,
+        })
+    }
+
+    pub fn to_byte(&self) -> u8 {
+        self.version << 4 | u8::from(&self.encoding)
+    }
+}
+
+/// How to treat signatures whose header version is newer than `SIGNATURE_HEADER_VERSION`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VersionPolicy {
+    /// fail with `WotsError::UnsupportedSignatureVersion`
+    #[default]
+    Reject,
+    /// skip the extension block and verify the rest as a current signature, ignoring what
+    /// the extensions would have added
+    BestEffort,
+}
+
+/// Rewrites `signature` into the current format under `policy`, borrowing it when it already
+/// is. Signatures of a newer header version lose their extension block with
+/// `VersionPolicy::BestEffort` and are rejected otherwise.
+pub fn normalize_header(
+    signature: &[u8],
+    policy: VersionPolicy,
+) -> Result<Cow<'_, [u8]>, WotsError> {
+    let byte = *signature.first().ok_or(WotsError::InvalidSignatureSize)?;
+    let header = SignatureHeader::from_byte(byte)?;
+    if header.version == SIGNATURE_HEADER_VERSION {
+        return Ok(Cow::Borrowed(signature));
+    }
+    if policy == VersionPolicy::Reject {
+        return Err(WotsError::UnsupportedSignatureVersion(header.version));
+    }
+    let ext_len = match signature.get(1..3) {
+        Some(len) => u16::from_be_bytes([len[0], len[1]]) as usize,
+        None => return Err(WotsError::InvalidSignatureSize),
+    };
+    let rest = signature
+        .get(3 + ext_len..)
+        .ok_or(WotsError::InvalidSignatureSize)?;
+    let mut normalized = Vec::with_capacity(1 + rest.len());
+    normalized.push(u8::from(&header.encoding));
+    normalized.extend_from_slice(rest);
+    Ok(Cow::Owned(normalized))
+}
+
+/// Frames a current `signature` as header `version` (1 to 15) carrying `extensions`, for
+/// trying out format extensions before a verifier understands them.
+pub fn extend_header(
+    signature: &[u8],
+    version: u8,
+    extensions: &[u8],
+) -> Result<Vec<u8>, WotsError> {
+    if !(1..=MAX_HEADER_VERSION).contains(&version) {
+        return Err(WotsError::UnsupportedSignatureVersion(version));
+    }
+    let header =
+        SignatureHeader::from_byte(*signature.first().ok_or(WotsError::InvalidSignatureSize)?)?;
+    if header.version != SIGNATURE_HEADER_VERSION {
+        return Err(WotsError::UnsupportedSignatureVersion(header.version));
+    }
+    let ext_len = u16::try_from(extensions.len()).map_err(|_| WotsError::InvalidSignatureSize)?;
+    let mut framed = Vec::with_capacity(3 + extensions.len() + signature.len() - 1);
+    framed.push(SignatureHeader { version, ..header }.to_byte());
+    framed.extend_from_slice(&ext_len.to_be_bytes());
+    framed.extend_from_slice(extensions);
+    framed.extend_from_slice(&signature[1..]);
+    Ok(framed)
+}
+
+/// Highest header version both this crate and a peer supporting `peer_versions` understand,
+/// to agree on the format before exchanging signatures; `None` if they share none.
+pub fn negotiate_version(peer_versions: &[u8]) -> Option<u8> {
+    SUPPORTED_HEADER_VERSIONS
+        .iter()
+        .rev()
+        .find(|version| peer_versions.contains(version))
+        .copied()
+}
+
+// validate_bytes checks the length, encoding byte and `p_seed` of a full signature against
+// `params`.
+pub(crate) fn validate_bytes<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    signature: &[u8],
+    params: &Params<PRFH, MSGH>,
+) -> Result<(), WotsError> {
+    if signature.len() != params.signature_size() {
+        return Err(WotsError::InvalidSignatureSize);
+    }
+    if signature[0] != u8::from(params.get_encoding()) {
+        return Err(WotsError::EncodingMismatch);
+    }
+    check_p_seed(&signature[SIGNATURE_P_SEED_OFFSET..SIGNATURE_POINTS_OFFSET])
+}
+
+// check_canonical is `validate_bytes`, also requiring the canonical header of `params`.
+pub(crate) fn check_canonical<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    signature: &[u8],
+    params: &Params<PRFH, MSGH>,
+) -> Result<(), WotsError> {
+    validate_bytes(signature, params)?;
+    if params.canonical_encoding() != *params.get_encoding() {
+        return Err(WotsError::NonCanonicalSignature);
+    }
+    Ok(())
+}
+
+// check_p_seed rejects an all-zero `p_seed`, the mark of an unset buffer.
+pub(crate) fn check_p_seed(p_seed: &[u8]) -> Result<(), WotsError> {
+    if p_seed.iter().all(|&b| b == 0) {
+        return Err(WotsError::DegenerateSeed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher, Sha3_256Hasher};
+    use crate::keys::Key;
+    use crate::params::{ChecksumOrder, EncodingProfile, Params, WotsError, SEED_SIZE};
+    use crate::security::{self, ParamsEncoding};
+    use crate::signature::{
+        extend_header, negotiate_version, normalize_header, Signature, SignatureHeader,
+        SignatureParts, VersionPolicy,
+    };
+    use std::convert::TryFrom;
+
+    #[test]
+    fn header_versions() {
+        let key = Key::from_seed(
+            security::level_1_params::<Blake2bHasher, Sha3_224Hasher>(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let signature = key.sign(b"hello").unwrap();
+        let header = SignatureHeader::from_byte(signature[0]).unwrap();
+        assert_eq!(header.version, 0);
+        assert_eq!(header.encoding, ParamsEncoding::Level1);
+        assert_eq!(header.to_byte(), signature[0]);
+        assert_eq!(
+            SignatureHeader::from_byte(0x17),
+            Err(WotsError::UnknownEncoding(0x17))
+        );
+
+        let extended = extend_header(&signature, 2, b"future field").unwrap();
+        assert_eq!(extended[0], 0x21);
+        for policy in [VersionPolicy::Reject, VersionPolicy::BestEffort] {
+            assert_eq!(
+                normalize_header(&signature, policy).unwrap(),
+                &signature[..]
+            );
+        }
+        assert_eq!(
+            normalize_header(&extended, VersionPolicy::Reject),
+            Err(WotsError::UnsupportedSignatureVersion(2))
+        );
+        assert_eq!(
+            normalize_header(&extended, VersionPolicy::BestEffort).unwrap(),
+            &signature[..]
+        );
+        assert_eq!(
+            normalize_header(&extended[..14], VersionPolicy::BestEffort),
+            Err(WotsError::InvalidSignatureSize)
+        );
+
+        let strict = security::Verifier::new(security::VerifyPolicy::all());
+        assert_eq!(
+            strict.verify(b"hello", &extended, &key.public_key),
+            Err(WotsError::UnsupportedSignatureVersion(2))
+        );
+        assert_eq!(
+            security::verify(b"hello", &extended, &key.public_key),
+            Err(WotsError::UnsupportedSignatureVersion(2))
+        );
+        let lenient = strict.with_version_policy(VersionPolicy::BestEffort);
+        lenient
+            .verify(b"hello", &extended, &key.public_key)
+            .unwrap();
+
+        assert_eq!(
+            extend_header(&signature, 16, b""),
+            Err(WotsError::UnsupportedSignatureVersion(16))
+        );
+        assert_eq!(
+            extend_header(&extended, 3, b""),
+            Err(WotsError::UnsupportedSignatureVersion(2))
+        );
+        assert_eq!(negotiate_version(&[2, 0, 1]), Some(0));
+        assert_eq!(negotiate_version(&[1]), None);
+    }
+
+    #[test]
+    fn recover_public_key() {
+        let key = Key::from_seed(
+            security::level_1_params::<Blake2bHasher, Sha3_224Hasher>(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let consensus = Key::from_seed(
+            security::consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+            [1u8; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap();
+        let signed = [
+            (key.sign(b"hello").unwrap(), key.public_key.clone()),
+            (
+                consensus.sign(b"hello").unwrap(),
+                consensus.public_key.clone(),
+            ),
+        ];
+        for (signature, public_key) in signed {
+            let signature = Signature::try_from(signature).unwrap();
+            let recovered = signature.recover_public_key(b"hello").unwrap();
+            assert_eq!(recovered.as_bytes()[..], public_key[..]);
+            assert_eq!(
+                security::recover_public_key(b"hello", signature.as_bytes()),
+                Ok(recovered)
+            );
+            // any message decodes, to another key
+            assert_ne!(signature.recover_public_key(b"hellp").unwrap(), recovered);
+        }
+        assert_eq!(
+            security::recover_public_key(b"hello", [1u8; 10]),
+            Err(WotsError::InvalidSignatureSize)
+        );
+        assert_eq!(
+            security::recover_public_key(b"hello", []),
+            Err(WotsError::InvalidSignatureSize)
+        );
+    }
+
+    #[test]
+    fn signature_parts() {
+        let params = security::level_1_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let parts = key.sign_parts(b"hello").unwrap();
+        assert_eq!(parts.encoding, ParamsEncoding::Level1);
+        assert_eq!(parts.p_seed, key.p_seed);
+
+        let signature = parts.assemble();
+        assert_eq!(signature, key.sign(b"hello").unwrap());
+        assert_eq!(SignatureParts::split(&signature).unwrap(), parts);
+        security::verify(b"hello", &signature, &key.public_key).unwrap();
+        assert_eq!(
+            SignatureParts::split(&signature[..40]).err(),
+            Some(WotsError::InvalidSignatureSize)
+        );
+    }
+
+    #[test]
+    fn signature_try_from() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params, [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let bytes = key.sign(b"hello").unwrap();
+
+        let signature = Signature::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(signature.encoding(), ParamsEncoding::Level0);
+        assert_eq!(signature.p_seed(), key.p_seed);
+        assert_eq!(signature.points(), &bytes[1 + SEED_SIZE..]);
+        assert_eq!(
+            signature.to_string(),
+            format!("sig level0 {}B p_seed=0202…0202", bytes.len())
+        );
+        let pk = hex::encode(&key.public_key);
+        assert_eq!(
+            key.to_string(),
+            format!("wots:level0 pk={}…{}", &pk[..4], &pk[pk.len() - 4..])
+        );
+        assert_eq!(signature.into_bytes(), bytes);
+
+        let err = |bytes: &[u8]| Signature::try_from(bytes).err();
+        assert_eq!(err(&[]), Some(WotsError::InvalidSignatureSize));
+        assert_eq!(err(&bytes[1..]), Some(WotsError::InvalidSignatureSize));
+
+        let mut mutated = bytes.clone();
+        mutated[0] = 5;
+        assert_eq!(err(&mutated), Some(WotsError::InvalidParamsEncodingType));
+        mutated[0] = 9;
+        assert_eq!(err(&mutated), Some(WotsError::UnknownEncoding(9)));
+
+        let mut mutated = bytes.clone();
+        mutated[1..1 + SEED_SIZE].fill(0);
+        assert_eq!(err(&mutated), Some(WotsError::DegenerateSeed));
+    }
+
+    #[test]
+    fn signature_validate() {
+        let level0 = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let level3 = security::level_3_params::<Blake2bHasher, Sha3_224Hasher>();
+        let consensus = security::consensus_params::<Blake2bHasher, Sha3_256Hasher>();
+        let custom = Params::<Blake2bHasher, Sha3_224Hasher>::new_from_values(32, 24).unwrap();
+        let key = Key::from_seed(level3.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let signature = Signature::try_from(key.sign(b"hello").unwrap()).unwrap();
+
+        signature.validate(&level3).unwrap();
+        assert_eq!(
+            signature.validate(&level0),
+            Err(WotsError::InvalidSignatureSize)
+        );
+        assert_eq!(
+            signature.validate(&consensus),
+            Err(WotsError::InvalidSignatureSize)
+        );
+        // custom params with level3's values expect the same size, but not the same encoding byte
+        assert_eq!(
+            signature.validate(&custom),
+            Err(WotsError::EncodingMismatch)
+        );
+    }
+
+    #[test]
+    fn signature_malleability_check() {
+        let level3 = security::level_3_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(level3.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let bytes = key.sign(b"hello").unwrap();
+        Signature::try_from(bytes.clone())
+            .unwrap()
+            .malleability_check(&level3)
+            .unwrap();
+        level3
+            .verify_strict(b"hello", &bytes, &key.public_key)
+            .unwrap();
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            level3.verify_strict(b"hello", &trailing, &key.public_key),
+            Err(WotsError::InvalidSignatureSize)
+        );
+
+        // the same signature under a custom header is a second encoding of it
+        let custom = Params::<Blake2bHasher, Sha3_224Hasher>::new_from_values(32, 24).unwrap();
+        assert_eq!(custom.canonical_encoding(), ParamsEncoding::Level3);
+        let key = Key::from_seed(custom.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let redundant = key.sign(b"hello").unwrap();
+        assert_eq!(redundant[1..], bytes[1..]);
+        custom
+            .verify(b"hello", &redundant[1..], &key.public_key)
+            .unwrap();
+        assert_eq!(
+            custom.verify_strict(b"hello", &redundant, &key.public_key),
+            Err(WotsError::NonCanonicalSignature)
+        );
+
+        // a predefined header over params that aren't that encoding's, which `with_profile`
+        // never makes on its own
+        let mut little_endian = level3.with_profile(EncodingProfile {
+            checksum_order: ChecksumOrder::LittleEndian,
+            ..EncodingProfile::default()
+        });
+        assert_eq!(little_endian.encoding, ParamsEncoding::Custom);
+        assert_eq!(little_endian.canonical_encoding(), ParamsEncoding::Custom);
+        little_endian.encoding = ParamsEncoding::Level3;
+        let key =
+            Key::from_seed(little_endian.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let bytes = key.sign(b"hello").unwrap();
+        assert_eq!(
+            Signature::try_from(bytes)
+                .unwrap()
+                .malleability_check(&little_endian),
+            Err(WotsError::NonCanonicalSignature)
+        );
+    }
+}
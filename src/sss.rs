@@ -0,0 +1,290 @@
+use core::fmt;
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha3::{Digest, Sha3_256};
+use subtle::ConstantTimeEq;
+
+use crate::entropy::EntropySource;
+use crate::params::{WotsError, MAX_SEED_SIZE, SEED_SIZE};
+use crate::secret::{SecretBytes, SecretSeed};
+use crate::wire::SHARE_HEADER_SIZE;
+pub use crate::wire::SHARE_VERSION;
+
+/// Size of the seed digest every share carries
+pub const SEED_DIGEST_SIZE: usize = 32;
+
+/// Size of the checksum closing an encoded share
+pub const SHARE_CHECKSUM_SIZE: usize = 4;
+
+const SEED_DIGEST_DOMAIN: &[u8] = b"W-OTS+ seed digest";
+const SHARE_CHECKSUM_DOMAIN: &[u8] = b"W-OTS+ seed share";
+
+/// One of the shares `SecretSeed::split` makes of a seed, over GF(2^8) as in most Shamir
+/// implementations.
+///
+/// Every share carries a digest of the seed, so `SecretSeed::combine` detects shares of
+/// different seeds or tampered values, and its encoding ends in a checksum catching
+/// transcription errors share by share. Zeroed on drop.
+#[derive(Clone)]
+pub struct SeedShare {
+    threshold: u8,
+    index: u8,
+    data: SecretBytes,
+    seed_digest: [u8; SEED_DIGEST_SIZE],
+}
+
+impl SeedShare {
+    /// number of shares needed to recover the seed
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// evaluation point of the share, from 1
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Encoded as version || threshold || index || seed length || share || seed digest ||
+    /// checksum.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            SHARE_HEADER_SIZE + self.data.len() + SEED_DIGEST_SIZE + SHARE_CHECKSUM_SIZE,
+        );
+        bytes.extend_from_slice(&[
+            SHARE_VERSION,
+            self.threshold,
+            self.index,
+            self.data.len() as u8,
+        ]);
+        bytes.extend_from_slice(&self.data);
+        bytes.extend_from_slice(&self.seed_digest);
+        let checksum = share_checksum(&bytes);
+        bytes.extend_from_slice(&checksum);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WotsError> {
+        if bytes.len() < SHARE_HEADER_SIZE || bytes[0] != SHARE_VERSION {
+            return Err(WotsError::InvalidShare);
+        }
+        let (threshold, index, seed_size) = (bytes[1], bytes[2], bytes[3] as usize);
+        if threshold == 0
+            || index == 0
+            || !(SEED_SIZE..=MAX_SEED_SIZE).contains(&seed_size)
+            || bytes.len() != SHARE_HEADER_SIZE + seed_size + SEED_DIGEST_SIZE + SHARE_CHECKSUM_SIZE
+        {
+            return Err(WotsError::InvalidShare);
+        }
+        let (body, checksum) = bytes.split_at(bytes.len() - SHARE_CHECKSUM_SIZE);
+        if !bool::from(share_checksum(body).ct_eq(checksum)) {
+            return Err(WotsError::InvalidShare);
+        }
+        let (data, seed_digest) = body[SHARE_HEADER_SIZE..].split_at(seed_size);
+        Ok(SeedShare {
+            threshold,
+            index,
+            data: SecretBytes::from_slice(data),
+            seed_digest: seed_digest.try_into().expect("split at the seed size"),
+        })
+    }
+}
+
+impl fmt::Debug for SeedShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeedShare")
+            .field("threshold", &self.threshold)
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SecretSeed {
+    /// Splits the seed into `n` shares, any `k` of which recover it with `combine`; fewer
+    /// reveal nothing about it. Fails with `WotsError::InvalidSharePolicy` unless
+    /// `1 <= k <= n <= 255`.
+    pub fn split(&self, k: usize, n: usize) -> Result<Vec<SeedShare>, WotsError> {
+        self.split_with_entropy(k, n, &mut EntropySource::new(OsRng))
+    }
+
+    /// Same as `split`, drawing the polynomial coefficients from `entropy`.
+    pub fn split_with_entropy<R: RngCore>(
+        &self,
+        k: usize,
+        n: usize,
+        entropy: &mut EntropySource<R>,
+    ) -> Result<Vec<SeedShare>, WotsError> {
+        if k == 0 || k > n || n > u8::MAX as usize {
+            return Err(WotsError::InvalidSharePolicy);
+        }
+        // coefficients of x^1..x^(k-1), one row per power
+        let mut coefficients = SecretBytes::zeroed((k - 1) * self.len());
+        if !coefficients.is_empty() {
+            entropy.fill(&mut coefficients)?;
+        }
+
+        let seed_digest = seed_digest(self);
+        Ok((1..=n as u8)
+            .map(|x| {
+                let mut data = SecretBytes::from_slice(self);
+                let mut power = 1;
+                for row in coefficients.chunks(self.len()) {
+                    power = gf_mul(power, x);
+                    for (y, c) in data.iter_mut().zip(row) {
+                        *y ^= gf_mul(*c, power);
+                    }
+                }
+                SeedShare {
+                    threshold: k as u8,
+                    index: x,
+                    data,
+                    seed_digest,
+                }
+            })
+            .collect())
+    }
+
+    /// Recovers a seed from at least `threshold` of its shares, using the first `threshold`.
+    /// Fails with `WotsError::NotEnoughShares` for too few shares, and with
+    /// `WotsError::ShareMismatch` for shares of different splits, repeated shares or shares
+    /// that don't reproduce the seed digest.
+    pub fn combine(shares: &[SeedShare]) -> Result<SecretSeed, WotsError> {
+        let first = shares.first().ok_or(WotsError::NotEnoughShares)?;
+        let k = first.threshold as usize;
+        if shares.len() < k {
+            return Err(WotsError::NotEnoughShares);
+        }
+        let shares = &shares[..k];
+        for (i, share) in shares.iter().enumerate() {
+            if share.threshold != first.threshold
+                || share.data.len() != first.data.len()
+                || share.seed_digest != first.seed_digest
+                || shares[..i].iter().any(|other| other.index == share.index)
+            {
+                return Err(WotsError::ShareMismatch);
+            }
+        }
+
+        // Lagrange interpolation at x = 0
+        let mut seed = SecretBytes::zeroed(first.data.len());
+        for share in shares {
+            let mut basis = 1;
+            for other in shares.iter().filter(|other| other.index != share.index) {
+                basis = gf_mul(
+                    basis,
+                    gf_mul(other.index, gf_inv(other.index ^ share.index)),
+                );
+            }
+            for (s, y) in seed.iter_mut().zip(share.data.iter()) {
+                *s ^= gf_mul(*y, basis);
+            }
+        }
+
+        let seed = SecretSeed::from_slice(&seed)?;
+        if !bool::from(seed_digest(&seed).ct_eq(&first.seed_digest)) {
+            return Err(WotsError::ShareMismatch);
+        }
+        Ok(seed)
+    }
+}
+
+// gf_mul multiplies in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1, without secret dependent
+// branches or table lookups.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+// gf_inv computes a^254 = a^-1 for a != 0.
+fn gf_inv(a: u8) -> u8 {
+    let mut result = 1;
+    let mut base = a;
+    let mut exp = 254u8;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+fn seed_digest(seed: &[u8]) -> [u8; SEED_DIGEST_SIZE] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(SEED_DIGEST_DOMAIN);
+    hasher.update(seed);
+    hasher.finalize().into()
+}
+
+fn share_checksum(body: &[u8]) -> [u8; SHARE_CHECKSUM_SIZE] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(SHARE_CHECKSUM_DOMAIN);
+    hasher.update(body);
+    let digest = hasher.finalize();
+    let mut checksum = [0u8; SHARE_CHECKSUM_SIZE];
+    checksum.copy_from_slice(&digest[..SHARE_CHECKSUM_SIZE]);
+    checksum
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::params::{WotsError, MAX_SEED_SIZE};
+    use crate::secret::SecretSeed;
+    use crate::sss::{gf_inv, gf_mul, SeedShare};
+
+    #[test]
+    fn split_and_combine() {
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1);
+        }
+
+        let seed = SecretSeed::from_slice(&[9u8; MAX_SEED_SIZE]).unwrap();
+        let shares = seed.split(3, 5).unwrap();
+        for picked in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let picked: Vec<_> = picked.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(&SecretSeed::combine(&picked).unwrap()[..], &seed[..]);
+        }
+        assert_eq!(
+            SecretSeed::combine(&shares[..2]).err(),
+            Some(WotsError::NotEnoughShares)
+        );
+        let repeated = [shares[0].clone(), shares[1].clone(), shares[0].clone()];
+        assert_eq!(
+            SecretSeed::combine(&repeated).err(),
+            Some(WotsError::ShareMismatch)
+        );
+        let other = SecretSeed::new([1u8; 32]).split(3, 5).unwrap();
+        let mixed = [shares[0].clone(), shares[1].clone(), other[2].clone()];
+        assert_eq!(
+            SecretSeed::combine(&mixed).err(),
+            Some(WotsError::ShareMismatch)
+        );
+        assert_eq!(seed.split(4, 3).err(), Some(WotsError::InvalidSharePolicy));
+    }
+
+    #[test]
+    fn share_encoding() {
+        let shares = SecretSeed::new([5u8; 32]).split(2, 2).unwrap();
+        let mut bytes = shares[1].to_bytes();
+        let decoded = SeedShare::from_bytes(&bytes).unwrap();
+        assert_eq!((decoded.threshold(), decoded.index()), (2, 2));
+        assert_eq!(
+            &SecretSeed::combine(&[shares[0].clone(), decoded]).unwrap()[..],
+            &[5u8; 32]
+        );
+        assert!(!format!("{:?}", shares[0]).contains("data"));
+
+        bytes[10] ^= 1;
+        assert_eq!(
+            SeedShare::from_bytes(&bytes).err(),
+            Some(WotsError::InvalidShare)
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Reproducible fixtures for tests against this crate's types. Nothing here is fit for
+//! production keys: `DevRng` is deterministic by design.
+
+use rand::RngCore;
+use sha3::{Digest, Sha3_256};
+
+use crate::hasher::{Blake2bHasher, Hasher, Sha3_224Hasher, Sha3_256Hasher};
+use crate::keys::Key;
+use crate::params::{Params, SEED_SIZE};
+use crate::security::{self, ParamsEncoding};
+use crate::traits::WotsSigner;
+
+const DEV_RNG_DOMAIN: &[u8] = b"W-OTS+ dev rng";
+
+/// Deterministic RNG: block `i` of the output is `SHA3-256(domain || seed || i)`. Passes the
+/// `EntropySource` health tests, so it can stand in for `OsRng` in `Key::from_entropy`.
+#[derive(Debug, Clone)]
+pub struct DevRng {
+    seed: u64,
+    counter: u64,
+    block: [u8; 32],
+    // bytes of `block` already handed out
+    used: usize,
+}
+
+impl DevRng {
+    pub fn new(seed: u64) -> Self {
+        DevRng {
+            seed,
+            counter: 0,
+            block: [0u8; 32],
+            used: 32,
+        }
+    }
+}
+
+impl RngCore for DevRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for b in dest.iter_mut() {
+            if self.used == self.block.len() {
+                let mut hasher = Sha3_256::new();
+                hasher.update(DEV_RNG_DOMAIN);
+                hasher.update(self.seed.to_be_bytes());
+                hasher.update(self.counter.to_be_bytes());
+                self.block = hasher.finalize().into();
+                self.counter += 1;
+                self.used = 0;
+            }
+            *b = self.block[self.used];
+            self.used += 1;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// The fixed key pair `n` of `params`, with seeds drawn from `DevRng::new(n)`.
+pub fn fixture_key<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    params: Params<PRFH, MSGH>,
+    n: u64,
+) -> Key<PRFH, MSGH> {
+    let mut rng = DevRng::new(n);
+    let mut seed = [0u8; SEED_SIZE];
+    let mut p_seed = [0u8; SEED_SIZE];
+    rng.fill_bytes(&mut seed);
+    rng.fill_bytes(&mut p_seed);
+    Key::from_seed(params, seed, p_seed).expect("fixture params are valid")
+}
+
+/// The fixed key pair `n` of a predefined encoding, with the hashers `security::verify`
+/// dispatches it to. Panics for `Custom`.
+pub fn fixture_signer(encoding: ParamsEncoding, n: u64) -> Box<dyn WotsSigner> {
+    type Level = Params<Blake2bHasher, Sha3_224Hasher>;
+    match encoding {
+        ParamsEncoding::Consensus => Box::new(fixture_key(
+            security::consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+            n,
+        )),
+        ParamsEncoding::Custom => panic!("custom encodings have no fixture signer"),
+        _ => Box::new(fixture_key(
+            Level::new(encoding).expect("predefined encoding"),
+            n,
+        )),
+    }
+}
+
+/// Ways `malform` breaks a signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Malformation {
+    /// flip the top bit of the first byte of ladder `i`
+    FlipLadderBit(usize),
+    /// keep only the first bytes
+    Truncate(usize),
+    TrailingByte,
+    /// replace the encoding byte
+    EncodingByte(u8),
+    /// replace `p_seed`
+    PSeed([u8; SEED_SIZE]),
+}
+
+/// Returns a copy of the full `signature` (encoding byte included) broken as described by
+/// `malformation`. Panics for ladder indexes outside the signature.
+pub fn malform(signature: &[u8], malformation: Malformation) -> Vec<u8> {
+    let mut sig = signature.to_vec();
+    match malformation {
+        Malformation::FlipLadderBit(i) => {
+            let (n, _) = ParamsEncoding::try_from(sig[0])
+                .ok()
+                .and_then(|encoding| encoding.values())
+                .expect("signature of a predefined encoding");
+            sig[1 + SEED_SIZE + i * n] ^= 0x80;
+        }
+        Malformation::Truncate(len) => sig.truncate(len),
+        Malformation::TrailingByte => sig.push(0),
+        Malformation::EncodingByte(byte) => sig[0] = byte,
+        Malformation::PSeed(p_seed) => sig[1..1 + SEED_SIZE].copy_from_slice(&p_seed),
+    }
+    sig
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+
+    use crate::entropy::EntropySource;
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::security::{self, ParamsEncoding};
+    use crate::test_utils::{fixture_signer, malform, DevRng, Malformation};
+
+    #[test]
+    fn fixtures() {
+        let (mut a, mut b) = (DevRng::new(1), DevRng::new(1));
+        assert_eq!(a.next_u64(), b.next_u64());
+        EntropySource::new(DevRng::new(2)).self_test().unwrap();
+
+        for encoding in [ParamsEncoding::Level1, ParamsEncoding::Consensus] {
+            let signer = fixture_signer(encoding, 0);
+            assert_eq!(
+                signer.public_key().unwrap(),
+                fixture_signer(encoding, 0).public_key().unwrap()
+            );
+            let signature = signer.sign(b"msg").unwrap();
+            let pk = signer.public_key().unwrap();
+            security::verify(b"msg", &signature, pk.as_bytes()).unwrap();
+
+            let cases = [
+                (Malformation::FlipLadderBit(3), WotsError::InvalidSignature),
+                (Malformation::Truncate(10), WotsError::InvalidSignatureSize),
+                (Malformation::TrailingByte, WotsError::InvalidSignatureSize),
+                (Malformation::EncodingByte(9), WotsError::UnknownEncoding(9)),
+                (
+                    Malformation::PSeed([3u8; SEED_SIZE]),
+                    WotsError::InvalidSignature,
+                ),
+            ];
+            for (malformation, expected) in cases {
+                let sig = malform(&signature, malformation);
+                assert_eq!(security::verify(b"msg", &sig, pk.as_bytes()), Err(expected));
+            }
+        }
+    }
+}
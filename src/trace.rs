@@ -0,0 +1,229 @@
+use crate::hasher::Hasher;
+use crate::keys::PK_SIZE;
+use crate::params::{parity, Aggregation, Params, StepIndex, WotsError, SEED_SIZE};
+
+/// One hash evaluation of a verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HashCall {
+    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))]
+    pub input: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))]
+    pub output: Vec<u8>,
+}
+
+/// One ladder step: `output = H(p_seed || step || input ^ mask)[..n]`, with `step` hashed as
+/// one byte, or two big-endian bytes when `w` is above `W`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChainStep {
+    pub ladder: usize,
+
+    /// 1-based step number hashed in; `mask` is `random_elements[step - 1]`
+    pub step: u16,
+
+    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))]
+    pub input: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))]
+    pub mask: Vec<u8>,
+
+    /// `input ^ mask`
+    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))]
+    pub xored: Vec<u8>,
+
+    /// full hasher call; its output truncated to `n` bytes is `output`
+    pub hash: HashCall,
+
+    #[cfg_attr(feature = "serde", serde(with = "hex::serde"))]
+    pub output: Vec<u8>,
+}
+
+/// Every hash input/output and XOR performed by a verification, in order, for generating
+/// circuit witnesses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VerifyTrace {
+    pub n: usize,
+    pub m: usize,
+    pub w: usize,
+    pub total: usize,
+
+    /// message hash; its first `m` bytes are split into the message digits
+    pub message_hash: HashCall,
+
+    /// message digits followed by the checksum digits
+    pub digits: Vec<u16>,
+
+    /// `random_elements[j] = H(p_seed || j + 1)[..n]`
+    pub random_elements: Vec<HashCall>,
+
+    pub steps: Vec<ChainStep>,
+
+    /// aggregation hash (SHA3-256 by default) of the ladder tops with odd parity
+    pub tweak: HashCall,
+
+    /// aggregation hash of `p_seed || tweak || ladder tops`, truncated to `PK_SIZE` bytes
+    pub public_key: HashCall,
+
+    /// whether `public_key.output` matched the expected public key
+    pub valid: bool,
+}
+
+// trace_verification walks the verification of `signature` (without encoding byte) step by
+// step, as `Params::decode_into` does, recording every hash call.
+pub(crate) fn trace_verification<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    params: &Params<PRFH, MSGH>,
+    msg: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+) -> Result<VerifyTrace, WotsError> {
+    if public_key.len() != PK_SIZE {
+        return Err(WotsError::InvalidPublicKeySize);
+    }
+    if signature.len() != params.n * params.total + SEED_SIZE {
+        return Err(WotsError::InvalidSignatureSize);
+    }
+    let n = params.n;
+    let (p_seed, points) = signature.split_at(SEED_SIZE);
+
+    let message_hash = hash::<MSGH>(&[msg]);
+    let digits = params.msg_hash_and_compute_checksum(msg);
+
+    let step_index = |j: usize| StepIndex::new(params.w, j + 1);
+    let random_elements: Vec<HashCall> = (0..params.w - 1)
+        .map(|j| hash::<PRFH>(&[p_seed, step_index(j).as_bytes()]))
+        .collect();
+
+    let mut steps = Vec::new();
+    let mut tops = Vec::with_capacity(n * params.total);
+    for (ladder, point) in points.chunks(n).enumerate() {
+        let mut value = point.to_vec();
+        for (j, element) in random_elements
+            .iter()
+            .enumerate()
+            .skip(digits[ladder] as usize)
+        {
+            let mask = element.output[..n].to_vec();
+            let xored: Vec<u8> = value.iter().zip(&mask).map(|(v, r)| v ^ r).collect();
+            let call = hash::<PRFH>(&[p_seed, step_index(j).as_bytes(), &xored]);
+            let output = call.output[..n].to_vec();
+            steps.push(ChainStep {
+                ladder,
+                step: (j + 1) as u16,
+                input: value,
+                mask,
+                xored,
+                hash: call,
+                output: output.clone(),
+            });
+            value = output;
+        }
+        tops.extend_from_slice(&value);
+    }
+
+    let odd: Vec<u8> = tops
+        .chunks(n)
+        .filter(|top| parity(top))
+        .flatten()
+        .copied()
+        .collect();
+    let aggregation = params.aggregation();
+    let tweak = aggregate(aggregation, &[&odd], aggregation.size);
+    let public_key_call = aggregate(aggregation, &[p_seed, &tweak.output, &tops], PK_SIZE);
+    let valid = public_key_call.output == public_key;
+
+    Ok(VerifyTrace {
+        n,
+        m: params.m,
+        w: params.w,
+        total: params.total,
+        message_hash,
+        digits,
+        random_elements,
+        steps,
+        tweak,
+        public_key: public_key_call,
+        valid,
+    })
+}
+
+fn hash<H: Hasher>(parts: &[&[u8]]) -> HashCall {
+    let mut hasher = H::new();
+    for part in parts {
+        hasher.write(part);
+    }
+    let mut output = vec![0u8; H::size()];
+    hasher.sum(&mut output);
+    HashCall {
+        input: parts.concat(),
+        output,
+    }
+}
+
+// aggregate is `hash` with the aggregation hasher, keeping `len` bytes of its output.
+fn aggregate(aggregation: Aggregation, parts: &[&[u8]], len: usize) -> HashCall {
+    let mut output = vec![0u8; len];
+    (aggregation.hash)(parts, &mut output);
+    HashCall {
+        input: parts.concat(),
+        output,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{SEED_SIZE, W};
+    use crate::security;
+
+    #[test]
+    fn verify_traced() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let msg = b"XX NETWORK";
+        let signature = key.sign(msg).unwrap();
+
+        let trace = params
+            .verify_traced(msg, &signature[1..], &key.public_key)
+            .unwrap();
+        assert!(trace.valid);
+        assert_eq!(trace.public_key.output, key.public_key);
+        assert_eq!(trace.random_elements.len(), W - 1);
+        let expected_steps: usize = trace.digits.iter().map(|&d| W - 1 - d as usize).sum();
+        assert_eq!(trace.steps.len(), expected_steps);
+
+        // every step feeds the next one of its ladder
+        for pair in trace.steps.windows(2) {
+            if pair[0].ladder == pair[1].ladder {
+                assert_eq!(pair[0].output, pair[1].input);
+                assert_eq!(pair[0].step + 1, pair[1].step);
+            }
+        }
+
+        let mut mutated = signature.clone();
+        mutated[1 + SEED_SIZE] ^= 1;
+        let trace = params
+            .verify_traced(msg, &mutated[1..], &key.public_key)
+            .unwrap();
+        assert!(!trace.valid);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn verify_trace_json() {
+        let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let signature = key.sign(b"trace").unwrap();
+        let trace = params
+            .verify_traced(b"trace", &signature[1..], &key.public_key)
+            .unwrap();
+
+        let json = serde_json::to_string(&trace).unwrap();
+        assert!(json.contains(&hex::encode(&key.public_key)));
+        assert_eq!(
+            serde_json::from_str::<crate::trace::VerifyTrace>(&json).unwrap(),
+            trace
+        );
+    }
+}
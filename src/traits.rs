@@ -0,0 +1,96 @@
+use crate::hasher::Hasher;
+use crate::keys::{Key, PublicKey};
+use crate::params::{Params, WotsError};
+use crate::security::{self, ParamsEncoding, Verifier};
+
+/// Source of W-OTS+ signatures, whatever holds the key. Object safe, so callers can be
+/// generic over `&dyn WotsSigner` instead of the hashers of a `Key`.
+pub trait WotsSigner {
+    fn public_key(&self) -> Result<PublicKey, WotsError>;
+
+    /// Full signature: encoding byte || `p_seed` || ladder points.
+    fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, WotsError>;
+
+    /// Predefined encoding the signatures carry and `security::verify` checks them under;
+    /// `Custom` when they only verify with the signer's own params, or the signer can't tell.
+    fn canonical_encoding(&self) -> ParamsEncoding {
+        ParamsEncoding::Custom
+    }
+}
+
+/// Checks full signatures (encoding byte included) against public keys. Object safe.
+pub trait WotsVerifier {
+    fn verify(&self, msg: &[u8], signature: &[u8], public_key: &PublicKey)
+        -> Result<(), WotsError>;
+}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> WotsSigner for Key<PRFH, MSGH> {
+    fn public_key(&self) -> Result<PublicKey, WotsError> {
+        self.verifying_key()
+    }
+
+    fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, WotsError> {
+        Key::sign(self, msg)
+    }
+
+    fn canonical_encoding(&self) -> ParamsEncoding {
+        let params = self.params();
+        match params.canonical_encoding() {
+            // the header carries `params.encoding`, which must name the same params
+            encoding if encoding == params.encoding => encoding,
+            _ => ParamsEncoding::Custom,
+        }
+    }
+}
+
+/// Verifies signatures of exactly these params, encoding byte included.
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> WotsVerifier for Params<PRFH, MSGH> {
+    fn verify(
+        &self,
+        msg: &[u8],
+        signature: &[u8],
+        public_key: &PublicKey,
+    ) -> Result<(), WotsError> {
+        security::verify_full(self, msg, signature, public_key.as_bytes())
+    }
+}
+
+impl WotsVerifier for Verifier {
+    fn verify(
+        &self,
+        msg: &[u8],
+        signature: &[u8],
+        public_key: &PublicKey,
+    ) -> Result<(), WotsError> {
+        Verifier::verify(self, msg, signature, public_key.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::security::{self, Verifier, VerifyPolicy};
+    use crate::traits::{WotsSigner, WotsVerifier};
+
+    #[test]
+    fn dyn_signer_verifier() {
+        let params = security::level_1_params::<Blake2bHasher, Sha3_224Hasher>();
+        let key = Key::from_seed(params.clone(), [1u8; SEED_SIZE], [2u8; SEED_SIZE]).unwrap();
+        let signer: &dyn WotsSigner = &key;
+        let signature = signer.sign(b"msg").unwrap();
+
+        let level0 = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+        let policy_verifier = Verifier::new(VerifyPolicy::default());
+        let verifiers: [&dyn WotsVerifier; 3] = [&params, &policy_verifier, &level0];
+        let results: Vec<_> = verifiers
+            .iter()
+            .map(|verifier| verifier.verify(b"msg", &signature, &signer.public_key().unwrap()))
+            .collect();
+        assert_eq!(
+            results,
+            [Ok(()), Ok(()), Err(WotsError::InvalidSignatureSize)]
+        );
+    }
+}
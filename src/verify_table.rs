@@ -0,0 +1,143 @@
+//! Verification against a fixed set of messages, for protocols that only ever sign one of a
+//! few known messages (e.g. yes/no votes).
+
+use sha3::{Digest, Sha3_256};
+use subtle::ConstantTimeEq;
+
+use crate::backend::SoftwareBackend;
+use crate::hasher::Hasher;
+use crate::keys::PK_SIZE;
+use crate::ladders::LadderPoints;
+use crate::params::{ComputeLaddersMode, Params, WotsError, SEED_SIZE};
+use crate::randomness::PublicRandomness;
+
+struct TableEntry {
+    msg: Vec<u8>,
+    digits: Vec<u16>,
+    // digest of the signature accepted for `msg`, if any
+    accepted: Option<[u8; 32]>,
+}
+
+/// Verifies signatures of one public key over a fixed set of messages, built by
+/// `Params::precompute_verify_table`.
+///
+/// The message digits are computed up front. A key has exactly one valid signature per
+/// message, so once one is accepted, later signatures of that message are checked by
+/// comparing their digest with it instead of walking the ladders again.
+pub struct VerifyTable<PRFH: Hasher + Clone, MSGH: Hasher + Clone> {
+    params: Params<PRFH, MSGH>,
+    public_key: [u8; PK_SIZE],
+    entries: Vec<TableEntry>,
+    // randomizers of the key's p_seed, once a signature has been accepted
+    randomness: Option<PublicRandomness>,
+}
+
+impl<PRFH: Hasher + Clone, MSGH: Hasher + Clone> VerifyTable<PRFH, MSGH> {
+    pub(crate) fn new(
+        params: &Params<PRFH, MSGH>,
+        msgs: &[&[u8]],
+        public_key: [u8; PK_SIZE],
+    ) -> Self {
+        let entries = msgs
+            .iter()
+            .map(|&msg| TableEntry {
+                msg: msg.to_vec(),
+                digits: params.msg_hash_and_compute_checksum(msg),
+                accepted: None,
+            })
+            .collect();
+        VerifyTable {
+            params: params.clone(),
+            public_key,
+            entries,
+            randomness: None,
+        }
+    }
+
+    pub fn messages(&self) -> impl Iterator<Item = &[u8]> {
+        self.entries.iter().map(|entry| entry.msg.as_slice())
+    }
+
+    /// Same as `Params::verify` for `msg`, which must be one of the table's messages.
+    pub fn verify(&mut self, msg: &[u8], signature: &[u8]) -> Result<(), WotsError> {
+        let (n, total) = (self.params.n, self.params.total);
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.msg == msg)
+            .ok_or(WotsError::MessageNotInTable)?;
+        if signature.len() != total * n + SEED_SIZE {
+            return Err(WotsError::InvalidSignatureSize);
+        }
+
+        let digest: [u8; 32] = Sha3_256::digest(signature).into();
+        if let Some(accepted) = entry.accepted {
+            return match bool::from(accepted.ct_eq(&digest)) {
+                true => Ok(()),
+                false => Err(WotsError::InvalidSignature),
+            };
+        }
+
+        let p_seed: &[u8; SEED_SIZE] = signature[..SEED_SIZE].try_into().expect("sized above");
+        let randomness = match self.randomness.take() {
+            Some(randomness) if randomness.p_seed() == p_seed => randomness,
+            _ => self.params.public_randomness(p_seed),
+        };
+        let mut pk = [0u8; PK_SIZE];
+        self.params.walk_ladders_from(
+            &SoftwareBackend,
+            &randomness,
+            &entry.digits,
+            LadderPoints::new(&signature[SEED_SIZE..], n, total)?,
+            ComputeLaddersMode::Verify,
+            &mut pk,
+            None,
+        )?;
+        if !bool::from(self.public_key.ct_eq(&pk)) {
+            return Err(WotsError::InvalidSignature);
+        }
+
+        // only the key's own p_seed gets here
+        entry.accepted = Some(digest);
+        self.randomness = Some(randomness);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::keys::Key;
+    use crate::params::{Params, WotsError};
+    use crate::security::ParamsEncoding;
+
+    #[test]
+    fn verify_table() {
+        let params = Params::<Blake2bHasher, Sha3_224Hasher>::new(ParamsEncoding::Level0).unwrap();
+        let key = Key::from_seed(params.clone(), [1u8; 32], [2u8; 32]).unwrap();
+        let yes = key.sign(b"yes").unwrap();
+        let mut table = params
+            .precompute_verify_table(&[b"yes", b"no"], &key.public_key)
+            .unwrap();
+        assert_eq!(table.messages().count(), 2);
+
+        // the first check walks the ladders, the second only compares digests
+        for _ in 0..2 {
+            table.verify(b"yes", &yes[1..]).unwrap();
+        }
+        let mut forged = yes[1..].to_vec();
+        forged[40] ^= 1;
+        assert_eq!(
+            table.verify(b"yes", &forged),
+            Err(WotsError::InvalidSignature)
+        );
+        assert_eq!(
+            table.verify(b"no", &yes[1..]),
+            Err(WotsError::InvalidSignature)
+        );
+        assert_eq!(
+            table.verify(b"maybe", &yes[1..]),
+            Err(WotsError::MessageNotInTable)
+        );
+    }
+}
@@ -0,0 +1,245 @@
+// Every byte layout the crate reads or writes, with its version. Changing a layout means
+// bumping its version here, and the pinned values in the tests below make that a deliberate
+// edit. Multi-byte integers are big-endian throughout. The modules owning each format
+// re-export its version constant.
+
+use crate::keys::PK_SIZE;
+use crate::params::SEED_SIZE;
+
+/// Signature: header byte || `p_seed` || ladder points (`n` bytes per ladder). The low nibble
+/// of the header byte is the encoding byte, selecting the params, and the high nibble the
+/// header version (see `signature::SignatureHeader`). Headers of any version after
+/// `SIGNATURE_HEADER_VERSION` are followed by an extension block, extension length (u16) ||
+/// extensions, before `p_seed`, so verifiers that don't know the version can skip it.
+pub const SIGNATURE_HEADER_VERSION: u8 = 0;
+pub const SIGNATURE_ENCODING_OFFSET: usize = 0;
+pub const SIGNATURE_P_SEED_OFFSET: usize = 1;
+pub const SIGNATURE_POINTS_OFFSET: usize = SIGNATURE_P_SEED_OFFSET + SEED_SIZE;
+
+/// Public bundle (`Key::export_public_bundle`): version || encoding byte || `p_seed` ||
+/// public key || fingerprint.
+pub const BUNDLE_VERSION: u8 = 1;
+pub const FINGERPRINT_SIZE: usize = 32;
+pub const BUNDLE_SIZE: usize = 2 + SEED_SIZE + PK_SIZE + FINGERPRINT_SIZE;
+
+/// Chain cache (`Key::export_chain_cache`): version || public key || n, total, stride (u16
+/// each) || cached rows || one tag per ladder.
+pub const CHAIN_CACHE_VERSION: u8 = 1;
+pub const CHAIN_CACHE_HEADER_SIZE: usize = 1 + PK_SIZE + 6;
+pub const CHAIN_CACHE_TAG_SIZE: usize = 16;
+
+/// Generate checkpoint (`GenerateCheckpoint::to_bytes`): version || public key || n, total,
+/// stride, next ladder (u16 each) || cached rows.
+pub const CHECKPOINT_VERSION: u8 = 1;
+pub const CHECKPOINT_HEADER_SIZE: usize = 1 + PK_SIZE + 8;
+
+/// Signed message envelope (`Key::sign_attached`): version || message length (1 byte) ||
+/// message || signature.
+pub const ENVELOPE_VERSION: u8 = 1;
+pub const ENVELOPE_HEADER_SIZE: usize = 2;
+
+/// Certificate (`Certificate::to_bytes`): version || issuer || subject || signature.
+pub const CERTIFICATE_VERSION: u8 = 1;
+pub const CERTIFICATE_HEADER_SIZE: usize = 1 + 2 * PK_SIZE;
+
+/// Public randomness (`PublicRandomness::to_bytes`): version || n || log2(w) || `p_seed` ||
+/// elements. Version 1 had no `log2(w)` byte.
+pub const RANDOMNESS_VERSION: u8 = 2;
+pub const RANDOMNESS_HEADER_SIZE: usize = 3 + SEED_SIZE;
+
+/// Public key set (`PublicKeySet::to_bytes`): version || number of keys (u32) || keys in
+/// ascending order.
+pub const KEY_SET_VERSION: u8 = 1;
+pub const KEY_SET_HEADER_SIZE: usize = 5;
+
+/// Key directory record: version || encoding byte || profile flags || used flag || seed
+/// length || seed || `p_seed` || public key || metadata.
+pub const RECORD_VERSION: u8 = 1;
+pub const RECORD_HEADER_SIZE: usize = 5;
+
+/// Seed share (`SeedShare::to_bytes`): version || threshold || index || seed length || share
+/// || seed digest || checksum.
+pub const SHARE_VERSION: u8 = 1;
+pub const SHARE_HEADER_SIZE: usize = 4;
+
+/// Sealed seed (`SealedSeed::to_bytes`): version || encoding byte || nonce || ciphertext || tag.
+pub const SEALED_SEED_VERSION: u8 = 1;
+
+/// Couriered envelope (`courier::seal_envelope`): version || ephemeral X25519 public key ||
+/// ciphertext || tag.
+pub const COURIER_VERSION: u8 = 1;
+pub const COURIER_HEADER_SIZE: usize = 1 + 32;
+
+/// Hybrid signature: version || W-OTS+ signature || Ed25519 signature.
+pub const HYBRID_VERSION: u8 = 1;
+
+#[cfg(test)]
+mod tests {
+    use crate::bundle::{verify_bundle, PublicBundle};
+    use crate::certificate::{self, Certificate};
+    use crate::envelope;
+    use crate::hasher::{Blake2bHasher, Sha3_224Hasher};
+    use crate::key_set::PublicKeySet;
+    use crate::keys::{ChainCacheStrategy, GenerateCheckpoint, Key, PublicKey, PK_SIZE};
+    use crate::params::{WotsError, SEED_SIZE};
+    use crate::randomness::PublicRandomness;
+    use crate::security::{self, ParamsEncoding};
+    use crate::signature::{Signature, SignatureParts};
+    use crate::wire::*;
+
+    fn key(seed: u8) -> Key<Blake2bHasher, Sha3_224Hasher> {
+        Key::from_seed(
+            security::level_0_params(),
+            [seed; SEED_SIZE],
+            [2u8; SEED_SIZE],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn pinned_versions() {
+        // bumping a version is a format change: update the layout docs and readers with it
+        assert_eq!(
+            [
+                BUNDLE_VERSION,
+                CHAIN_CACHE_VERSION,
+                CHECKPOINT_VERSION,
+                ENVELOPE_VERSION,
+                CERTIFICATE_VERSION,
+                RANDOMNESS_VERSION,
+                KEY_SET_VERSION,
+                RECORD_VERSION,
+                SHARE_VERSION,
+                SEALED_SEED_VERSION,
+                COURIER_VERSION,
+                HYBRID_VERSION,
+            ],
+            [1, 1, 1, 1, 1, 2, 1, 1, 1, 1, 1, 1]
+        );
+        assert_eq!(SIGNATURE_HEADER_VERSION, 0);
+        assert_eq!(SIGNATURE_POINTS_OFFSET, 33);
+        assert_eq!(BUNDLE_SIZE, 98);
+    }
+
+    #[test]
+    fn signature_round_trip() {
+        let key = key(1);
+        let bytes = key.sign(b"msg").unwrap();
+        let parts = SignatureParts::split(&bytes).unwrap();
+        assert_eq!(
+            bytes[SIGNATURE_ENCODING_OFFSET],
+            u8::from(&ParamsEncoding::Level0)
+        );
+        assert_eq!(
+            bytes[SIGNATURE_P_SEED_OFFSET..SIGNATURE_POINTS_OFFSET],
+            parts.p_seed
+        );
+        assert_eq!(bytes[SIGNATURE_POINTS_OFFSET..], parts.points[..]);
+        assert_eq!(parts.assemble(), bytes);
+        assert_eq!(
+            Signature::try_from(bytes.clone()).unwrap().as_bytes(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn bundle_round_trip() {
+        let key = key(1);
+        let bytes = key.export_public_bundle().unwrap();
+        assert_eq!(bytes.len(), BUNDLE_SIZE);
+        assert_eq!(bytes[0], BUNDLE_VERSION);
+        let bundle = verify_bundle(&bytes, None).unwrap();
+        assert_eq!(
+            bytes[2 + SEED_SIZE..2 + SEED_SIZE + PK_SIZE],
+            key.public_key[..]
+        );
+        assert_eq!(
+            bytes[BUNDLE_SIZE - FINGERPRINT_SIZE..],
+            bundle.fingerprint()
+        );
+        let rebuilt = PublicBundle::new(bundle.encoding, bundle.p_seed, bundle.public_key).unwrap();
+        assert_eq!(rebuilt.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn chain_cache_round_trip() {
+        let mut key = key(1);
+        key.generate_with_strategy(ChainCacheStrategy::Sparse(16))
+            .unwrap();
+        let bytes = key.export_chain_cache().unwrap();
+        assert_eq!(bytes[0], CHAIN_CACHE_VERSION);
+        assert_eq!(bytes[1..1 + PK_SIZE], key.public_key[..]);
+        let header = &bytes[1 + PK_SIZE..CHAIN_CACHE_HEADER_SIZE];
+        let (n, total) = (key.params().n, key.params().total);
+        assert_eq!(header, [[0, n as u8], [0, total as u8], [0, 16]].concat());
+        assert_eq!(
+            (bytes.len() - CHAIN_CACHE_HEADER_SIZE - total * CHAIN_CACHE_TAG_SIZE) % (n * total),
+            0
+        );
+
+        let mut restored = self::key(1);
+        restored.import_chain_cache(&bytes).unwrap();
+        assert_eq!(restored.export_chain_cache().unwrap(), bytes);
+    }
+
+    #[test]
+    fn checkpoint_round_trip() {
+        let mut key = key(1);
+        let checkpoint = key.start_generate(ChainCacheStrategy::Full).unwrap();
+        let checkpoint = key.resume_generate(checkpoint, 3).unwrap().unwrap();
+        let bytes = checkpoint.to_bytes();
+        assert_eq!(bytes[0], CHECKPOINT_VERSION);
+        assert_eq!(bytes[1..1 + PK_SIZE], key.public_key[..]);
+        assert_eq!(
+            bytes[CHECKPOINT_HEADER_SIZE - 2..CHECKPOINT_HEADER_SIZE],
+            [0, 3]
+        );
+        let parsed = GenerateCheckpoint::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn envelope_and_certificate_round_trip() {
+        let signer = key(1);
+        let envelope = signer.sign_attached(b"msg").unwrap();
+        assert_eq!(envelope[..ENVELOPE_HEADER_SIZE], [ENVELOPE_VERSION, 3]);
+        assert_eq!(
+            envelope[ENVELOPE_HEADER_SIZE..ENVELOPE_HEADER_SIZE + 3],
+            *b"msg"
+        );
+        assert_eq!(
+            envelope::open_attached(&envelope, &signer.public_key),
+            Ok(b"msg".to_vec())
+        );
+
+        let subject = PublicKey::try_from(&key(2).public_key[..]).unwrap();
+        let cert = certificate::certify(&subject, &mut key(3)).unwrap();
+        let bytes = cert.to_bytes();
+        assert_eq!(bytes[0], CERTIFICATE_VERSION);
+        assert_eq!(bytes[1..1 + PK_SIZE], cert.issuer.as_bytes()[..]);
+        assert_eq!(
+            bytes[1 + PK_SIZE..CERTIFICATE_HEADER_SIZE],
+            subject.as_bytes()[..]
+        );
+        assert_eq!(bytes[CERTIFICATE_HEADER_SIZE..], *cert.signature.as_bytes());
+        assert_eq!(Certificate::from_bytes(&bytes), Ok(cert));
+    }
+
+    #[test]
+    fn randomness_and_key_set_round_trip() {
+        let randomness = PublicRandomness::new::<Blake2bHasher>(20, &[2u8; SEED_SIZE]);
+        let bytes = randomness.to_bytes();
+        assert_eq!(bytes[..3], [RANDOMNESS_VERSION, 20, 8]);
+        assert_eq!(bytes[3..RANDOMNESS_HEADER_SIZE], [2u8; SEED_SIZE]);
+        assert_eq!(PublicRandomness::from_bytes(&bytes), Ok(randomness));
+
+        let set: PublicKeySet = [PublicKey::from([7u8; PK_SIZE])].into_iter().collect();
+        let bytes = set.to_bytes();
+        assert_eq!(bytes[..KEY_SET_HEADER_SIZE], [KEY_SET_VERSION, 0, 0, 0, 1]);
+        assert_eq!(PublicKeySet::from_bytes(&bytes), Ok(set));
+        assert_eq!(
+            PublicKeySet::from_bytes(&bytes[1..]),
+            Err(WotsError::InvalidKeySet)
+        );
+    }
+}
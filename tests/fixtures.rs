@@ -0,0 +1,38 @@
+//! Checks the shared fixtures through the public API only, as a downstream crate or an older
+//! crate version would use them.
+
+use w_ots::fixtures::{Fixture, FIXTURES, FIXTURE_MSG};
+use w_ots::hasher::{Blake2bHasher, Sha3_224Hasher};
+use w_ots::keys::Key;
+use w_ots::params::Params;
+use w_ots::security::{self, ParamsEncoding};
+
+#[test]
+fn fixtures_match_from_seed() {
+    for fixture in FIXTURES
+        .iter()
+        .filter(|f| f.encoding != ParamsEncoding::Consensus)
+    {
+        let params = Params::<Blake2bHasher, Sha3_224Hasher>::new(fixture.encoding).unwrap();
+        let key = Key::from_seed(params.clone(), fixture.seed, fixture.p_seed).unwrap();
+        assert_eq!(fixture.key(params).unwrap().public_key, key.public_key);
+
+        let signature = fixture.signer().sign(FIXTURE_MSG).unwrap();
+        assert_eq!(signature, key.sign(FIXTURE_MSG).unwrap());
+        security::verify(FIXTURE_MSG, &signature, &key.public_key).unwrap();
+    }
+}
+
+#[test]
+fn fixtures_are_distinct() {
+    for (i, a) in FIXTURES.iter().enumerate() {
+        for b in &FIXTURES[i + 1..] {
+            assert_ne!(a.seed, b.seed);
+            assert_ne!(a.p_seed, b.p_seed);
+        }
+    }
+    let signer = Fixture::of(ParamsEncoding::Consensus).unwrap().signer();
+    let signature = signer.sign(FIXTURE_MSG).unwrap();
+    let public_key = signer.public_key().unwrap();
+    security::verify(FIXTURE_MSG, &signature, public_key.as_bytes()).unwrap();
+}
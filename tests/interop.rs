@@ -0,0 +1,145 @@
+//! Differential tests against the xx-labs Go implementation.
+//!
+//! Run with `cargo test --features interop-tests --test interop`. Needs a Go toolchain, and is
+//! skipped without one. The Go module is pinned by `interop/go/go.mod` and `go.sum`, and the
+//! helper runs with `-mod=readonly`, so Go never resolves another version on its own.
+#![cfg(feature = "interop-tests")]
+
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use w_ots::hasher::{Blake2bHasher, Hasher, Sha3_224Hasher, Sha3_256Hasher};
+use w_ots::keys::Key;
+use w_ots::params::{Params, MAX_MSG_SIZE, SEED_SIZE};
+use w_ots::security::{self, ParamsEncoding};
+
+const ROUNDS: usize = 20;
+
+// GoPeer drives the helper in `interop/go`, one command per line.
+struct GoPeer {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl GoPeer {
+    // spawn starts the helper; `None` when there is no `go` on the PATH to run it with.
+    fn spawn() -> Option<Self> {
+        match Command::new("go").arg("version").output() {
+            Err(err) if err.kind() == ErrorKind::NotFound => return None,
+            res => assert!(res.unwrap().status.success(), "`go version` failed"),
+        }
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("interop/go");
+        assert!(
+            dir.join("go.sum").exists(),
+            "interop/go/go.sum is missing: pin the Go module with `go get \
+             github.com/xx-labs/sleeve@<commit>` in interop/go and commit go.mod and go.sum"
+        );
+        let mut child = Command::new("go")
+            .args(["run", "-mod=readonly", "."])
+            .current_dir(dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to run the go helper");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Some(GoPeer {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    fn call(&mut self, command: &str) -> Vec<Vec<u8>> {
+        writeln!(self.stdin, "{}", command).unwrap();
+        let mut line = String::new();
+        self.stdout.read_line(&mut line).unwrap();
+        assert!(
+            !line.is_empty() && !line.starts_with("error"),
+            "go helper failed on {:?}: {:?}",
+            command,
+            line
+        );
+        line.split_whitespace()
+            .map(|field| hex::decode(field).unwrap())
+            .collect()
+    }
+}
+
+impl Drop for GoPeer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn cross_check<PRFH: Hasher + Clone, MSGH: Hasher + Clone>(
+    go: &mut GoPeer,
+    params: Params<PRFH, MSGH>,
+) {
+    let encoding = u8::from(&params.encoding);
+    for _ in 0..ROUNDS {
+        let mut seed = [0u8; SEED_SIZE];
+        let mut p_seed = [0u8; SEED_SIZE];
+        OsRng.fill_bytes(&mut seed);
+        OsRng.fill_bytes(&mut p_seed);
+        let msg = random_bytes((OsRng.next_u32() as usize) % (MAX_MSG_SIZE + 1));
+        let key = Key::from_seed(params.clone(), seed, p_seed).unwrap();
+        let signature = key.sign(&msg).unwrap();
+
+        // Rust -> Go
+        let decoded = go.call(&format!(
+            "decode {} {}",
+            hex::encode(&msg),
+            hex::encode(&signature)
+        ));
+        assert_eq!(decoded[0], key.public_key, "go decoded a rust signature");
+
+        // Go -> Rust
+        let signed = go.call(&format!(
+            "sign {} {} {} {}",
+            hex::encode([encoding]),
+            hex::encode(seed),
+            hex::encode(p_seed),
+            hex::encode(&msg)
+        ));
+        assert_eq!(
+            signed[0], key.public_key,
+            "go derived a different public key"
+        );
+        assert_eq!(signed[1], signature, "go produced a different signature");
+        security::verify(&msg, &signed[1], &signed[0]).unwrap();
+    }
+}
+
+#[test]
+fn go_interop_test() {
+    let Some(mut go) = GoPeer::spawn() else {
+        eprintln!("skipping go_interop_test: no `go` on the PATH");
+        return;
+    };
+    for encoding in [
+        ParamsEncoding::Level0,
+        ParamsEncoding::Level1,
+        ParamsEncoding::Level2,
+        ParamsEncoding::Level3,
+    ] {
+        cross_check(
+            &mut go,
+            Params::<Blake2bHasher, Sha3_224Hasher>::new(encoding).unwrap(),
+        );
+    }
+    cross_check(
+        &mut go,
+        security::consensus_params::<Blake2bHasher, Sha3_256Hasher>(),
+    );
+}
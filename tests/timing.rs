@@ -0,0 +1,114 @@
+//! dudect-style timing leakage tests: signing and verifying are timed for two classes of
+//! secret inputs, interleaved at random, and Welch's t-test flags a difference between the
+//! class means.
+//!
+//! Run with `cargo test --release --features timing-tests --test timing -- --test-threads=1`. Results on a busy or
+//! frequency-scaling machine are noisy; a failure should be reproduced before being trusted.
+#![cfg(feature = "timing-tests")]
+
+use std::time::Instant;
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use w_ots::hasher::{Blake2bHasher, Sha3_224Hasher};
+use w_ots::keys::Key;
+use w_ots::params::SEED_SIZE;
+use w_ots::security;
+
+const SAMPLES: usize = 4000;
+
+// |t| above which the classes are considered distinguishable, as in dudect
+const T_THRESHOLD: f64 = 10.0;
+
+// samples above this percentile are dropped, as preemption and interrupts only add time
+const CROP_PERCENTILE: f64 = 0.9;
+
+const MSG: &[u8; 10] = b"XX NETWORK";
+
+// welch_t returns Welch's t statistic of two samples, after cropping both at the same
+// duration.
+fn welch_t(a: &[f64], b: &[f64]) -> f64 {
+    let mut all: Vec<f64> = a.iter().chain(b).copied().collect();
+    all.sort_by(f64::total_cmp);
+    let crop = all[(all.len() as f64 * CROP_PERCENTILE) as usize];
+
+    let stats = |xs: &[f64]| {
+        let xs: Vec<f64> = xs.iter().copied().filter(|&x| x <= crop).collect();
+        let n = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / n;
+        let var = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, var, n)
+    };
+    let (mean_a, var_a, n_a) = stats(a);
+    let (mean_b, var_b, n_b) = stats(b);
+    (mean_a - mean_b) / (var_a / n_a + var_b / n_b).sqrt()
+}
+
+// measure runs `op` on inputs of class 0 or 1 in a random order and returns the durations of
+// each class in nanoseconds.
+fn measure(mut op: impl FnMut(usize, usize)) -> (Vec<f64>, Vec<f64>) {
+    let mut classes = [Vec::with_capacity(SAMPLES), Vec::with_capacity(SAMPLES)];
+    for i in 0..2 * SAMPLES {
+        let class = (OsRng.next_u32() & 1) as usize;
+        let start = Instant::now();
+        op(class, i);
+        classes[class].push(start.elapsed().as_nanos() as f64);
+    }
+    let [a, b] = classes;
+    (a, b)
+}
+
+fn random_seed() -> [u8; SEED_SIZE] {
+    let mut seed = [0u8; SEED_SIZE];
+    OsRng.fill_bytes(&mut seed);
+    seed
+}
+
+#[test]
+fn sign_independent_of_key() {
+    let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+    // class 0 always signs with the same key, class 1 with one of many random keys
+    let fixed = Key::from_seed(params.clone(), [0u8; SEED_SIZE], [1u8; SEED_SIZE]).unwrap();
+    let random: Vec<Key<_, _>> = (0..64)
+        .map(|_| Key::from_seed(params.clone(), random_seed(), [1u8; SEED_SIZE]).unwrap())
+        .collect();
+
+    let (a, b) = measure(|class, i| {
+        let key = if class == 0 {
+            &fixed
+        } else {
+            &random[i % random.len()]
+        };
+        std::hint::black_box(key.sign(std::hint::black_box(MSG)).unwrap());
+    });
+    let t = welch_t(&a, &b);
+    assert!(t.abs() < T_THRESHOLD, "sign: t = {:.2}", t);
+}
+
+#[test]
+fn verify_independent_of_signature_points() {
+    let params = security::level_0_params::<Blake2bHasher, Sha3_224Hasher>();
+    let key = Key::from_seed(params.clone(), random_seed(), random_seed()).unwrap();
+    // class 0 verifies the valid signature, class 1 one with random points, which must not
+    // be rejected any faster
+    let valid = key.sign(MSG).unwrap();
+    let invalid: Vec<Vec<u8>> = (0..64)
+        .map(|_| {
+            let mut sig = valid.clone();
+            OsRng.fill_bytes(&mut sig[1 + SEED_SIZE..]);
+            sig
+        })
+        .collect();
+
+    let (a, b) = measure(|class, i| {
+        let sig = if class == 0 {
+            &valid
+        } else {
+            &invalid[i % invalid.len()]
+        };
+        let res = security::verify(MSG, std::hint::black_box(sig), &key.public_key);
+        assert_eq!(res.is_ok(), class == 0);
+    });
+    let t = welch_t(&a, &b);
+    assert!(t.abs() < T_THRESHOLD, "verify: t = {:.2}", t);
+}